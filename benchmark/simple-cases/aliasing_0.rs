@@ -0,0 +1,10 @@
+// Run with `--check aliasing`
+fn main() {
+    let mut x = 0;
+    let p = &mut x as *mut i32;
+    let a = unsafe { &mut *p };
+    let b = unsafe { &mut *p };
+    *b = 1;
+    *a = 2; // aliasing failure
+    rbmc::assert(x == 2);
+}
@@ -0,0 +1,18 @@
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+
+struct Node {
+    next: *mut Node,
+    data: [u8; 4],
+}
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<Node>();
+        let p = alloc_zeroed(layout) as *mut Node;
+        rbmc::assert((*p).next.is_null());
+        rbmc::assert((*p).data[3] == 0);
+        (*p).data[0] = 1;
+        rbmc::assert((*p).data[0] == 0); // assertion failure
+        dealloc(p as *mut u8, layout);
+    }
+}
@@ -0,0 +1,11 @@
+fn main() {
+    let a = [7u8; 16];
+    rbmc::assert(a[10] == 7);
+    let x: i32 = rbmc::nondet();
+    let b = [x; 4];
+    let i: usize = rbmc::nondet();
+    if i < 4 {
+        rbmc::assert(b[i] == x);
+    }
+    rbmc::assert(a[15] == 0); // assertion failure
+}
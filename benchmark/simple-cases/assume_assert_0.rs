@@ -0,0 +1,6 @@
+fn main() {
+    let x = rbmc::nondet::<i32>();
+    rbmc::assume(x > 0 && x < 100);
+    rbmc::assert(x + 1 > 1);
+    rbmc::assert(x < 50); // assertion failure
+}
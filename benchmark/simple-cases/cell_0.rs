@@ -0,0 +1,15 @@
+use std::cell::Cell;
+
+fn bump(c: &Cell<i32>) {
+    c.set(c.get() + 1);
+}
+
+fn main() {
+    let c = Cell::new(1);
+    bump(&c);
+    bump(&c);
+    rbmc::assert(c.get() == 3);
+    let old = c.replace(10);
+    rbmc::assert(old == 3);
+    rbmc::assert(c.into_inner() == 3); // fails
+}
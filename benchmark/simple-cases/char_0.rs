@@ -0,0 +1,11 @@
+fn main() {
+    let c = 'a';
+    rbmc::assert(c as u32 == 97);
+    rbmc::assert(c as u8 + 1 == b'b');
+    let d = 98u8 as char;
+    rbmc::assert(d == 'b' && d > c);
+
+    let x = rbmc::nondet::<u32>();
+    // Surrogates and code points above 0x10FFFF are not chars
+    let _e = unsafe { std::mem::transmute::<u32, char>(x) };
+}
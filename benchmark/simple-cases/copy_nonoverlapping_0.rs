@@ -0,0 +1,17 @@
+use std::ptr;
+
+fn main() {
+    let mut src: [i32; 4] = [1, 2, 3, 4];
+    let mut dst: [i32; 4] = [0; 4];
+    let p = &mut src as *mut [i32; 4] as *mut i32;
+    let q = &mut dst as *mut [i32; 4] as *mut i32;
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n <= 4);
+    unsafe {
+        ptr::copy_nonoverlapping(p, q, 2);
+        rbmc::assert(dst[0] == 1 && dst[1] == 2 && dst[2] == 0);
+        ptr::copy_nonoverlapping(p, q, n);
+        rbmc::assert(n < 3 || dst[2] == 3);
+        ptr::copy_nonoverlapping(p, p.add(1), 2); // overlap
+    }
+}
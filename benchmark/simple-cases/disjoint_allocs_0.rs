@@ -0,0 +1,15 @@
+// Safe by default, since objects are assumed disjoint when their liveness
+// is sliced away. Violated with `--no-disjoint-allocs`: the allocator may
+// reuse the address freed by `dealloc`.
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let layout = Layout::new::<u32>();
+    unsafe {
+        let a = alloc(layout);
+        dealloc(a, layout);
+        let b = alloc(layout);
+        rbmc::assert(a != b);
+        dealloc(b, layout);
+    }
+}
@@ -0,0 +1,9 @@
+fn main() {
+    let x = rbmc::nondet::<i32>();
+    let y = rbmc::nondet::<i32>();
+    if y != 0 && y != -1 {
+        let q = x / y;
+        let r = x % y;
+    }
+    let z = x / y; // division-by-zero, arithmetic-overflow
+}
@@ -0,0 +1,11 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let layout = Layout::new::<i32>();
+    let x = unsafe { alloc(layout) };
+    let y = if rbmc::nondet::<bool>() { x } else { unsafe { alloc(layout) } };
+    unsafe {
+        dealloc(x, layout);
+        dealloc(y, layout); // double-free
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let mut x = Box::new(0);
+    let y = Box::new(1);
+    // `y` is dropped early and never used again
+    drop(y);
+    unsafe {
+        std::ptr::drop_in_place(&mut x);
+    }
+    // double-free of `x` when it goes out of scope
+}
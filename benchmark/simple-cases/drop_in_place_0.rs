@@ -0,0 +1,19 @@
+struct Pair {
+    a: Box<i32>,
+    b: Box<i32>,
+    n: i32,
+}
+
+fn main() {
+    // Both boxes are freed, so there is no leak
+    let p = Pair { a: Box::new(1), b: Box::new(2), n: 3 };
+    rbmc::assert(*p.a + *p.b == p.n);
+    drop(p);
+
+    let mut x = 7;
+    unsafe { std::ptr::drop_in_place(&mut x as *mut i32) };
+
+    let mut q = (Box::new(3), [Box::new(4), Box::new(5)]);
+    unsafe { std::ptr::drop_in_place(&mut q as *mut (Box<i32>, [Box<i32>; 2])) };
+    // `q` is dropped again at the end of scope: double free
+}
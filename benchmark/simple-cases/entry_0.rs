@@ -0,0 +1,17 @@
+// Run with `--entry checks::harness`
+mod checks {
+    pub fn harness() {
+        let v = super::double(21);
+        rbmc::assert(v == 42);
+        let x = rbmc::nondet::<u8>();
+        rbmc::assert(super::double(x as i32) < 510); // fails
+    }
+}
+
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+fn main() {
+    rbmc::assert(double(1) == 2);
+}
@@ -0,0 +1,21 @@
+// Run with `--entry incr` and `--entry read`
+struct Counter {
+    n: u8,
+}
+
+fn incr(c: &mut Counter, k: u8) {
+    if k < 10 && c.n < 200 {
+        c.n += k;
+    }
+    c.n += 100; // overflows
+}
+
+fn read(p: *const i32) -> i32 {
+    unsafe { *p } // null dereference
+}
+
+fn main() {
+    let mut c = Counter { n: 0 };
+    incr(&mut c, 1);
+    rbmc::assert(read(&3) == 3);
+}
@@ -0,0 +1,19 @@
+// Run with `--entry sum` and `--entry first`
+struct Pair<'a> {
+    x: &'a u8,
+    y: Box<u8>,
+}
+
+fn sum(p: Pair) -> u8 {
+    *p.x + *p.y // overflows
+}
+
+fn first(a: (*const u8, u8)) -> u8 {
+    unsafe { *a.0 } // null dereference
+}
+
+fn main() {
+    let x = 1;
+    rbmc::assert(sum(Pair { x: &x, y: Box::new(2) }) == 3);
+    rbmc::assert(first((&x, 0)) == 1);
+}
@@ -0,0 +1,13 @@
+// Safe. A pointer into the data of a variant is placed after the
+// discriminant, in the same way as symex counts the fields of the enum.
+fn main() {
+    let mut x: Option<(u8, i32)> = Some((1, rbmc::nondet::<i32>()));
+    if let Some((_, b)) = &mut x {
+        let p = b as *mut i32;
+        unsafe { *p = 3 };
+    }
+    match x {
+        Some((a, b)) => rbmc::assert(a == 1 && b == 3),
+        None => rbmc::assert(false),
+    }
+}
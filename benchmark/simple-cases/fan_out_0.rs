@@ -0,0 +1,18 @@
+// Many independent checks. Try `--jobs 8`.
+fn step(x: i32, k: i32) -> i32 {
+    if x > k { x / k } else if x < -k { x % k } else { x * 2 }
+}
+
+fn main() {
+    let mut x = rbmc::nondet::<i32>();
+    rbmc::assume(x > -1000 && x < 1000);
+    x = step(x, 1);
+    x = step(x, 2);
+    x = step(x, 3);
+    x = step(x, 4);
+    x = step(x, 5);
+    x = step(x, 6);
+    x = step(x, 7);
+    x = step(x, 8);
+    let _ = 100 / (x - 2); // division-by-zero
+}
@@ -0,0 +1,14 @@
+fn id<T>(x: T) -> T {
+    x
+}
+
+fn main() {
+    let a = id(5i32);
+    rbmc::assert(a == 5);
+    let mut v = 1u8;
+    let p = id(&mut v as *mut u8);
+    unsafe { *p = 2; }
+    rbmc::assert(v == 2);
+    let q = id(std::ptr::null_mut::<u8>());
+    unsafe { *q = 3; } // null-deref
+}
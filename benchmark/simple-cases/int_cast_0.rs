@@ -0,0 +1,13 @@
+fn main() {
+    let x = 300i32;
+    rbmc::assert(x as u8 == 44);
+    let y = -1i8;
+    rbmc::assert(y as u32 == 4294967295);
+    rbmc::assert(y as i64 == -1);
+    let z = 200u8;
+    rbmc::assert(z as i8 == -56);
+    rbmc::assert(-300i32 as u8 == 212);
+    let n = rbmc::nondet::<i16>();
+    rbmc::assert(n as u16 as i16 == n);
+    rbmc::assert(n as u8 as i16 == n); // fails
+}
@@ -0,0 +1,16 @@
+// Safe. No overflow is reported for the methods.
+fn main() {
+    rbmc::assert(u8::MAX.wrapping_add(1) == 0);
+    rbmc::assert(0u8.wrapping_sub(1) == 255);
+    rbmc::assert(i8::MIN.wrapping_sub(1) == i8::MAX);
+    rbmc::assert(100i8.wrapping_mul(2) == -56);
+
+    let x = rbmc::nondet::<u8>();
+    match x.checked_add(200) {
+        Some(y) => rbmc::assert(x <= 55 && y == x + 200),
+        None => rbmc::assert(x > 55),
+    }
+    rbmc::assert(x.saturating_add(200) >= 200);
+    rbmc::assert(x.saturating_sub(200) <= 55);
+    rbmc::assert((-100i8).saturating_mul(2) == i8::MIN);
+}
@@ -0,0 +1,16 @@
+#![allow(internal_features)]
+#![feature(core_intrinsics)]
+
+fn pick(x: u32) -> u32 {
+    if std::intrinsics::likely(x < 10) { x } else { 10 }
+}
+
+fn main() {
+    let x = rbmc::nondet::<u32>();
+    let y = std::hint::black_box(pick(x));
+    rbmc::assert(y <= 10);
+    unsafe { std::hint::assert_unchecked(y < 10) }; // fails
+    if y == 10 {
+        unsafe { std::hint::unreachable_unchecked() };
+    }
+}
@@ -0,0 +1,6 @@
+use std::alloc::{dealloc, Layout};
+
+fn main() {
+    let mut x: i32 = 1;
+    unsafe { dealloc(&mut x as *mut i32 as *mut u8, Layout::new::<i32>()); } // invalid-free
+}
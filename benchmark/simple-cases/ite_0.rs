@@ -0,0 +1,10 @@
+// Safe. The values of `x` and `p` are merged into `ite`s at the join
+// point, over integers and over pointers.
+fn main() {
+    let c = rbmc::nondet::<bool>();
+    let a = 1;
+    let b = 2;
+    let x = if c { a + 1 } else { b + 2 };
+    let p: &i32 = if c { &a } else { &b };
+    rbmc::assert(c && x == 2 && *p == 1 || !c && x == 4 && *p == 2);
+}
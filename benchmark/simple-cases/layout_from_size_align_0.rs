@@ -0,0 +1,19 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n > 0 && n < 8);
+    let layout = Layout::from_size_align(n * 4, 4).unwrap();
+    unsafe {
+        let p = alloc(layout) as *mut u32;
+        *p.add(n - 1) = 1;
+        dealloc(p as *mut u8, layout);
+    }
+
+    // The size is rounded up to the align
+    let odd = Layout::from_size_align(6, 4).unwrap();
+    let _ = odd;
+
+    let bad = Layout::from_size_align(usize::MAX - 2, 4); // size overflows
+    let _ = bad.unwrap(); // unwrap failure
+}
@@ -0,0 +1,11 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    unsafe {
+        let x = alloc(Layout::new::<u16>());
+        // Same size and alignment
+        dealloc(x, Layout::new::<i16>());
+        let y = alloc(Layout::new::<u16>());
+        dealloc(y, Layout::new::<[u8; 3]>()); // layout-mismatch
+    }
+}
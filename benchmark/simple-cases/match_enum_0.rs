@@ -0,0 +1,25 @@
+#[repr(u8)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+fn main() {
+    let c = rbmc::nondet::<bool>();
+    let opt = if c { Some(rbmc::nondet::<i32>()) } else { None };
+    let y = match opt {
+        Some(x) if x > 0 => x,
+        Some(_) => 0,
+        None => -1,
+    };
+    rbmc::assert(c && y >= 0 || !c && y == -1);
+
+    let color = if c { Color::Green } else { Color::Blue };
+    let v = match color {
+        Color::Red => 0,
+        Color::Green => 1,
+        _ => 2,
+    };
+    rbmc::assert(v != 0);
+}
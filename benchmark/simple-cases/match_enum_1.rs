@@ -0,0 +1,27 @@
+// Fail. `SwitchInt` branches on explicit discriminants, which are not the
+// indices of the variants.
+#[derive(Clone, Copy)]
+enum Level {
+    Low = -1,
+    Mid = 10,
+    High,
+}
+
+fn main() {
+    let c = rbmc::nondet::<u8>();
+    let level = if c < 10 {
+        Level::Low
+    } else if c < 100 {
+        Level::Mid
+    } else {
+        Level::High
+    };
+    let v = match level {
+        Level::Low => 0,
+        Level::Mid => 1,
+        Level::High => 2,
+    };
+    rbmc::assert(v == 0 || c >= 10);
+    rbmc::assert(level as i32 == 11 || c < 100);
+    rbmc::assert(v != 2); // fails
+}
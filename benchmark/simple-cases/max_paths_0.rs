@@ -0,0 +1,12 @@
+// Safe. With `--max-paths 2`, the branches of `x == 3` are cut and the
+// result is `unknown (incomplete)`.
+fn main() {
+    let x = rbmc::nondet::<u8>();
+    let mut y = 0;
+    if x < 2 {
+        y += 1;
+    } else if x == 3 {
+        y += 2;
+    }
+    rbmc::assert(y <= 2);
+}
@@ -0,0 +1,10 @@
+use std::mem;
+
+fn main() {
+    let mut a = Box::new(1);
+    let old = mem::replace(&mut a, Box::new(2));
+    let mut n = 5;
+    let m = mem::take(&mut n);
+    rbmc::assert(*old == 1 && *a == 2 && n == 0 && m == 5);
+    mem::forget(a); // memory-leak
+}
@@ -0,0 +1,10 @@
+use std::mem;
+
+fn main() {
+    let mut a = Box::new(1);
+    let mut b = Box::new(2);
+    mem::swap(&mut a, &mut b);
+    rbmc::assert(*a == 2 && *b == 1);
+    drop(a);
+    mem::forget(b); // memory-leak
+}
@@ -0,0 +1,14 @@
+use std::mem;
+
+fn main() {
+    let mut v = Vec::new();
+    v.push(1u8);
+    let w = mem::take(&mut v);
+    let mut o = Some(w.len());
+    let p = mem::take(&mut o);
+    let mut b = Box::new(7);
+    let c = mem::take(&mut b);
+    rbmc::assert(v.len() == 0 && w.len() == 1);
+    rbmc::assert(o.is_none() && p.unwrap() == 1);
+    rbmc::assert(*b == 0 && *c == 7);
+}
@@ -0,0 +1,15 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let mut x = 1;
+    let px = &mut x as *mut i32;
+    let layout = Layout::new::<i32>();
+    let p = unsafe { alloc(layout) as *mut i32 };
+    unsafe {
+        *p = *px;
+        if rbmc::nondet::<bool>() {
+            dealloc(p as *mut u8, layout);
+        }
+    }
+    // memory-leak of `p` only
+}
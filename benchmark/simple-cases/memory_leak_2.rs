@@ -0,0 +1,7 @@
+fn main() {
+    let x = Box::new(0);
+    let y = Box::new(1);
+    drop(x);
+    // memory-leak of `y` only
+    std::mem::forget(y);
+}
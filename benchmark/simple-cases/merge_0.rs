@@ -0,0 +1,19 @@
+// Run with `--merge false`
+// The paths of the diamond are executed one by one. `p` is freed on the
+// first path, which reaches the use-after-free after the join.
+use std::alloc::*;
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<i32>();
+        let p = alloc(layout) as *mut i32;
+        *p = 1;
+        if rbmc::nondet::<bool>() {
+            dealloc(p as *mut u8, layout);
+        } else {
+            *p = 2;
+        }
+        rbmc::assert(*p > 0);
+        dealloc(p as *mut u8, layout);
+    }
+}
@@ -0,0 +1,16 @@
+struct Point {
+    x: i32,
+    y: u8,
+}
+
+fn main() {
+    let x = rbmc::nondet::<i32>();
+    rbmc::assert(x == x);
+
+    let p = rbmc::nondet::<Point>();
+    rbmc::assert(p.x == p.x && p.y as i32 <= 255);
+
+    let a = rbmc::nondet::<[u8; 3]>();
+    let t = rbmc::nondet::<(bool, u16)>();
+    rbmc::assert(a[1] as u32 + t.1 as u32 <= 65790 || t.0);
+}
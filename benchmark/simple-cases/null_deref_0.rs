@@ -0,0 +1,6 @@
+use std::ptr;
+
+fn main() {
+    let p: *mut i32 = ptr::null_mut();
+    unsafe { *p = 1; } // null-pointer-dereference
+}
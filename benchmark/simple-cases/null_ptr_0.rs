@@ -0,0 +1,21 @@
+use std::alloc::{dealloc, Layout};
+use std::ptr;
+
+struct TData {
+    lo: *mut u8,
+    hi: *mut u8,
+}
+
+fn main() {
+    let mut data = TData { lo: ptr::null_mut(), hi: ptr::null_mut() };
+    data.lo = ptr::null_mut();
+    data.hi = ptr::null_mut();
+    let lo = data.lo;
+    let hi = data.hi;
+    let c: *const u16 = ptr::null();
+    if lo == hi && c.is_null() {
+        unsafe {
+            dealloc(lo, Layout::new::<u16>()); // invalid-free
+        }
+    }
+}
@@ -0,0 +1,14 @@
+fn main() {
+    let mut a = Some(Box::new(1));
+    let b = a.take();
+    rbmc::assert(a.is_none() && b.is_some());
+    // The box is owned by `b`
+    drop(a);
+    let x = b.unwrap();
+    rbmc::assert(*x == 1);
+    drop(x);
+    let c = Some(Box::new(2));
+    drop(c);
+    let d: Option<Box<i32>> = None;
+    d.unwrap(); // unwrap failure
+}
@@ -0,0 +1,10 @@
+fn get(s: &[i32], i: usize) -> i32 {
+    s[i] // out-of-bound
+}
+
+fn main() {
+    let a = [1, 2, 3];
+    let i = rbmc::nondet::<usize>();
+    let x = a[i]; // out-of-bound
+    let y = get(&a[1..], i);
+}
@@ -0,0 +1,13 @@
+fn get(s: &[i32], i: usize) -> i32 {
+    s[i] // out-of-bound
+}
+
+fn main() {
+    let arr = [1, 2, 3, 4, 5];
+    let i = rbmc::nondet::<usize>();
+    // The length of the slice is the length of `arr`
+    let x = get(&arr, i);
+    if i < 5 {
+        rbmc::assert(x == arr[i]);
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    let x = rbmc::nondet::<i32>();
+    let y = rbmc::nondet::<u8>();
+    if x < 100 && x > -100 {
+        let z = x * 3 + 1; // ok
+    }
+    let w = y + 1; // arithmetic-overflow
+}
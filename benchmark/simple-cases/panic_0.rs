@@ -0,0 +1,11 @@
+// Fail. `reachable-panic` is reported at `unreachable!` if `x == 7`.
+fn main() {
+    let x = rbmc::nondet::<u8>();
+    rbmc::assume(x <= 10);
+    if x > 10 {
+        panic!("never reached");
+    }
+    if x == 7 {
+        unreachable!();
+    }
+}
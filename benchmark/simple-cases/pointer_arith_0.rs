@@ -0,0 +1,13 @@
+fn main() {
+    let mut a: [u16; 4] = [1, 2, 3, 4];
+    let p = &mut a as *mut [u16; 4] as *mut u16;
+    unsafe {
+        let q = p.add(3);
+        rbmc::assert(*q == 4);
+        let r = q.sub(2);
+        rbmc::assert(*r == 2);
+        let s = r.offset(-1);
+        rbmc::assert(*s == 1);
+        let _t = p.add(usize::MAX); // out-of-bound
+    }
+}
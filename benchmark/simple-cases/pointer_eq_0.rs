@@ -0,0 +1,17 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let lo = unsafe { alloc(Layout::new::<u16>()) };
+    let hi = unsafe { alloc(Layout::new::<[u8; 3]>()) };
+    // Distinct allocations are never equal
+    if lo == hi {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; }
+    }
+    let p = if rbmc::nondet::<bool>() { lo } else { hi };
+    rbmc::assert(p == lo || p == hi);
+    rbmc::assert(p != lo || p != hi);
+    unsafe {
+        dealloc(lo, Layout::new::<u16>());
+        dealloc(p, Layout::new::<[u8; 3]>()); // double-free
+    }
+}
@@ -0,0 +1,13 @@
+fn main() {
+    let a = [1u8, 2, 3];
+    let s = &a[1..] as *const [u8];
+    // The length is dropped by casting to a thin pointer
+    let p = s as *const u8;
+    let q = &a[1] as *const u8;
+    if p != q {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; }
+    }
+    if rbmc::nondet::<bool>() {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; } // null-deref
+    }
+}
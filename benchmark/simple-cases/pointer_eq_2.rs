@@ -0,0 +1,16 @@
+fn main() {
+    let lo: *mut u8 = std::ptr::null_mut();
+    let hi: *mut u8 = std::ptr::null_mut();
+    // Comparisons of constant pointers are folded by symex
+    if lo != hi {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; }
+    }
+    let mut x = 0u8;
+    let p = &mut x as *mut u8;
+    if p == lo || p != p {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; }
+    }
+    if lo == hi {
+        unsafe { *lo = 1; } // null-deref
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let mut a = [1, 2, 3];
+    let i = rbmc::nondet::<usize>();
+    let p = &mut a as *mut [i32; 3] as *mut i32;
+    let y = unsafe { *p.add(i) }; // pointer-out-of-bounds
+}
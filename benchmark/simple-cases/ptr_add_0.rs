@@ -0,0 +1,11 @@
+// Fail. A pointer may be moved one past the end of the array, but not
+// further.
+fn main() {
+    let a: [u16; 3] = [1, 2, 3];
+    let p = &a as *const [u16; 3] as *const u16;
+    let end = unsafe { p.add(3) };
+    rbmc::assert(end != p);
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n <= 4);
+    let _q = unsafe { p.add(n) }; // out of bounds if `n == 4`
+}
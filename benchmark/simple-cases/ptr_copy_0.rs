@@ -0,0 +1,14 @@
+use std::ptr;
+
+fn main() {
+    let mut a: [i32; 5] = [1, 2, 3, 4, 5];
+    let p = &mut a as *mut [i32; 5] as *mut i32;
+    unsafe {
+        // Overlapping forward copy, [1, 1, 2, 3, 5]
+        ptr::copy(p, p.add(1), 3);
+        rbmc::assert(a[0] == 1 && a[1] == 1 && a[2] == 2 && a[3] == 3 && a[4] == 5);
+        // Overlapping backward copy, [1, 2, 3, 5, 5]
+        ptr::copy(p.add(1), p, 4);
+        rbmc::assert(a[0] == 1 && a[1] == 2 && a[2] == 3 && a[3] == 5 && a[4] == 5);
+    }
+}
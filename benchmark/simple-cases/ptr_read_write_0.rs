@@ -0,0 +1,16 @@
+use std::alloc::{alloc, dealloc, Layout};
+use std::ptr;
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<(i32, u8)>();
+        let p = alloc(layout) as *mut (i32, u8);
+        ptr::write(p, (7, 1));
+        let v = ptr::read(p);
+        rbmc::assert(v.0 == 7 && v.1 == 1);
+        p.write((8, 2));
+        rbmc::assert(p.read().0 == 8);
+        dealloc(p as *mut u8, layout);
+        let _w = ptr::read(p); // use-after-free
+    }
+}
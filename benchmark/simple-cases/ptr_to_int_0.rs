@@ -0,0 +1,14 @@
+fn main() {
+    let a: u8 = 1;
+    let b: u32 = 2;
+    let pa = &a as *const u8;
+    let pb = &b as *const u32;
+    // Distinct objects never share an address
+    rbmc::assert(pa as usize != pb as usize);
+    let c: [u16; 2] = [3, 4];
+    let p0 = &c as *const [u16; 2] as *const u16;
+    let p1 = unsafe { p0.add(1) };
+    // Addresses are in bytes
+    rbmc::assert(p0 as usize + 2 == p1 as usize);
+    rbmc::assert((p1 as usize) < (p0 as usize)); // assertion failure
+}
@@ -0,0 +1,15 @@
+use std::rc::Rc;
+
+fn main() {
+    let a = Rc::new(Box::new(1));
+    let b = Rc::clone(&a);
+    rbmc::assert(Rc::strong_count(&a) == 2);
+    drop(a);
+    rbmc::assert(**b == 1);
+    drop(b);
+
+    let c = Rc::new(2);
+    let d = c.clone();
+    drop(c);
+    std::mem::forget(d); // memory-leak of the object of `c`
+}
@@ -0,0 +1,19 @@
+use std::alloc::{alloc, dealloc, realloc, Layout};
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<[i32; 2]>();
+        let p = alloc(layout) as *mut i32;
+        *p = 1;
+        *p.add(1) = 2;
+        let q = realloc(p as *mut u8, layout, 16) as *mut i32;
+        if !q.is_null() {
+            *q.add(3) = 4;
+            rbmc::assert(*q.add(1) == 2);
+            *p = 5; // use-after-free
+            dealloc(q as *mut u8, Layout::new::<[i32; 4]>());
+        } else {
+            dealloc(p as *mut u8, layout);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// safe
+use std::alloc::{alloc, dealloc, realloc, Layout};
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<[u8; 2]>();
+        let p = alloc(layout);
+        *p = 1;
+        let n: usize = rbmc::nondet();
+        rbmc::assume(n == 4);
+        let q = realloc(p, layout, n);
+        if !q.is_null() {
+            *q.add(n - 1) = 2;
+            rbmc::assert(*q == 1);
+            dealloc(q, Layout::new::<[u8; 4]>());
+        } else {
+            dealloc(p, layout);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+fn sum(n: u32) -> u32 {
+    if n == 0 { 0 } else { 1 + sum(n - 1) }
+}
+
+fn main() {
+    let n = rbmc::nondet::<u32>();
+    // With `--recursion 3 --unwinding-assertions`, the recursion is not fully unwound
+    if n < 5 {
+        let s = sum(n);
+    }
+}
@@ -0,0 +1,10 @@
+// Fail. `shift-overflow` is reported for `1u32 << n` if `n == 32`.
+fn main() {
+    let n = rbmc::nondet::<u32>();
+    rbmc::assume(n <= 32);
+    rbmc::assert(1u32.wrapping_shl(32) == 1);
+    rbmc::assert(0x80u8.wrapping_shr(9) == 0x40);
+    rbmc::assert(-8i32 >> 1 == -4 && -7i32 >> 1 == -4);
+    let x = 1u32 << n;
+    rbmc::assert(n == 32 || x != 0);
+}
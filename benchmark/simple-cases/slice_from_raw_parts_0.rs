@@ -0,0 +1,17 @@
+use std::slice;
+
+fn main() {
+    let mut a: [u8; 4] = [1, 2, 3, 4];
+    let p = &mut a as *mut [u8; 4] as *mut u8;
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n >= 1 && n <= 3);
+    unsafe {
+        let s = slice::from_raw_parts(p.add(1), n);
+        rbmc::assert(s[0] == 2);
+        let i = rbmc::nondet::<usize>();
+        if i < n {
+            rbmc::assert(s[i] >= 2);
+        }
+        let _t = slice::from_raw_parts(p.add(2), 3); // out of bounds
+    }
+}
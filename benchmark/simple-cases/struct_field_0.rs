@@ -0,0 +1,20 @@
+struct Pair {
+    a: u8,
+    b: u8,
+}
+
+struct Data {
+    pair: Pair,
+    lo: u16,
+    hi: u16,
+}
+
+fn main() {
+    let mut data = Data { pair: Pair { a: 1, b: 2 }, lo: 3, hi: 4 };
+    let p = &mut data.lo as *mut u16;
+    unsafe { *p = 5; }
+    rbmc::assert(data.pair.b == 2 && data.lo == 5 && data.hi == 4);
+    if rbmc::nondet::<bool>() {
+        unsafe { *std::ptr::null_mut::<i32>() = 0; } // null-deref
+    }
+}
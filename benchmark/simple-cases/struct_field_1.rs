@@ -0,0 +1,19 @@
+// safe
+struct S {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+fn main() {
+    let mut s = S { a: 1, b: 2, c: 3 };
+    let p = &mut s as *mut S as *mut u32;
+    unsafe {
+        rbmc::assert(*p == 2);
+        *p = 5;
+    }
+    rbmc::assert(s.b == 5);
+    let q = &mut s.b as *mut u32;
+    rbmc::assert(q == p);
+    rbmc::assert(s.a == 1 && s.c == 3);
+}
@@ -0,0 +1,16 @@
+fn main() {
+    let x: u32 = rbmc::nondet();
+    let a: [u8; 4] = unsafe { std::mem::transmute(x) };
+    let y: u32 = unsafe { std::mem::transmute(a) };
+    rbmc::assert(x == y);
+    let z: i32 = unsafe { std::mem::transmute(x) };
+    rbmc::assert((z < 0) == (x >= 0x8000_0000));
+    let b: [i8; 4] = unsafe { std::mem::transmute(z) };
+    let w: i32 = unsafe { std::mem::transmute(b) };
+    rbmc::assert(w == z);
+    let mut v = 5u8;
+    let p = &mut v as *mut u8;
+    let q: usize = unsafe { std::mem::transmute(p) };
+    rbmc::assert(q != 0);
+    rbmc::assert(a[0] == 0); // assertion failure
+}
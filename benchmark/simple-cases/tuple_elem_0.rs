@@ -0,0 +1,15 @@
+struct Pair {
+    a: u8,
+    b: u16,
+}
+
+fn main() {
+    let x = rbmc::nondet::<u16>();
+    let mut t = (Pair { a: 1, b: x }, 7i32);
+    let p = &mut t.1 as *mut i32;
+    unsafe { *p += 1; }
+    rbmc::assert(t.1 == 8);
+    rbmc::assert(t.0.b == x && t.0.a == 1);
+    let y = t.0.b + 1; // overflow
+    rbmc::assert(y > x);
+}
@@ -0,0 +1,18 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+struct Pair {
+    lo: i32,
+    hi: i32,
+}
+
+fn main() {
+    unsafe {
+        let layout = Layout::new::<Pair>();
+        let p = alloc(layout) as *mut Pair;
+        (*p).lo = 1;
+        rbmc::assert((*p).lo == 1);
+        let hi = (*p).hi; // uninitialized-read
+        rbmc::assert(hi == hi);
+        dealloc(p as *mut u8, layout);
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    let n = rbmc::nondet::<u32>();
+    let mut i = 0;
+    // With `--unwind 5 --unwinding-assertions`, the loop is not fully unwound
+    while i < n && i < 10 {
+        i += 1;
+    }
+}
@@ -0,0 +1,14 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let layout = Layout::new::<i32>();
+    let x = unsafe { alloc(layout) as *mut i32 };
+    unsafe { dealloc(x as *mut u8, layout); }
+    // The base of `x` may be reused by `y`
+    let y = unsafe { alloc(layout) as *mut i32 };
+    let p = if rbmc::nondet::<bool>() { x } else { y };
+    unsafe {
+        *p = 1; // use-after-free
+        dealloc(y as *mut u8, layout);
+    }
+}
@@ -0,0 +1,20 @@
+// Run with `--check use-after-move`
+// Safe. `d` is moved in each iteration and assigned again before it is
+// read, thus no use-after-move is reported.
+struct Data {
+    p: Box<i32>,
+}
+
+fn consume(d: Data) -> i32 {
+    *d.p
+}
+
+fn main() {
+    let mut d = Data { p: Box::new(0) };
+    let mut i = 0;
+    while i < 2 {
+        rbmc::assert(consume(d) == i);
+        i += 1;
+        d = Data { p: Box::new(i) };
+    }
+}
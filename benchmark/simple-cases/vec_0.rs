@@ -0,0 +1,15 @@
+// safe
+fn main() {
+    let mut v = Vec::new();
+    rbmc::assert(v.is_empty() && v.capacity() == 0);
+    v.push(1);
+    v.push(2);
+    rbmc::assert(v.len() == 2 && v.capacity() >= 2);
+    let mut w: Vec<u8> = Vec::with_capacity(2);
+    w.push(1);
+    w.push(2);
+    rbmc::assert(w.capacity() == 2);
+    w.push(3);
+    rbmc::assert(w.capacity() >= 3 && w[2] == 3);
+    drop(v);
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let mut v = Vec::with_capacity(1);
+    v.push(1);
+    v.push(2);
+    rbmc::assert(v.capacity() >= v.len());
+    unsafe {
+        std::ptr::drop_in_place(&mut v);
+    }
+    // double-free of the buffer when `v` goes out of scope
+}
@@ -0,0 +1,19 @@
+fn main() {
+    let mut v: Vec<i32> = Vec::with_capacity(4);
+    v.push(1);
+    let p = v.as_mut_ptr();
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n <= 4);
+    unsafe {
+        *p.add(1) = 2;
+        rbmc::assert(*p == 1 && *p.add(1) == 2);
+        *p.add(n) = 0; // out of bounds if `n == 4`
+    }
+
+    let a: [i32; 3] = [1, 2, 3];
+    let s: &[i32] = &a[1..];
+    let q = s.as_ptr();
+    unsafe {
+        rbmc::assert(*q == 2 && *q.add(1) == 3);
+    }
+}
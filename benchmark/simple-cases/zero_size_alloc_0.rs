@@ -0,0 +1,11 @@
+use std::alloc::{alloc, dealloc, Layout};
+
+fn main() {
+    let n = rbmc::nondet::<usize>();
+    rbmc::assume(n < 4);
+    let layout = Layout::from_size_align(n, 1).unwrap();
+    unsafe {
+        let p = alloc(layout); // zero-size-alloc if `n == 0`
+        dealloc(p, layout);
+    }
+}
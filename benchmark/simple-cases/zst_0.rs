@@ -0,0 +1,13 @@
+struct Empty;
+
+fn main() {
+    let b = Box::new(Empty);
+    let p = Box::into_raw(b);
+    unsafe {
+        *p = Empty;
+        drop(Box::from_raw(p));
+    }
+    let x = Box::new(1);
+    let q = Box::into_raw(x);
+    unsafe { *q = 2; } // memory-leak
+}
@@ -4,3 +4,15 @@
 pub fn nondet<T>() -> T {
     panic!("Nondet is a place holder for RBMC")
 }
+
+/// Only consider the paths where `cond` holds
+#[allow(dead_code)]
+pub fn assume(_cond: bool) {
+    panic!("Assume is a place holder for RBMC")
+}
+
+/// Check `cond` holds in all paths
+#[allow(dead_code)]
+pub fn assert(_cond: bool) {
+    panic!("Assert is a place holder for RBMC")
+}
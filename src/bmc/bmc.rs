@@ -1,9 +1,19 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::config::cli::SmtStrategy;
+use serde_json::json;
+
+use super::error::BmcError;
+use super::result::*;
+use crate::config::cli::{OutputFormat, SmtStrategy};
 use crate::config::config::Config;
-use crate::expr::expr::ExprBuilder;
+use crate::expr::expr::*;
 use crate::solvers::solver::*;
+use crate::symbol::nstring::NString;
 use crate::symex::symex::*;
 use crate::vc::slicer::Slicer;
 use crate::vc::vc::*;
@@ -13,55 +23,136 @@ pub struct Bmc<'cfg> {
     symex: Symex<'cfg>,
     vc_system: VCSysPtr,
     runtime_solver: Solver<'cfg>,
+    /// Statistics of solvers in worker threads
+    worker_stats: SolverStats,
+    /// Result of each assertion checked
+    results: Vec<PResult>,
+    /// The failed assertion that the model is for
+    bug: Option<usize>,
+    /// Branches taken on the path to the failed assertion
+    trace: Vec<Branch>,
 }
 
 impl<'cfg> Bmc<'cfg> {
     pub fn new(config: &'cfg Config) -> Self {
         let vc_system = VCSysPtr::new(RefCell::new(VCSystem::default()));
         let symex = Symex::new(config, vc_system.clone());
-        let runtime_solver = Solver::new(&config.solver_config);
-        Bmc { config, symex, vc_system, runtime_solver }
+        let runtime_solver = Solver::new(&config.solver_config, &config.cli);
+        Bmc {
+            config,
+            symex,
+            vc_system,
+            runtime_solver,
+            worker_stats: SolverStats::default(),
+            results: Vec::new(),
+            bug: None,
+            trace: Vec::new(),
+        }
     }
 
-    pub fn do_bmc(&mut self) {
+    pub fn do_bmc(&mut self) -> VerificationResult {
         if self.config.cli.show_program {
             self.config.program.show();
         }
 
         let verify_time = std::time::Instant::now();
+
+        // An unsupported construct makes the result unknown. Other panics
+        // are still bugs of the checker. The hook is restored after verifying.
+        let hook: Arc<dyn Fn(&PanicHookInfo) + Sync + Send> = Arc::from(panic::take_hook());
+        let default_hook = hook.clone();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().downcast_ref::<BmcError>().is_none() {
+                default_hook(info);
+            }
+        }));
+        let verified = panic::catch_unwind(AssertUnwindSafe(|| self.verify()));
+        let _ = panic::take_hook();
+        panic::set_hook(Box::new(move |info| hook(info)));
+        let (res, result) = match verified {
+            Ok(res) => (
+                res,
+                match res {
+                    PResult::PSat => Verdict::Fail,
+                    PResult::PUnknow => Verdict::Unknown,
+                    PResult::PTimeout => Verdict::Timeout,
+                    // No failure is found in the paths explored
+                    PResult::PUnsat if self.symex.paths().1 > 0 => Verdict::Incomplete,
+                    PResult::PUnsat => Verdict::Success,
+                },
+            ),
+            Err(payload) => match payload.downcast::<BmcError>() {
+                Ok(err) => {
+                    println!("{err}");
+                    (PResult::PUnknow, Verdict::Unsupported)
+                }
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        };
+
+        let mut stats = self.runtime_solver.stats();
+        stats.add(&self.worker_stats);
+        stats.show();
+
+        let time = verify_time.elapsed().as_secs_f32();
+        println!("\nVerification time: {time}s");
+        println!("Verification result: {}.", Bmc::result_name(result));
+
+        let report = self.verification_result(res, result, time);
+        if self.config.cli.format == OutputFormat::Json {
+            Bmc::json_report(&report);
+        }
+        report
+    }
+
+    fn verify(&mut self) -> PResult {
+        let symex_time = std::time::Instant::now();
         println!("Start Symex ...");
 
         self.symex.run();
-        println!("Runtime Symex: {}s", verify_time.elapsed().as_secs_f32());
+        println!("Runtime Symex: {}s", symex_time.elapsed().as_secs_f32());
+        let (explored, cut) = self.symex.paths();
+        if cut > 0 {
+            println!(
+                "Reach the path bound: explored {explored} path(s), {cut} path(s) remaining. \
+                 The results are partial"
+            );
+        }
+        println!("Interned {} string(s)", NString::intern_count());
 
         self.vc_system.borrow().show_info();
 
-        let res = if self.vc_system.borrow().num_asserts() == 0 {
+        if self.vc_system.borrow().num_asserts() == 0 {
             println!("No assertions should be checked");
             PResult::PUnsat
         } else {
             self.check_properties()
-        };
-
-        println!("\nVerification time: {}s", verify_time.elapsed().as_secs_f32());
-        println!(
-            "Verification result: {}.",
-            match res {
-                PResult::PSat => "fail",
-                PResult::PUnknow => "unknown",
-                PResult::PUnsat => "success",
-            }
-        );
+        }
     }
 
     fn check_properties(&mut self) -> PResult {
         println!("Verifying with SMT strategy: {:?}", self.config.cli.smt_strategy);
         let (res, bug) = match self.config.cli.smt_strategy {
+            SmtStrategy::Forward if self.config.cli.jobs > 1 && !self.config.cli.unsat_core => {
+                self.check_parallel()
+            }
             SmtStrategy::Forward => self.check_forward(),
             SmtStrategy::Once => (self.check_once(), None),
+            SmtStrategy::Incremental => self.check_incremental(),
         };
+        if self.config.cli.all_properties && self.config.cli.smt_strategy != SmtStrategy::Once {
+            self.property_report();
+        }
+        self.bug = bug;
         if res == PResult::PSat {
+            self.trace = self.collect_trace(bug);
             self.bug_report(bug);
+            if self.config.cli.show_trace {
+                self.show_trace();
+            }
+            if self.config.cli.show_counterexample {
+                self.counterexample();
+            }
         }
         res
     }
@@ -70,28 +161,7 @@ impl<'cfg> Bmc<'cfg> {
         let mut slicer = Slicer::default();
         let size = self.vc_system.borrow().num_asserts();
         for i in 0..size {
-            println!("Begin checking assertion {i}");
-            if self.config.cli.show_vcc {
-                print!("Verifying condition {i} ");
-            }
-
-            self.vc_system.borrow_mut().set_nth_assertion(i);
-
-            if !self.config.cli.no_slice {
-                let slice_time = std::time::Instant::now();
-                slicer.slice_nth(self.vc_system.clone(), i);
-                println!("Runtime slicing asssertion {i}: {}s", slice_time.elapsed().as_secs_f32());
-                println!("After slicing: {} VC(s)", self.vc_system.borrow().num_valid_vc());
-            }
-
-            if self.config.cli.show_vcc {
-                self.vc_system.borrow().show_vcc();
-            }
-
-            self.runtime_solver.reset();
-            let convert_time = std::time::Instant::now();
-            self.generate_smt_formula();
-            println!("Runtime Convert SSA: {}s", convert_time.elapsed().as_secs_f32());
+            self.encode_nth_assertion(&mut slicer, i);
 
             let solver_time = std::time::Instant::now();
             let res = self.smt_result();
@@ -99,13 +169,115 @@ impl<'cfg> Bmc<'cfg> {
             if self.config.cli.show_vcc {
                 println!("Result: {res:?} ");
             }
+            self.results.push(res);
+            if self.config.cli.all_properties {
+                continue;
+            }
             match res {
                 PResult::PSat => return (res, Some(i)),
-                PResult::PUnknow => return (res, None),
+                PResult::PUnknow | PResult::PTimeout => return (res, None),
                 _ => {}
             }
         }
-        (PResult::PUnsat, None)
+        match self.first_failure() {
+            Some(i) if self.results[i] == PResult::PSat => {
+                if i + 1 != size {
+                    // Check again for the model
+                    self.encode_nth_assertion(&mut slicer, i);
+                    self.smt_result();
+                }
+                (PResult::PSat, Some(i))
+            }
+            Some(i) => (self.results[i], None),
+            None => (PResult::PUnsat, None),
+        }
+    }
+
+    /// Encode the formulas of all assertions firstly. Then each worker
+    /// checks them with its own solver context. The result is the same
+    /// as `Forward`, i.e., the first failed assertion is reported.
+    ///
+    /// Assertions that are not checked are marked as `PUnknow`.
+    fn check_parallel(&mut self) -> (PResult, Option<usize>) {
+        let mut slicer = Slicer::default();
+        let size = self.vc_system.borrow().num_asserts();
+        let mut formulas = Vec::new();
+        for i in 0..size {
+            self.encode_nth_assertion(&mut slicer, i);
+            formulas.push(self.runtime_solver.smtlib());
+        }
+
+        let jobs = self.config.cli.jobs;
+        println!("Checking {size} assertion(s) with {jobs} jobs");
+        let solver_time = std::time::Instant::now();
+        let next = AtomicUsize::new(0);
+        let first_failure = AtomicUsize::new(size);
+        let all_properties = self.config.cli.all_properties;
+        let timeout = self.config.cli.solver_timeout;
+        let results = Mutex::new(vec![PResult::PUnknow; size]);
+        let stats = Mutex::new(SolverStats::default());
+        std::thread::scope(|s| {
+            for _ in 0..jobs {
+                s.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        // Assertions after a failed one are not needed
+                        if i >= size || !all_properties && i > first_failure.load(Ordering::Relaxed)
+                        {
+                            break;
+                        }
+                        let (res, s) = Solver::check_smtlib(&formulas[i], timeout);
+                        if res != PResult::PUnsat {
+                            first_failure.fetch_min(i, Ordering::Relaxed);
+                        }
+                        results.lock().unwrap()[i] = res;
+                        stats.lock().unwrap().add(&s);
+                    }
+                });
+            }
+        });
+        println!("Runtime SMT check: {}s", solver_time.elapsed().as_secs_f32());
+
+        self.worker_stats.add(&stats.into_inner().unwrap());
+        self.results = results.into_inner().unwrap();
+        match self.first_failure() {
+            Some(i) if self.results[i] == PResult::PSat => {
+                // Check again for the model
+                self.encode_nth_assertion(&mut slicer, i);
+                (self.smt_result(), Some(i))
+            }
+            Some(i) => (self.results[i], None),
+            None => (PResult::PUnsat, None),
+        }
+    }
+
+    fn encode_nth_assertion(&mut self, slicer: &mut Slicer, i: usize) {
+        println!("Begin checking assertion {i}");
+        if self.config.cli.show_vcc {
+            print!("Verifying condition {i} ");
+        }
+
+        self.vc_system.borrow_mut().set_nth_assertion(i);
+
+        if !self.config.cli.no_slice {
+            let slice_time = std::time::Instant::now();
+            slicer.slice_nth(self.vc_system.clone(), i);
+            println!("Runtime slicing asssertion {i}: {}s", slice_time.elapsed().as_secs_f32());
+            println!("After slicing: {} VC(s)", self.vc_system.borrow().num_valid_vc());
+        }
+
+        if self.config.cli.show_vcc {
+            self.vc_system.borrow().show_vcc();
+        }
+
+        self.runtime_solver.reset();
+        let convert_time = std::time::Instant::now();
+        self.generate_smt_formula();
+        println!("Runtime Convert SSA: {}s", convert_time.elapsed().as_secs_f32());
+
+        if !self.config.cli.dump_smt.is_empty() {
+            self.dump_smt_formula(self.config.cli.dump_smt + "." + i.to_string());
+        }
     }
 
     fn check_once(&mut self) -> PResult {
@@ -130,6 +302,10 @@ impl<'cfg> Bmc<'cfg> {
         self.generate_smt_formula();
         println!("Runtime Convert SSA: {}s", convert_time.elapsed().as_secs_f32());
 
+        if !self.config.cli.dump_smt.is_empty() {
+            self.dump_smt_formula(self.config.cli.dump_smt);
+        }
+
         let solver_time = std::time::Instant::now();
         let res = self.smt_result();
         println!("Runtime SMT check: {}s", solver_time.elapsed().as_secs_f32());
@@ -139,11 +315,119 @@ impl<'cfg> Bmc<'cfg> {
         res
     }
 
+    fn check_incremental(&mut self) -> (PResult, Option<usize>) {
+        let ctx = self.config.expr_ctx.clone();
+        self.runtime_solver.reset();
+        let convert_time = std::time::Instant::now();
+        println!("Converting SSA");
+        let vcs = self.vc_system.borrow().iter().cloned().collect::<Vec<_>>();
+        for vc in vcs.iter() {
+            if let VcKind::Assign(lhs, rhs) = &vc.kind {
+                self.runtime_solver.assert_assign(lhs.clone(), rhs.clone());
+            }
+        }
+        println!("Runtime Convert SSA: {}s", convert_time.elapsed().as_secs_f32());
+
+        // Assertions in a block are checked in the scope of its path
+        // condition, which is pushed once the first of them is reached and
+        // popped when symex enters another block.
+        let scopes = self.vc_system.borrow().scopes().to_vec();
+        let mut next_scope = 0;
+        let mut guard = ctx._true();
+        let mut in_scope = false;
+
+        let mut i = 0;
+        let mut assumption = ctx._true();
+        let mut violated = Vec::new();
+        for (pos, vc) in vcs.iter().enumerate() {
+            while next_scope < scopes.len() && scopes[next_scope].pos <= pos {
+                if in_scope {
+                    self.runtime_solver.pop();
+                    in_scope = false;
+                }
+                guard = scopes[next_scope].guard.clone();
+                next_scope += 1;
+            }
+            match &vc.kind {
+                VcKind::Assume(c) => {
+                    assumption = ctx.and(assumption, c.clone());
+                    assumption.simplify();
+                }
+                VcKind::Assert(_, c) => {
+                    if !in_scope && !guard.is_true() {
+                        self.runtime_solver.push();
+                        self.runtime_solver.assert_expr(guard.clone());
+                        in_scope = true;
+                    }
+                    println!("Begin checking assertion {i}");
+                    // The violation condition contains the path condition
+                    self.runtime_solver.push();
+                    self.runtime_solver.assert_expr(ctx.and(assumption.clone(), c.clone()));
+                    if !self.config.cli.dump_smt.is_empty() {
+                        self.dump_smt_formula(self.config.cli.dump_smt + "." + i.to_string());
+                    }
+
+                    let solver_time = std::time::Instant::now();
+                    let res = self.smt_result();
+                    println!("Runtime SMT check: {}s", solver_time.elapsed().as_secs_f32());
+                    self.results.push(res);
+                    if res == PResult::PSat {
+                        violated.push(ctx.and(assumption.clone(), c.clone()));
+                    }
+                    if !self.config.cli.all_properties {
+                        match res {
+                            // Keep the scope for bug report
+                            PResult::PSat => return (res, Some(i)),
+                            PResult::PUnknow | PResult::PTimeout => return (res, None),
+                            _ => {}
+                        }
+                    }
+                    self.runtime_solver.pop();
+                    i += 1;
+                }
+                _ => {}
+            }
+        }
+        if in_scope {
+            self.runtime_solver.pop();
+        }
+        match self.first_failure() {
+            Some(i) if self.results[i] == PResult::PSat => {
+                // Check again for the model
+                self.runtime_solver.push();
+                self.runtime_solver.assert_expr(violated[0].clone());
+                (self.smt_result(), Some(i))
+            }
+            Some(i) => (self.results[i], None),
+            None => (PResult::PUnsat, None),
+        }
+    }
+
+    /// The first violated assertion. Otherwise, the first unknown one.
+    fn first_failure(&self) -> Option<usize> {
+        self.results
+            .iter()
+            .position(|res| *res == PResult::PSat)
+            .or(self.results.iter().position(|res| *res != PResult::PUnsat))
+    }
+
+    fn dump_smt_formula(&self, file: NString) {
+        let path = file.to_string();
+        self.runtime_solver.dump_smtlib(Path::new(&path));
+        println!("Dump SMT formula to {path}");
+    }
+
     fn smt_result(&mut self) -> PResult {
         let res = self.runtime_solver.check();
         if res == PResult::PSat && self.config.cli.show_smt_model {
             self.runtime_solver.show_model();
         }
+        if res == PResult::PSat && self.config.cli.show_object_spaces {
+            self.runtime_solver.show_object_spaces();
+        }
+        if res == PResult::PUnsat && self.config.cli.unsat_core {
+            self.runtime_solver.show_unsat_core();
+        }
         res
     }
 
@@ -163,7 +447,7 @@ impl<'cfg> Bmc<'cfg> {
                     self.runtime_solver.assert_assign(lhs.clone(), rhs.clone());
                 }
                 VcKind::Assert(_, c) => {
-                    assertions.push(ctx.implies(assumetion.clone(), c.clone()));
+                    assertions.push(ctx.and(assumetion.clone(), c.clone()));
                 }
                 VcKind::Assume(c) => {
                     assumetion = ctx.and(assumetion, c.clone());
@@ -183,7 +467,7 @@ impl<'cfg> Bmc<'cfg> {
 
     fn bug_report(&self, bug: Option<usize>) {
         println!("\nBug Report:");
-        if self.config.cli.smt_strategy == SmtStrategy::Forward {
+        if self.config.cli.smt_strategy != SmtStrategy::Once {
             let assertion = self.vc_system.borrow().nth_assertion(bug.unwrap());
             Bmc::bug_info(&assertion);
         } else {
@@ -197,15 +481,205 @@ impl<'cfg> Bmc<'cfg> {
         println!("");
     }
 
+    fn property_report(&self) {
+        println!("\nProperty Report:");
+        for (n, res) in self.results.iter().enumerate() {
+            let assertion = self.vc_system.borrow().nth_assertion(n);
+            let status = Bmc::verdict_name(self.verdict(*res));
+            println!("-> {}: {:?}: {status}", assertion.location(), assertion.msg());
+        }
+    }
+
+    /// An assertion is not safe for sure if paths are cut
+    fn verdict(&self, res: PResult) -> PropertyVerdict {
+        match res {
+            PResult::PSat => PropertyVerdict::Violated,
+            PResult::PUnknow => PropertyVerdict::Unknown,
+            PResult::PTimeout => PropertyVerdict::Timeout,
+            PResult::PUnsat if self.symex.paths().1 > 0 => PropertyVerdict::Incomplete,
+            PResult::PUnsat => PropertyVerdict::Safe,
+        }
+    }
+
+    fn result_name(result: Verdict) -> &'static str {
+        match result {
+            Verdict::Success => "success",
+            Verdict::Fail => "fail",
+            Verdict::Unknown => "unknown",
+            Verdict::Timeout => "unknown (timeout)",
+            Verdict::Unsupported => "unknown (unsupported)",
+            Verdict::Incomplete => "unknown (incomplete)",
+        }
+    }
+
+    fn verdict_name(verdict: PropertyVerdict) -> &'static str {
+        match verdict {
+            PropertyVerdict::Safe => "SAFE",
+            PropertyVerdict::Violated => "VIOLATED",
+            PropertyVerdict::Unknown => "UNKNOWN",
+            PropertyVerdict::Timeout => "UNKNOWN (timeout)",
+            PropertyVerdict::Incomplete => "UNKNOWN (incomplete)",
+            PropertyVerdict::Unchecked => "UNCHECKED",
+        }
+    }
+
+    /// The verdicts of all assertions. Properties after the reported
+    /// failure are unchecked. With `Once` strategy, only the failed ones in the
+    /// model are known
+    fn verification_result(&self, res: PResult, result: Verdict, time: f32) -> VerificationResult {
+        let once = self.config.cli.smt_strategy == SmtStrategy::Once;
+        let mut properties = Vec::new();
+        for n in 0..self.vc_system.borrow().num_asserts() {
+            let assertion = self.vc_system.borrow().nth_assertion(n);
+            let failed = res == PResult::PSat
+                && match once {
+                    true => self.runtime_solver.eval_bool(assertion.cond()),
+                    false => self.bug == Some(n),
+                };
+            let verdict = match self.results.get(n) {
+                Some(res) => self.verdict(*res),
+                None if once && failed => self.verdict(PResult::PSat),
+                None if once && res == PResult::PSat => self.verdict(PResult::PUnknow),
+                None if once => self.verdict(res),
+                None => PropertyVerdict::Unchecked,
+            };
+            let counterexample = failed.then(|| {
+                self.counterexample_values()
+                    .into_iter()
+                    .map(|(input, value)| (format!("{input:?}"), value.to_string()))
+                    .collect()
+            });
+            let trace = failed.then(|| {
+                self.trace
+                    .iter()
+                    .map(|branch| (span_location(branch.span), format!("{:?}", branch.guard)))
+                    .collect()
+            });
+            properties.push(PropertyResult {
+                location: assertion.location(),
+                message: assertion.msg().to_string(),
+                verdict,
+                counterexample,
+                trace,
+            });
+        }
+        VerificationResult {
+            function: self.config.program.function(0).name().to_string(),
+            result,
+            time,
+            properties,
+        }
+    }
+
+    /// Print the result in JSON in one line
+    fn json_report(report: &VerificationResult) {
+        let properties = report
+            .properties
+            .iter()
+            .map(|property| {
+                let counterexample = match &property.counterexample {
+                    Some(values) => serde_json::Value::Object(
+                        values.iter().map(|(input, value)| (input.clone(), json!(value))).collect(),
+                    ),
+                    None => serde_json::Value::Null,
+                };
+                let trace = match &property.trace {
+                    Some(branches) => serde_json::Value::Array(
+                        branches
+                            .iter()
+                            .map(|(location, guard)| json!({"location": location, "guard": guard}))
+                            .collect(),
+                    ),
+                    None => serde_json::Value::Null,
+                };
+                json!({
+                    "location": property.location,
+                    "message": property.message,
+                    "verdict": Bmc::verdict_name(property.verdict),
+                    "counterexample": counterexample,
+                    "trace": trace,
+                })
+            })
+            .collect::<Vec<_>>();
+        let report = json!({
+            "function": report.function,
+            "result": Bmc::result_name(report.result),
+            "time": report.time,
+            "properties": properties,
+        });
+        println!("{report}");
+    }
+
+    /// The branches whose guards hold in the model. With `Once` strategy,
+    /// all branches before the last assertion are considered.
+    fn collect_trace(&mut self, bug: Option<usize>) -> Vec<Branch> {
+        let n = bug.unwrap_or(self.vc_system.borrow().num_asserts() - 1);
+        let branches = self.vc_system.borrow().branches_to_nth_assertion(n);
+        // The values of sliced symbols are not constrained by the model
+        let symbols = self.vc_system.borrow().unsliced_symbols();
+        branches
+            .into_iter()
+            .filter(|branch| {
+                let mut path_symbols = HashSet::new();
+                collect_symbols(&branch.path, &mut path_symbols);
+                path_symbols.is_subset(&symbols)
+                    && self.runtime_solver.eval_cond(branch.path.clone()) == Some(true)
+            })
+            .collect()
+    }
+
+    fn show_trace(&self) {
+        println!("Trace:");
+        for (i, branch) in self.trace.iter().enumerate() {
+            println!("  #{i} {}: {:?}", span_location(branch.span), branch.guard);
+        }
+        println!("");
+    }
+
+    fn counterexample(&self) {
+        println!("Counterexample:");
+        for (input, value) in self.counterexample_values() {
+            println!("  {input:?} = {value:?}");
+        }
+        println!("");
+    }
+
+    /// Values of nondet inputs in the model
+    fn counterexample_values(&self) -> Vec<(Expr, NString)> {
+        let mut inputs = Vec::new();
+        for vc in self.vc_system.borrow().iter() {
+            if vc.is_sliced {
+                continue;
+            }
+            if let VcKind::Assign(_, rhs) = &vc.kind {
+                Bmc::collect_nondet(rhs, &mut inputs);
+            }
+        }
+        inputs
+            .into_iter()
+            .filter_map(|input| Some((input.clone(), self.runtime_solver.eval_expr(input)?)))
+            .collect()
+    }
+
+    fn collect_nondet(expr: &Expr, inputs: &mut Vec<Expr>) {
+        if expr.is_symbol() && expr.extract_symbol().ident().find("nondet_".into()) == Some(0) {
+            if !inputs.contains(expr) {
+                inputs.push(expr.clone());
+            }
+            return;
+        }
+        if let Some(sub_exprs) = expr.sub_exprs() {
+            for e in sub_exprs.iter() {
+                Bmc::collect_nondet(e, inputs);
+            }
+        }
+    }
+
     #[inline]
     fn bug_info(assertion: &Vc) {
-        let span = assertion.span.expect("Span must exist");
-        println!(
-            "-> {}:{}:{}: {:?}",
-            span.get_filename(),
-            span.get_lines().start_line,
-            span.get_lines().start_col,
-            assertion.msg()
-        );
+        println!("-> {}: {:?}", assertion.location(), assertion.msg());
+        if let Some((line, source)) = span_source_line(assertion.span) {
+            println!("   {line} | {}", source.trim());
+        }
     }
 }
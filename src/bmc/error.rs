@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+/// Errors that stop verifying the current function. They are raised as
+/// panic payloads by `unsupported!` and caught by `Bmc::do_bmc`, which
+/// reports the result as unknown instead of aborting.
+#[derive(Debug, Clone)]
+pub enum BmcError {
+    /// A type or construct that can not be modeled
+    Unsupported(String),
+}
+
+impl Display for BmcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BmcError::Unsupported(msg) => write!(f, "Unsupported: {msg}"),
+        }
+    }
+}
+
+/// Give up on a construct that can not be modeled
+macro_rules! unsupported {
+    ($($arg:tt)*) => {
+        std::panic::panic_any($crate::bmc::error::BmcError::Unsupported(format!($($arg)*)))
+    };
+}
+
+pub(crate) use unsupported;
@@ -1 +1,3 @@
 pub mod bmc;
+pub mod error;
+pub mod result;
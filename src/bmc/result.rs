@@ -0,0 +1,62 @@
+/// The result of verifying a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Success,
+    Fail,
+    Unknown,
+    /// The solver times out
+    Timeout,
+    /// A construct can not be modeled
+    Unsupported,
+    /// No failure is found, but paths are cut by `--max-paths`
+    Incomplete,
+}
+
+/// The result of checking an assertion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyVerdict {
+    Safe,
+    Violated,
+    Unknown,
+    /// The solver times out
+    Timeout,
+    /// Not checked after the failure being reported
+    Unchecked,
+    /// No failure is found, but paths are cut by `--max-paths`
+    Incomplete,
+}
+
+/// The result of verifying a function, which is the same as the JSON
+/// report of `--format json`
+#[derive(Debug, Clone)]
+pub struct VerificationResult {
+    /// The entry function
+    pub function: String,
+    pub result: Verdict,
+    /// Verification time in seconds
+    pub time: f32,
+    pub properties: Vec<PropertyResult>,
+}
+
+impl VerificationResult {
+    pub fn is_success(&self) -> bool {
+        self.result == Verdict::Success
+    }
+
+    pub fn is_fail(&self) -> bool {
+        self.result == Verdict::Fail
+    }
+}
+
+/// The verdict of an assertion. The counterexample and the trace only
+/// exist for the failure being reported.
+#[derive(Debug, Clone)]
+pub struct PropertyResult {
+    pub location: String,
+    pub message: String,
+    pub verdict: PropertyVerdict,
+    /// Values of nondet inputs
+    pub counterexample: Option<Vec<(String, String)>>,
+    /// Locations and guards of the branches taken
+    pub trace: Option<Vec<(String, String)>>,
+}
@@ -11,6 +11,56 @@ pub enum SmtStrategy {
     #[default]
     Forward,
     Once,
+    Incremental,
+}
+
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PointerEncoding {
+    #[default]
+    Int,
+    Bv,
+}
+
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SolverKind {
+    #[default]
+    Z3,
+}
+
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Classes of properties to be checked
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Leak,
+    DoubleFree,
+    UseAfterFree,
+    InvalidFree,
+    InvalidPointer,
+    Null,
+    Bound,
+    Overflow,
+    DivByZero,
+    Assertion,
+    Unwind,
+    /// Reading alloced memory before it is written
+    UninitializedRead,
+    /// Producing a `char` that is not a Unicode scalar value
+    InvalidChar,
+    /// Reading a local whose value is moved out. Not checked unless it is
+    /// given explicitly
+    UseAfterMove,
+    /// Calling `alloc` with a zero-sized layout
+    ZeroSizeAlloc,
+    /// Reaching a panic, e.g., `panic!` and `unreachable!`
+    Panic,
+    /// Conflicting borrows. Not checked unless it is given explicitly
+    Aliasing,
 }
 
 #[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -23,17 +73,42 @@ pub enum DisplayState {
     All,
 }
 
-#[derive(Parser, Debug, Default)]
+#[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
     /// Source file '.rs'
     #[arg(default_value_t = NString::EMPTY)]
     pub file: NString,
 
+    /// The path of the function to verify, e.g., `path::to::fn`. It is
+    /// `main` by default
+    #[arg(long, default_value_t = NString::EMPTY)]
+    pub entry: NString,
+
     /// Loop bound. '0' indicates unbounded
     #[arg(long, default_value_t = 0)]
     pub unwind: usize,
 
+    /// Recursion bound. '0' indicates unbounded
+    #[arg(long, default_value_t = 0)]
+    pub recursion: usize,
+
+    /// Bound of the paths forked at branches. '0' indicates unbounded.
+    /// The branches beyond the bound are cut and the result is incomplete
+    #[arg(long, default_value_t = 0)]
+    pub max_paths: usize,
+
+    /// Report a failure if a loop or a recursion is not fully unwound
+    /// within the bound
+    #[arg(long, default_value_t = false)]
+    pub unwinding_assertions: bool,
+
+    /// Merge the states at the join points of the control flow by `ite`
+    /// over the guards of the incoming paths. With `--merge false`, the
+    /// paths are executed one by one until a loop entry or the return
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub merge: bool,
+
     /// Show program
     #[arg(long, default_value_t = false)]
     pub show_program: bool,
@@ -56,10 +131,31 @@ pub struct Cli {
     /// `Forward`: stop while an assertion fail.
     ///
     /// `Once`: encoding all assertions and check only for one time.
+    ///
+    /// `Incremental`: encoding assignments for one time and check each
+    /// assertion in a solver scope, nested in the scope of the path
+    /// condition of its block. Slicing is not applied.
     #[clap(value_enum)]
     #[arg(long, default_value_t = SmtStrategy::Forward)]
     pub smt_strategy: SmtStrategy,
 
+    /// Check all assertions instead of stopping at the first failure, and
+    /// report whether each one is safe, violated or unknown. Not for `Once`
+    #[arg(long, default_value_t = false)]
+    pub all_properties: bool,
+
+    /// Number of worker threads checking assertions in parallel. Only
+    /// for `Forward` strategy
+    #[arg(long, default_value_t = 1)]
+    pub jobs: usize,
+
+    /// Only check the given classes of properties, separated by ','.
+    /// All properties except `aliasing` and `use-after-move` are checked if
+    /// none is given
+    #[clap(value_enum)]
+    #[arg(long, value_delimiter = ',')]
+    pub check: Vec<Check>,
+
     /// Show SMT formula
     #[arg(long, default_value_t = false)]
     pub show_smt: bool,
@@ -68,15 +164,83 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub show_smt_model: bool,
 
-    /// SMT solver
-    #[arg(long, default_value_t = NString::from("z3"))]
-    pub solver: NString,
+    /// Show the object spaces for a failed check, i.e., the base, the
+    /// length, the liveness and the contents of each object
+    #[arg(long, default_value_t = false)]
+    pub show_object_spaces: bool,
+
+    /// Show values of nondet inputs for a failed check
+    #[arg(long, default_value_t = false)]
+    pub show_counterexample: bool,
+
+    /// Show the branches taken on the path to a failed check
+    #[arg(long, default_value_t = false)]
+    pub show_trace: bool,
+
+    /// Show the unsat core, i.e., the VCs making a safe check UNSAT.
+    /// Assertions are checked in this process even if `jobs > 1`
+    #[arg(long, default_value_t = false)]
+    pub unsat_core: bool,
+
+    /// Dump SMT formula in SMT-LIB2 format to the file. With `Forward`
+    /// strategy, the index of assertion is appended to the file name
+    #[arg(long, default_value_t = NString::EMPTY)]
+    pub dump_smt: NString,
+
+    /// The encoding of base/offset/meta in a pointer.
+    ///
+    /// `Int`: unbounded integers.
+    ///
+    /// `Bv`: bit-vectors with the width of target `usize`. Pointer
+    /// arithmetic wraps around.
+    #[clap(value_enum)]
+    #[arg(long, default_value_t = PointerEncoding::Int)]
+    pub pointer_encoding: PointerEncoding,
+
+    /// Skip disjointness between object spaces whose types can not alias
+    /// until addresses are exposed. It makes the formulas smaller, but is
+    /// unsound if a pointer is cast to an unrelated type
+    #[arg(long, default_value_t = false)]
+    pub assume_type_disjoint: bool,
+
+    /// Do not assume objects are disjoint if their liveness is not in the
+    /// slice. By default, such objects never share an address, which is
+    /// unsound if a freed address is reused. Skipping the assumption is
+    /// sound, but distinct objects may be reported equal
+    #[arg(long, default_value_t = false)]
+    pub no_disjoint_allocs: bool,
+
+    /// Timeout of each SMT check in milliseconds. '0' indicates no timeout
+    #[arg(long, default_value_t = 0)]
+    pub solver_timeout: u32,
+
+    /// The format of the report.
+    ///
+    /// `Text`: human-readable messages.
+    ///
+    /// `Json`: additionally print a JSON object with the verdict of each
+    /// property, the counterexample and the trace in the last line.
+    #[clap(value_enum)]
+    #[arg(long, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// SMT solver. Only `z3` is supported, other values are rejected
+    #[clap(value_enum)]
+    #[arg(long, default_value_t = SolverKind::Z3)]
+    pub solver: SolverKind,
 
     /// Close warnings [default: true]
     #[arg(long, default_value_t = false)]
     pub show_warnings: bool,
 }
 
+impl Default for Cli {
+    /// The options with the default values of the command line
+    fn default() -> Self {
+        Cli::parse_from(["rbmc"])
+    }
+}
+
 impl Cli {
     pub fn new() -> Self {
         // Carefully, dno't print anything in this function.
@@ -130,6 +294,13 @@ impl Cli {
         }
     }
 
+    pub fn enable_check(&self, check: Check) -> bool {
+        if matches!(check, Check::Aliasing | Check::UseAfterMove) {
+            return self.check.contains(&check);
+        }
+        self.check.is_empty() || self.check.contains(&check)
+    }
+
     pub fn enable_display_state_bb(&self) -> bool {
         self.show_states == DisplayState::BB || self.show_states == DisplayState::All
     }
@@ -1,7 +1,11 @@
 use std::cell::RefCell;
 
+use clap::CommandFactory;
+use clap::error::ErrorKind;
+
 use super::cli::*;
 use crate::expr::context::*;
+use crate::expr::ty::FunctionDef;
 use crate::program::program::*;
 use crate::solvers::context::SolverCtx;
 
@@ -14,9 +18,19 @@ pub struct Config {
 
 impl Config {
     pub fn new(cli: Cli) -> Self {
-        // Get stable mir
-        let program = Program::new(stable_mir::local_crate());
+        // Get stable mir. A bad entry is reported as a CLI error
+        let program = Program::new(stable_mir::local_crate(), cli.entry)
+            .unwrap_or_else(|msg| Cli::command().error(ErrorKind::InvalidValue, msg).exit());
+        Config::with_program(cli, program)
+    }
+
+    /// Verify from the function `entry` instead of `main`
+    pub fn with_entry(cli: Cli, entry: FunctionDef) -> Self {
+        let program = Program::with_entry(stable_mir::local_crate(), entry);
+        Config::with_program(cli, program)
+    }
 
+    fn with_program(cli: Cli, program: Program) -> Self {
         // Context for managing Expr
         let expr_ctx = ExprCtx::new(RefCell::new(Context::new()));
 
@@ -107,6 +107,8 @@ pub(super) enum NodeKind {
     /// `Store(object, index, value)` updates an array/slice or
     /// a field of a struct.
     Store(NodeId, NodeId, NodeId),
+    /// `Repeat(value)` is the array whose elements are all `value`.
+    Repeat(NodeId),
 
     // `Pointer(base, offset, meta)`: pointer/ref in a uniform.
     // We currentlly support `usize` meta.
@@ -129,6 +131,13 @@ pub(super) enum NodeKind {
     VecCap(NodeId),
     /// Retrieving inner pointer of smart pointer, including `Box`, `Vec`, and son on.
     InnerPointer(NodeId),
+    /// `ObjectSize(object)` retrieves the length of the space of an object.
+    /// It is symbolic for an infinite array with a runtime size.
+    ObjectSize(NodeId),
+    /// `CopyRegion(dst, src, len)` is the array of `dst` whose region is
+    /// overwritten by the first `len` elements of the region of `src`. A
+    /// region is an array, or a slice of an array starting from its start.
+    CopyRegion(NodeId, NodeId, NodeId),
 
     // enum
     /// `Variant(i, x)`: variant `i` with data `x`.
@@ -202,6 +211,10 @@ impl NodeKind {
         matches!(self, NodeKind::Store(..))
     }
 
+    pub fn is_repeat(&self) -> bool {
+        matches!(self, NodeKind::Repeat(..))
+    }
+
     pub fn is_offset(&self) -> bool {
         matches!(self, NodeKind::Offset(..))
     }
@@ -238,6 +251,14 @@ impl NodeKind {
         matches!(self, NodeKind::InnerPointer(..))
     }
 
+    pub fn is_object_size(&self) -> bool {
+        matches!(self, NodeKind::ObjectSize(..))
+    }
+
+    pub fn is_copy_region(&self) -> bool {
+        matches!(self, NodeKind::CopyRegion(..))
+    }
+
     pub fn is_variant(&self) -> bool {
         matches!(self, NodeKind::Variant(..))
     }
@@ -298,7 +319,7 @@ impl Node {
             NodeKind::Binary(_, l, r) | NodeKind::Cast(l, r) | NodeKind::SameObject(l, r) => {
                 Some(vec![*l, *r])
             }
-            NodeKind::Unary(_, o) | NodeKind::Object(o) => Some(vec![*o]),
+            NodeKind::Unary(_, o) | NodeKind::Object(o) | NodeKind::Repeat(o) => Some(vec![*o]),
             NodeKind::Slice(o, s, l) => Some(vec![*o, *s, *l]),
             NodeKind::Ite(c, tv, fv) => Some(vec![*c, *tv, *fv]),
             NodeKind::Index(o, i) => Some(vec![*o, *i]),
@@ -310,8 +331,9 @@ impl Node {
             | NodeKind::Box(p)
             | NodeKind::VecLen(p)
             | NodeKind::VecCap(p)
-            | NodeKind::InnerPointer(p) => Some(vec![*p]),
-            NodeKind::Vec(p, l, c) => Some(vec![*p, *l, *c]),
+            | NodeKind::InnerPointer(p)
+            | NodeKind::ObjectSize(p) => Some(vec![*p]),
+            NodeKind::Vec(p, l, c) | NodeKind::CopyRegion(p, l, c) => Some(vec![*p, *l, *c]),
             NodeKind::Variant(i, x) => {
                 if let Some(data) = x {
                     Some(vec![*i, *data])
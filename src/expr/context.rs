@@ -180,6 +180,11 @@ impl Context {
         self.nodes[i].kind().is_store()
     }
 
+    pub fn is_repeat(&self, i: NodeId) -> bool {
+        assert!(i < self.nodes.len());
+        self.nodes[i].kind().is_repeat()
+    }
+
     pub fn is_offset(&self, i: NodeId) -> bool {
         assert!(i < self.nodes.len());
         self.nodes[i].kind().is_offset()
@@ -225,6 +230,16 @@ impl Context {
         self.nodes[i].kind().is_inner_pointer()
     }
 
+    pub fn is_object_size(&self, i: NodeId) -> bool {
+        assert!(i < self.nodes.len());
+        self.nodes[i].kind().is_object_size()
+    }
+
+    pub fn is_copy_region(&self, i: NodeId) -> bool {
+        assert!(i < self.nodes.len());
+        self.nodes[i].kind().is_copy_region()
+    }
+
     pub fn is_enum(&self, i: NodeId) -> bool {
         assert!(i < self.nodes.len());
         self.nodes[i].kind().is_variant()
@@ -492,6 +507,15 @@ impl ExprBuilder for ExprCtx {
         Expr { ctx: self.clone(), id }
     }
 
+    fn rem(&self, lhs: Expr, rhs: Expr) -> Expr {
+        assert!(lhs.ty().is_integer() && rhs.ty().is_integer());
+        let kind = NodeKind::Binary(BinOp::Rem, lhs.id, rhs.id);
+        let ty = lhs.ty();
+        let new_node = Node::new(kind, ty);
+        let id = self.borrow_mut().add_node(new_node);
+        Expr { ctx: self.clone(), id }
+    }
+
     fn eq(&self, lhs: Expr, rhs: Expr) -> Expr {
         assert!(lhs.ty() == rhs.ty());
         let kind = NodeKind::Binary(BinOp::Eq, lhs.id, rhs.id);
@@ -596,7 +620,12 @@ impl ExprBuilder for ExprCtx {
 
     fn ite(&self, cond: Expr, true_value: Expr, false_value: Expr) -> Expr {
         assert!(cond.ty().is_bool());
-        assert!(true_value.ty() == false_value.ty());
+        assert!(
+            true_value.ty() == false_value.ty(),
+            "Branches of ite have different types {} and {}",
+            true_value.ty(),
+            false_value.ty()
+        );
         let kind = NodeKind::Ite(cond.id, true_value.id, false_value.id);
         let ty = true_value.ty();
         let new_node = Node::new(kind, ty);
@@ -666,6 +695,14 @@ impl ExprBuilder for ExprCtx {
         Expr { ctx: self.clone(), id }
     }
 
+    fn repeat(&self, value: Expr, ty: Type) -> Expr {
+        assert!(ty.is_array() && ty.elem_type() == value.ty());
+        let kind = NodeKind::Repeat(value.id);
+        let new_node = Node::new(kind, ty);
+        let id = self.borrow_mut().add_node(new_node);
+        Expr { ctx: self.clone(), id }
+    }
+
     fn offset(&self, pt: Expr, offset: Expr) -> Expr {
         assert!(pt.ty().is_ptr() && offset.ty().is_integer());
         let kind = NodeKind::Offset(pt.id, offset.id);
@@ -696,7 +733,7 @@ impl ExprBuilder for ExprCtx {
     }
 
     fn pointer_meta(&self, pt: Expr) -> Expr {
-        assert!(pt.ty().is_slice_ptr());
+        assert!(pt.ty().is_fat_pointer());
         let kind = NodeKind::PointerMeta(pt.id);
         let ty = Type::usize_type();
         let new_node = Node::new(kind, ty);
@@ -749,6 +786,27 @@ impl ExprBuilder for ExprCtx {
         Expr { ctx: self.clone(), id }
     }
 
+    fn object_size(&self, object: Expr) -> Expr {
+        assert!(object.is_object());
+        let kind = NodeKind::ObjectSize(object.id);
+        let ty = Type::usize_type();
+        let new_node = Node::new(kind, ty);
+        let id = self.borrow_mut().add_node(new_node);
+        Expr { ctx: self.clone(), id }
+    }
+
+    fn copy_region(&self, dst: Expr, src: Expr, len: Expr) -> Expr {
+        let array = |region: &Expr| {
+            if region.is_slice() { region.extract_object() } else { region.clone() }
+        };
+        let ty = array(&dst).ty();
+        assert!(ty.is_array() && array(&src).ty().is_array() && len.ty().is_integer());
+        let kind = NodeKind::CopyRegion(dst.id, src.id, len.id);
+        let new_node = Node::new(kind, ty);
+        let id = self.borrow_mut().add_node(new_node);
+        Expr { ctx: self.clone(), id }
+    }
+
     fn variant(&self, idx: Expr, data: Option<Expr>, ty: Type) -> Expr {
         assert!(ty.is_enum());
         let kind = NodeKind::Variant(
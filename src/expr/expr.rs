@@ -84,6 +84,9 @@ impl Expr {
     pub fn is_store(&self) -> bool {
         self.ctx.borrow().is_store(self.id)
     }
+    pub fn is_repeat(&self) -> bool {
+        self.ctx.borrow().is_repeat(self.id)
+    }
 
     pub fn is_offset(&self) -> bool {
         self.ctx.borrow().is_offset(self.id)
@@ -112,6 +115,12 @@ impl Expr {
     pub fn is_inner_pointer(&self) -> bool {
         self.ctx.borrow().is_inner_pointer(self.id)
     }
+    pub fn is_object_size(&self) -> bool {
+        self.ctx.borrow().is_object_size(self.id)
+    }
+    pub fn is_copy_region(&self) -> bool {
+        self.ctx.borrow().is_copy_region(self.id)
+    }
 
     pub fn is_enum(&self) -> bool {
         self.ctx.borrow().is_enum(self.id)
@@ -173,6 +182,7 @@ impl Expr {
                 || self.is_move()
                 || self.is_valid()
                 || self.is_invalid()
+                || self.is_object_size()
         );
         self.extract_sub_expr(0)
     }
@@ -190,6 +200,10 @@ impl Expr {
             return self.extract_object().extract_root_object();
         }
 
+        if self.is_as_variant() {
+            return self.extract_enum().extract_root_object();
+        }
+
         panic!("Impossible")
     }
 
@@ -292,6 +306,11 @@ impl Expr {
         self.extract_sub_expr(2)
     }
 
+    pub fn extract_repeat_value(&self) -> Expr {
+        assert!(self.is_repeat());
+        self.extract_sub_expr(0)
+    }
+
     pub fn extract_inner_pointer(&self) -> Expr {
         assert!(
             self.is_offset()
@@ -335,6 +354,10 @@ impl Expr {
             return self.extract_inner_expr().compute_offset();
         }
 
+        if self.is_as_variant() {
+            return self.extract_enum().compute_offset();
+        }
+
         if self.is_slice() {
             let mut offset = self.extract_object().compute_offset();
             let elem_size = self.ty().elem_type().num_fields();
@@ -360,10 +383,14 @@ impl Expr {
                 let def = inner_object.ty().struct_def();
                 assert!(BigInt::ZERO <= idx && idx < def.1.len().into());
                 let i = bigint_to_usize(&idx);
-                let mut res = 0;
-                for j in 0..i {
-                    res += def.1[j].1.num_fields();
-                }
+                let res = inner_object.ty().struct_field_offset(i);
+                self.ctx.constant_isize(res as isize)
+            } else if inner_object.ty().is_enum() {
+                // A field in the data of a variant
+                assert!(index.is_constant());
+                let k = inner_object.extract_inner_expr().extract_variant_idx();
+                let i = bigint_to_usize(&index.extract_constant().to_integer());
+                let res = inner_object.ty().variant_field_offset(k, i);
                 self.ctx.constant_isize(res as isize)
             } else {
                 assert!(inner_object.ty().is_tuple());
@@ -372,10 +399,7 @@ impl Expr {
                 let def = inner_object.ty().tuple_def();
                 assert!(BigInt::ZERO <= idx && idx < def.len().into());
                 let i = bigint_to_usize(&idx);
-                let mut res = 0;
-                for j in 0..i {
-                    res += def[j].num_fields();
-                }
+                let res = inner_object.ty().tuple_elem_offset(i);
                 self.ctx.constant_isize(res as isize)
             };
             offset = self.ctx.add(offset, collected_offset);
@@ -448,6 +472,7 @@ impl Expr {
                 BinOp::Sub => self.ctx.sub(lhs, rhs),
                 BinOp::Mul => self.ctx.mul(lhs, rhs),
                 BinOp::Div => self.ctx.div(lhs, rhs),
+                BinOp::Rem => self.ctx.rem(lhs, rhs),
                 BinOp::Eq => self.ctx.eq(lhs, rhs),
                 BinOp::Ne => self.ctx.ne(lhs, rhs),
                 BinOp::Ge => self.ctx.ge(lhs, rhs),
@@ -522,6 +547,12 @@ impl Expr {
             return;
         }
 
+        if self.is_repeat() {
+            let value = sub_exprs[0].clone();
+            *self = self.ctx.repeat(value, self.ty());
+            return;
+        }
+
         if self.is_offset() {
             let pt = sub_exprs[0].clone();
             let offset = sub_exprs[1].clone();
@@ -579,6 +610,20 @@ impl Expr {
             return;
         }
 
+        if self.is_object_size() {
+            let object = sub_exprs[0].clone();
+            *self = self.ctx.object_size(object);
+            return;
+        }
+
+        if self.is_copy_region() {
+            let dst = sub_exprs[0].clone();
+            let src = sub_exprs[1].clone();
+            let len = sub_exprs[2].clone();
+            *self = self.ctx.copy_region(dst, src, len);
+            return;
+        }
+
         if self.is_enum() {
             if sub_exprs.len() == 2 {
                 let idx = sub_exprs[0].clone();
@@ -720,6 +765,11 @@ impl Debug for Expr {
                 return write!(f, "store({object:?}, {index:?}, {value:?})");
             }
 
+            if self.is_repeat() {
+                let value = &sub_exprs[0];
+                return write!(f, "[{value:?}; {}]", self.ty().array_size().unwrap());
+            }
+
             if self.is_offset() {
                 let pt = &sub_exprs[0];
                 let offset = &sub_exprs[1];
@@ -768,6 +818,18 @@ impl Debug for Expr {
                 return write!(f, "iptr({pt:?})");
             }
 
+            if self.is_object_size() {
+                let object = &sub_exprs[0];
+                return write!(f, "size({object:?})");
+            }
+
+            if self.is_copy_region() {
+                let dst = &sub_exprs[0];
+                let src = &sub_exprs[1];
+                let len = &sub_exprs[2];
+                return write!(f, "copy({dst:?}, {src:?}, {len:?})");
+            }
+
             if self.is_enum() {
                 let def = self.ty().enum_def();
                 let idx = bigint_to_usize(&sub_exprs[0].extract_constant().to_integer());
@@ -839,6 +901,7 @@ pub trait ExprBuilder {
     fn sub(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn mul(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn div(&self, lhs: Expr, rhs: Expr) -> Expr;
+    fn rem(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn eq(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn ne(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn ge(&self, lhs: Expr, rhs: Expr) -> Expr;
@@ -858,6 +921,7 @@ pub trait ExprBuilder {
     fn same_object(&self, lhs: Expr, rhs: Expr) -> Expr;
     fn index(&self, object: Expr, index: Expr, ty: Type) -> Expr;
     fn store(&self, object: Expr, key: Expr, value: Expr) -> Expr;
+    fn repeat(&self, value: Expr, ty: Type) -> Expr;
 
     fn offset(&self, pt: Expr, offset: Expr) -> Expr;
     fn pointer_base(&self, pt: Expr) -> Expr;
@@ -868,6 +932,8 @@ pub trait ExprBuilder {
     fn vec_len(&self, pt: Expr) -> Expr;
     fn vec_cap(&self, pt: Expr) -> Expr;
     fn inner_pointer(&self, pt: Expr) -> Expr;
+    fn object_size(&self, object: Expr) -> Expr;
+    fn copy_region(&self, dst: Expr, src: Expr, len: Expr) -> Expr;
 
     fn variant(&self, idx: Expr, data: Option<Expr>, ty: Type) -> Expr;
     fn as_variant(&self, x: Expr, idx: Expr) -> Expr;
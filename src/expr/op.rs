@@ -1,14 +1,16 @@
 use std::fmt::Debug;
-use std::fmt::Error;
 
 use stable_mir::mir;
 
+use crate::bmc::error::unsupported;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BinOp {
     Add,
     Sub,
     Mul,
     Div,
+    Rem,
     Eq,
     Ne,
     Ge,
@@ -27,6 +29,7 @@ impl Debug for BinOp {
             BinOp::Sub => write!(f, "-"),
             BinOp::Mul => write!(f, "*"),
             BinOp::Div => write!(f, "/"),
+            BinOp::Rem => write!(f, "%"),
             BinOp::Eq => write!(f, "=="),
             BinOp::Ne => write!(f, "!="),
             BinOp::Ge => write!(f, ">="),
@@ -43,21 +46,21 @@ impl Debug for BinOp {
 impl From<mir::BinOp> for BinOp {
     fn from(value: mir::BinOp) -> Self {
         match value {
-            mir::BinOp::Add => Ok(BinOp::Add),
-            mir::BinOp::Sub => Ok(BinOp::Sub),
-            mir::BinOp::Mul => Ok(BinOp::Mul),
-            mir::BinOp::Div => Ok(BinOp::Div),
-            mir::BinOp::Eq => Ok(BinOp::Eq),
-            mir::BinOp::Ne => Ok(BinOp::Ne),
-            mir::BinOp::Le => Ok(BinOp::Le),
-            mir::BinOp::Lt => Ok(BinOp::Lt),
-            mir::BinOp::Ge => Ok(BinOp::Ge),
-            mir::BinOp::Gt => Ok(BinOp::Gt),
-            mir::BinOp::BitAnd => Ok(BinOp::And),
-            mir::BinOp::BitOr => Ok(BinOp::Or),
-            _ => Err(Error),
+            mir::BinOp::Add => BinOp::Add,
+            mir::BinOp::Sub => BinOp::Sub,
+            mir::BinOp::Mul => BinOp::Mul,
+            mir::BinOp::Div => BinOp::Div,
+            mir::BinOp::Rem => BinOp::Rem,
+            mir::BinOp::Eq => BinOp::Eq,
+            mir::BinOp::Ne => BinOp::Ne,
+            mir::BinOp::Le => BinOp::Le,
+            mir::BinOp::Lt => BinOp::Lt,
+            mir::BinOp::Ge => BinOp::Ge,
+            mir::BinOp::Gt => BinOp::Gt,
+            mir::BinOp::BitAnd => BinOp::And,
+            mir::BinOp::BitOr => BinOp::Or,
+            _ => unsupported!("Not support binary operator {value:?}"),
         }
-        .expect("Do not support")
     }
 }
 
@@ -1,8 +1,10 @@
 use num_bigint::BigInt;
 
+use super::constant::Constant;
 use super::context::*;
 use super::expr::*;
 use super::op::*;
+use crate::program::program::bigint_to_usize;
 
 impl Expr {
     pub fn simplify(&mut self) {
@@ -33,21 +35,34 @@ impl Expr {
         if self.is_index() {
             self.simplify_index();
         }
+
+        if self.is_match_variant() {
+            self.simplify_match_variant();
+        }
+
+        if self.is_copy_region() {
+            self.simplify_copy_region();
+        }
     }
 
     fn to_nnf(&mut self, is_not: bool) {
         if self.is_binary() {
             if self.extract_bin_op() == BinOp::Implies {
+                if is_not {
+                    *self = self.ctx.not(self.clone());
+                }
                 return;
             }
             let sub_exprs = self.sub_exprs().unwrap();
             let mut lhs = sub_exprs[0].clone();
             let mut rhs = sub_exprs[1].clone();
+            // Only `and` and `or` push the negation into the operands
+            let is_logic = matches!(self.extract_bin_op(), BinOp::And | BinOp::Or);
             if lhs.ty().is_bool() {
-                lhs.to_nnf(is_not);
+                lhs.to_nnf(is_not && is_logic);
             }
             if rhs.ty().is_bool() {
-                rhs.to_nnf(is_not);
+                rhs.to_nnf(is_not && is_logic);
             }
             if is_not {
                 *self = match self.extract_bin_op() {
@@ -99,7 +114,9 @@ impl Expr {
         let lhs = sub_exprs[0].clone();
         let rhs = sub_exprs[1].clone();
         match self.extract_bin_op() {
-            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => self.simplify_arith(lhs, rhs),
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem => {
+                self.simplify_arith(lhs, rhs)
+            }
             BinOp::Eq | BinOp::Ne | BinOp::Ge | BinOp::Gt | BinOp::Le | BinOp::Lt => {
                 self.simplify_cmp(lhs, rhs)
             }
@@ -108,7 +125,14 @@ impl Expr {
     }
 
     fn simplify_arith(&mut self, lhs: Expr, rhs: Expr) {
-        if lhs.is_constant() && rhs.is_constant() {
+        let is_div = matches!(self.extract_bin_op(), BinOp::Div | BinOp::Rem);
+        if is_div && rhs.is_constant() && rhs.extract_constant().to_integer() == BigInt::ZERO {
+            // Division by zero is reported by symex. Keep it.
+            *self = match self.extract_bin_op() {
+                BinOp::Div => self.ctx.div(lhs, rhs),
+                _ => self.ctx.rem(lhs, rhs),
+            };
+        } else if lhs.is_constant() && rhs.is_constant() {
             let a = lhs.extract_constant().to_integer();
             let b = rhs.extract_constant().to_integer();
             let res = match self.extract_bin_op() {
@@ -116,6 +140,7 @@ impl Expr {
                 BinOp::Sub => a - b,
                 BinOp::Mul => a * b,
                 BinOp::Div => a / b,
+                BinOp::Rem => a % b,
                 _ => todo!("Impossible"),
             };
             *self = self.ctx.constant_integer(res, self.ty());
@@ -123,7 +148,9 @@ impl Expr {
             let mut res = match self.extract_bin_op() {
                 BinOp::Add => rhs,
                 BinOp::Sub => self.ctx.neg(rhs),
-                BinOp::Mul | BinOp::Div => self.ctx.constant_integer(BigInt::ZERO, self.ty()),
+                BinOp::Mul | BinOp::Div | BinOp::Rem => {
+                    self.ctx.constant_integer(BigInt::ZERO, self.ty())
+                }
                 _ => todo!("Impossible"),
             };
             res.simplify();
@@ -132,11 +159,20 @@ impl Expr {
             let mut res = match self.extract_bin_op() {
                 BinOp::Add | BinOp::Sub => lhs,
                 BinOp::Mul => self.ctx.constant_integer(BigInt::ZERO, self.ty()),
-                BinOp::Div => panic!("Div zero"),
                 _ => todo!("Impossible"),
             };
             res.simplify();
             *self = res;
+        } else if lhs.is_constant()
+            && lhs.extract_constant().to_integer() == BigInt::from(1)
+            && self.extract_bin_op() == BinOp::Mul
+        {
+            *self = rhs;
+        } else if rhs.is_constant()
+            && rhs.extract_constant().to_integer() == BigInt::from(1)
+            && matches!(self.extract_bin_op(), BinOp::Mul | BinOp::Div)
+        {
+            *self = lhs;
         } else {
             // Build with simplified sub-exprs
             *self = match self.extract_bin_op() {
@@ -144,6 +180,7 @@ impl Expr {
                 BinOp::Sub => self.ctx.sub(lhs, rhs),
                 BinOp::Mul => self.ctx.mul(lhs, rhs),
                 BinOp::Div => self.ctx.div(lhs, rhs),
+                BinOp::Rem => self.ctx.rem(lhs, rhs),
                 _ => todo!("Impossible"),
             };
         }
@@ -176,10 +213,21 @@ impl Expr {
                     _ => todo!("Impossible"),
                 }
             } else {
+                // The only constant pointer is null
                 assert!(lhs.ty().is_any_ptr());
-                true
+                matches!(self.extract_bin_op(), BinOp::Eq | BinOp::Ge | BinOp::Le)
             };
             *self = self.ctx.constant_bool(res);
+        } else if lhs == rhs && !lhs.ty().is_float() {
+            let res = matches!(self.extract_bin_op(), BinOp::Eq | BinOp::Ge | BinOp::Le);
+            *self = self.ctx.constant_bool(res);
+        } else if lhs.ty().is_any_ptr()
+            && matches!(self.extract_bin_op(), BinOp::Eq | BinOp::Ne)
+            && (lhs.is_null() && rhs.is_address_of() || lhs.is_address_of() && rhs.is_null())
+        {
+            // The address of an object is never null
+            let res = self.extract_bin_op() == BinOp::Ne;
+            *self = self.ctx.constant_bool(res);
         } else {
             *self = match self.extract_bin_op() {
                 BinOp::Eq => self.ctx.eq(lhs, rhs),
@@ -283,8 +331,8 @@ impl Expr {
         let mut src = self.extract_src();
         let ty = self.extract_target_type();
         src.simplify();
-        if src.is_constant() && ty.is_integer() {
-            let integer = if src.ty().is_integer() {
+        if src.is_constant() && (ty.is_integer() || ty.is_char()) {
+            let integer = if src.ty().is_integer() || src.ty().is_char() {
                 src.extract_constant().to_integer()
             } else {
                 assert!(src.ty().is_any_ptr());
@@ -305,12 +353,55 @@ impl Expr {
         }
     }
 
+    fn simplify_match_variant(&mut self) {
+        let mut _enum = self.extract_enum();
+        _enum.simplify();
+        if _enum.is_enum() {
+            let res = _enum.extract_variant_idx() == self.extract_variant_idx();
+            *self = self.ctx.constant_bool(res);
+        }
+    }
+
+    /// Copying nothing keeps the array of `dst`
+    fn simplify_copy_region(&mut self) {
+        let sub_exprs = self.sub_exprs().unwrap();
+        let len = &sub_exprs[2];
+        if len.is_constant() && len.extract_constant().to_integer() == BigInt::ZERO {
+            let dst = &sub_exprs[0];
+            *self = if dst.is_slice() { dst.extract_object() } else { dst.clone() };
+        }
+    }
+
     /// Read-Write simplify
     fn simplify_index(&mut self) {
         let mut object = self.extract_object().extract_inner_expr();
         let mut index = self.extract_index();
         object.simplify();
-        if object.is_store() {
+        if object.is_constant() {
+            // Each element of a constant array is the same
+            if let Constant::Array(elem, _) = object.extract_constant() {
+                match *elem {
+                    Constant::Bool(b) => *self = self.ctx.constant_bool(b),
+                    Constant::Integer(i) => *self = self.ctx.constant_integer(i, self.ty()),
+                    _ => {}
+                }
+            }
+        } else if object.is_aggregate() && object.ty().is_array() {
+            index.simplify();
+            if index.is_constant() {
+                let i = bigint_to_usize(&index.extract_constant().to_integer());
+                let mut fields = object.extract_fields();
+                if i < fields.len() {
+                    fields[i].simplify();
+                    *self = fields.swap_remove(i);
+                }
+            }
+        } else if object.is_repeat() {
+            // Each element of a repeat array is the same
+            let mut value = object.extract_repeat_value();
+            value.simplify();
+            *self = value;
+        } else if object.is_store() {
             index.simplify();
             let mut update_index = object.extract_index();
             let mut update_value = object.extract_update_value();
@@ -322,3 +413,118 @@ impl Expr {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use rustc_smir::{run, run_driver, rustc_internal};
+    use stable_mir::ty::IntTy;
+    use z3::ast::Ast;
+
+    use super::*;
+    use crate::config::cli::Cli;
+    use crate::expr::ty::Type;
+    use crate::solvers::smt::smt_conv::Convert;
+    use crate::solvers::z3::z3_conv::Z3Conv;
+    use crate::symbol::nstring::NString;
+    use crate::symbol::symbol::Symbol;
+
+    /// A xorshift generator, which is enough for picking expressions
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+    }
+
+    /// Integers over `x`, `y` and the constants in `[-2, 2]`, which hit
+    /// the identity laws of `0` and `1` frequently
+    fn gen_int(ctx: &ExprCtx, rng: &mut Rng, depth: u32) -> Expr {
+        let ty = Type::signed_type(IntTy::I32);
+        if depth == 0 || rng.below(4) == 0 {
+            return match rng.below(3) {
+                0 => ctx.mk_symbol(Symbol::from(NString::from("x")), ty),
+                1 => ctx.mk_symbol(Symbol::from(NString::from("y")), ty),
+                _ => ctx.constant_integer(BigInt::from(rng.below(5) as i32 - 2), ty),
+            };
+        }
+        let lhs = gen_int(ctx, rng, depth - 1);
+        let rhs = gen_int(ctx, rng, depth - 1);
+        match rng.below(4) {
+            0 => ctx.add(lhs, rhs),
+            1 => ctx.sub(lhs, rhs),
+            2 => ctx.mul(lhs, rhs),
+            _ => ctx.ite(gen_bool(ctx, rng, depth - 1), lhs, rhs),
+        }
+    }
+
+    fn gen_bool(ctx: &ExprCtx, rng: &mut Rng, depth: u32) -> Expr {
+        if depth == 0 || rng.below(4) == 0 {
+            let lhs = gen_int(ctx, rng, depth.saturating_sub(1));
+            let rhs = gen_int(ctx, rng, depth.saturating_sub(1));
+            return match rng.below(7) {
+                0 => ctx.eq(lhs, rhs),
+                1 => ctx.ne(lhs, rhs),
+                2 => ctx.lt(lhs, rhs),
+                3 => ctx.le(lhs, rhs),
+                4 => ctx.gt(lhs, rhs),
+                5 => ctx.ge(lhs, rhs),
+                _ => ctx.constant_bool(rng.below(2) == 0),
+            };
+        }
+        let lhs = gen_bool(ctx, rng, depth - 1);
+        let rhs = gen_bool(ctx, rng, depth - 1);
+        match rng.below(4) {
+            0 => ctx.and(lhs, rhs),
+            1 => ctx.or(lhs, rhs),
+            2 => ctx.implies(lhs, rhs),
+            _ => ctx.not(lhs),
+        }
+    }
+
+    /// `simplify` keeps the value of random expressions, i.e., the formula
+    /// `e != simplify(e)` is unsatisfiable
+    fn check_simplify_preserves_value() {
+        let ctx = ExprCtx::new(RefCell::new(Context::new()));
+        let z3_ctx = z3::Context::new(&z3::Config::new());
+        let mut conv = Z3Conv::new(&z3_ctx, &Cli::default());
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for i in 0..500 {
+            let expr =
+                if i % 2 == 0 { gen_int(&ctx, &mut rng, 4) } else { gen_bool(&ctx, &mut rng, 4) };
+            let mut simplified = expr.clone();
+            simplified.simplify();
+            let a = conv.convert_ast(expr.clone());
+            let b = conv.convert_ast(simplified.clone());
+            let solver = z3::Solver::new(&z3_ctx);
+            solver.assert(&a._eq(&b).not());
+            assert_eq!(
+                solver.check(),
+                z3::SatResult::Unsat,
+                "{expr:?} is simplified to {simplified:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn simplify_preserves_value() {
+        // Types are only available with the compiler
+        let file = std::env::temp_dir().join("rbmc_simplify_test.rs");
+        std::fs::write(&file, "pub fn f() {}").unwrap();
+        let args = vec![
+            "rbmc".to_string(),
+            file.to_str().unwrap().to_string(),
+            "--crate-type=lib".to_string(),
+        ];
+        let res = run!(args, || {
+            check_simplify_preserves_value();
+            ControlFlow::<()>::Break(())
+        });
+        assert!(matches!(res, Err(stable_mir::CompilerError::Interrupted(()))));
+    }
+}
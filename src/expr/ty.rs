@@ -1,10 +1,16 @@
-use std::fmt::Debug;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display};
 
+use num_bigint::BigInt;
+use rustc_smir::rustc_internal;
 use stable_mir::CrateDef;
+use stable_mir::abi::FieldsShape;
 use stable_mir::mir::*;
 use stable_mir::ty::*;
 
-use crate::symbol::nstring::NString;
+use crate::bmc::error::unsupported;
+use crate::symbol::nstring::{NString, NStringBuilder};
 
 pub type Variant = Vec<FieldDef>;
 pub type Variants = Vec<(NString, Variant)>;
@@ -15,6 +21,19 @@ pub type StructDef = (NString, Vec<FieldDef>);
 pub type TupleDef = Vec<Type>;
 pub type FunctionDef = (FnDef, GenericArgs);
 
+/// Length used to mark an infinite array
+const INFINITE_ARRAY_LEN: u64 = u64::MAX;
+
+thread_local! {
+    /// Results of `num_fields` for types that have been computed
+    static NUM_FIELDS_CACHE: RefCell<HashMap<Ty, usize>> = RefCell::new(HashMap::new());
+    /// `Ty::kind` rebuilds `TyKind` in each call. Cache it for predicates
+    /// on `Type`, which keeps `Type` being `Copy`.
+    static KIND_CACHE: RefCell<HashMap<Ty, TyKind>> = RefCell::new(HashMap::new());
+    /// Adts that implement `Copy`, collected from all trait impls once
+    static COPY_ADTS: RefCell<Option<HashSet<AdtDef>>> = RefCell::new(None);
+}
+
 /// A wrapper for `Ty` in MIR
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Type(Ty);
@@ -32,6 +51,10 @@ impl Type {
         Type::from(Ty::signed_ty(ty))
     }
 
+    pub fn char_type() -> Self {
+        Type::from(Ty::from_rigid_kind(RigidTy::Char))
+    }
+
     pub fn isize_type() -> Self {
         Type::signed_type(IntTy::Isize)
     }
@@ -44,16 +67,21 @@ impl Type {
         Type::unsigned_type(UintTy::Usize)
     }
 
+    pub fn float_type(ty: FloatTy) -> Self {
+        Type::from(Ty::from_rigid_kind(RigidTy::Float(ty)))
+    }
+
     pub fn array_type(elem_ty: Type, len: u64) -> Self {
         Type::from(
             Ty::try_new_array(elem_ty.0, len)
-                .expect(format!("({elem_ty:?}, {len}) is wrong for an array type").as_str()),
+                .expect(format!("({elem_ty}, {len}) is wrong for an array type").as_str()),
         )
     }
 
     pub fn infinite_array_type(elem_ty: Type) -> Self {
-        // Array with len 0 as const array type
-        Type::array_type(elem_ty, 0)
+        // Array with len `u64::MAX` as const array type, so that
+        // it is not confused with a real zero-length array.
+        Type::array_type(elem_ty, INFINITE_ARRAY_LEN)
     }
 
     pub fn slice_type(elem_ty: Type) -> Self {
@@ -88,15 +116,19 @@ impl Type {
     }
 
     pub fn is_unit(&self) -> bool {
-        self.0.kind().is_unit()
+        self.kind().is_unit()
     }
 
     pub fn is_bool(&self) -> bool {
-        self.0.kind().is_bool()
+        self.kind().is_bool()
+    }
+
+    pub fn is_char(&self) -> bool {
+        self.kind().is_char()
     }
 
     pub fn is_signed(&self) -> bool {
-        self.0.kind().is_signed()
+        self.kind().is_signed()
     }
 
     pub fn is_isize(&self) -> bool {
@@ -104,7 +136,7 @@ impl Type {
     }
 
     pub fn is_unsigned(&self) -> bool {
-        self.0.kind().is_integral() && !self.is_signed()
+        self.kind().is_integral() && !self.is_signed()
     }
 
     pub fn is_usize(&self) -> bool {
@@ -112,82 +144,288 @@ impl Type {
     }
 
     pub fn is_integer(&self) -> bool {
-        self.0.kind().is_integral()
+        self.kind().is_integral()
+    }
+
+    pub fn is_float(&self) -> bool {
+        self.kind().is_float()
     }
 
     pub fn is_primitive(&self) -> bool {
-        self.0.kind().is_primitive()
+        self.kind().is_primitive()
     }
 
     pub fn is_enum(&self) -> bool {
-        self.0.kind().is_enum()
+        self.kind().is_enum()
     }
 
     pub fn is_array(&self) -> bool {
-        self.0.kind().is_array()
+        self.kind().is_array()
+    }
+
+    pub fn is_infinite_array(&self) -> bool {
+        self.is_array() && self.array_len() == INFINITE_ARRAY_LEN
     }
 
     pub fn is_slice(&self) -> bool {
-        self.0.kind().is_slice()
+        self.kind().is_slice()
+    }
+
+    pub fn is_str(&self) -> bool {
+        self.kind().is_str()
     }
 
     pub fn is_fn(&self) -> bool {
-        self.0.kind().is_fn()
+        self.kind().is_fn()
     }
 
     pub fn is_layout(&self) -> bool {
         self.name() == "Layout"
     }
 
+    /// Whether a value of the type carries a layout, e.g., the result of
+    /// `Layout::from_size_align`
+    pub fn contains_layout(&self) -> bool {
+        if self.is_layout() {
+            return true;
+        }
+        if self.is_enum() {
+            return self
+                .enum_def()
+                .1
+                .iter()
+                .any(|(_, fields)| fields.iter().any(|(_, ty)| ty.contains_layout()));
+        }
+        if self.is_tuple() {
+            return self.tuple_def().iter().any(|ty| ty.contains_layout());
+        }
+        false
+    }
+
     pub fn is_struct(&self) -> bool {
-        self.0.kind().is_struct() && !self.is_layout() && !self.is_box() && !self.is_vec()
+        self.kind().is_struct()
+            && !self.is_layout()
+            && !self.is_box()
+            && !self.is_vec()
+            && !self.is_rc()
     }
 
     pub fn is_tuple(&self) -> bool {
-        match self.0.kind().rigid() {
+        match self.kind().rigid() {
             Some(r) => matches!(r, RigidTy::Tuple(..)),
             None => false,
         }
     }
 
     pub fn is_ref(&self) -> bool {
-        self.0.kind().is_ref()
+        self.kind().is_ref()
     }
 
     pub fn is_ptr(&self) -> bool {
-        self.0.kind().is_raw_ptr()
+        self.kind().is_raw_ptr()
     }
 
+    /// A pointer to slice is a fat pointer. Its length is carried
+    /// in the `meta` field of the pointer datatype.
     pub fn is_slice_ptr(&self) -> bool {
         self.is_primitive_ptr() && self.pointee_ty().is_slice()
     }
 
+    /// A fat pointer carries metadata. Thin pointers always have
+    /// zero `meta`, which must not be relied on.
+    pub fn is_fat_pointer(&self) -> bool {
+        self.is_slice_ptr()
+    }
+
+    /// Element type of the slice that a fat pointer points to
+    pub fn slice_ptr_elem_type(&self) -> Type {
+        assert!(self.is_slice_ptr());
+        self.pointee_ty().elem_type()
+    }
+
     pub fn is_box(&self) -> bool {
-        self.0.kind().is_box()
+        self.kind().is_box()
     }
 
     pub fn is_vec(&self) -> bool {
         self.name() == "Vec"
     }
 
+    /// `Cell` and `UnsafeCell` are transparent wrappers. They are
+    /// structs whose only field is the wrapped value.
+    pub fn is_cell(&self) -> bool {
+        self.kind().is_struct() && (self.name() == "Cell" || self.name() == "UnsafeCell")
+    }
+
+    /// `Rc` and `Arc` are smart pointers to a shared object with a
+    /// strong count
+    pub fn is_rc(&self) -> bool {
+        self.kind().is_struct() && (self.name() == "Rc" || self.name() == "Arc")
+    }
+
+    /// Whether dropping a value of the type frees memory, i.e., the value
+    /// owns a box, a vec or an rc
+    pub fn needs_drop(&self) -> bool {
+        if self.is_smart_ptr() {
+            return true;
+        }
+        if self.is_struct() {
+            return self.struct_def().1.iter().any(|(_, ty)| ty.needs_drop());
+        }
+        if self.is_enum() {
+            return self
+                .enum_def()
+                .1
+                .iter()
+                .any(|(_, fields)| fields.iter().any(|(_, ty)| ty.needs_drop()));
+        }
+        if self.is_tuple() {
+            return self.tuple_def().iter().any(|ty| ty.needs_drop());
+        }
+        if self.is_array() && !self.is_infinite_array() {
+            return self.elem_type().needs_drop();
+        }
+        false
+    }
+
+    /// An approximation of `Copy`. Scalars, functions, raw pointers and
+    /// shared references are `Copy`. Tuples and arrays are `Copy` if all
+    /// fields are. Structs and enums must also implement `Copy`, whose
+    /// bounds are approximated by the fields.
+    pub fn is_copy(&self) -> bool {
+        if self.is_bool() || self.is_char() || self.is_integer() || self.is_float() {
+            return true;
+        }
+        if self.is_fn() || self.is_ptr() || self.is_layout() {
+            return true;
+        }
+        if self.is_ref() {
+            return !matches!(self.kind().rigid(), Some(RigidTy::Ref(_, _, Mutability::Mut)));
+        }
+        if self.is_smart_ptr() || self.is_cell() {
+            return false;
+        }
+        if (self.is_struct() || self.is_enum()) && !self.implements_copy() {
+            return false;
+        }
+        if self.is_struct() {
+            return self.struct_def().1.iter().all(|(_, ty)| ty.is_copy());
+        }
+        if self.is_enum() {
+            return self
+                .enum_def()
+                .1
+                .iter()
+                .all(|(_, fields)| fields.iter().all(|(_, ty)| ty.is_copy()));
+        }
+        if self.is_tuple() {
+            return self.tuple_def().iter().all(|ty| ty.is_copy());
+        }
+        if self.is_array() && !self.is_infinite_array() {
+            return self.elem_type().is_copy();
+        }
+        false
+    }
+
+    fn implements_copy(&self) -> bool {
+        let TyKind::RigidTy(RigidTy::Adt(def, _)) = self.kind() else {
+            return false;
+        };
+        COPY_ADTS.with(|adts| {
+            adts.borrow_mut()
+                .get_or_insert_with(|| {
+                    stable_mir::all_trait_impls()
+                        .iter()
+                        .filter_map(|impl_def| {
+                            let trait_ref = impl_def.trait_impl().value;
+                            if !trait_ref.def_id.name().ends_with("marker::Copy") {
+                                return None;
+                            }
+                            match trait_ref.self_ty().kind() {
+                                TyKind::RigidTy(RigidTy::Adt(def, _)) => Some(def),
+                                _ => None,
+                            }
+                        })
+                        .collect()
+                })
+                .contains(&def)
+        })
+    }
+
     pub fn is_primitive_ptr(&self) -> bool {
         self.is_ptr() || self.is_ref()
     }
 
     pub fn is_smart_ptr(&self) -> bool {
-        self.is_box() || self.is_vec()
+        self.is_box() || self.is_vec() || self.is_rc()
     }
 
     pub fn is_any_ptr(&self) -> bool {
         self.is_primitive_ptr() || self.is_smart_ptr()
     }
 
+    /// Scalar types in an object of this type
+    fn leaf_types(&self, leaves: &mut Vec<Type>) {
+        if self.is_array() || self.is_slice() || self.is_str() {
+            self.elem_type().leaf_types(leaves);
+        } else if self.is_struct() {
+            for (_, ty) in self.struct_def().1 {
+                ty.leaf_types(leaves);
+            }
+        } else if self.is_tuple() {
+            for ty in self.tuple_def() {
+                ty.leaf_types(leaves);
+            }
+        } else if !leaves.contains(self) {
+            leaves.push(*self);
+        }
+    }
+
+    /// Whether a pointer may point into objects of both types. That is,
+    /// they share a scalar type, or one of them contains bytes or enums.
+    pub fn may_alias(&self, other: Type) -> bool {
+        let (mut l1, mut l2) = (Vec::new(), Vec::new());
+        self.leaf_types(&mut l1);
+        other.leaf_types(&mut l2);
+        let opaque = |ty: &Type| {
+            ty.is_enum()
+                || matches!(
+                    ty.kind().rigid(),
+                    Some(RigidTy::Int(IntTy::I8)) | Some(RigidTy::Uint(UintTy::U8))
+                )
+        };
+        l1.iter().any(|t| opaque(t) || l2.contains(t)) || l2.iter().any(opaque)
+    }
+
+    fn kind(&self) -> TyKind {
+        if let Some(kind) = KIND_CACHE.with_borrow(|cache| cache.get(&self.0).cloned()) {
+            return kind;
+        }
+        let kind = self.0.kind();
+        KIND_CACHE.with_borrow_mut(|cache| cache.insert(self.0, kind.clone()));
+        kind
+    }
+
     /// Size will be in field-level
     pub fn num_fields(&self) -> usize {
-        if self.is_unit() {
+        if let Some(n) = NUM_FIELDS_CACHE.with_borrow(|cache| cache.get(&self.0).copied()) {
+            return n;
+        }
+        let n = self.compute_num_fields();
+        NUM_FIELDS_CACHE.with_borrow_mut(|cache| cache.insert(self.0, n));
+        n
+    }
+
+    fn compute_num_fields(&self) -> usize {
+        if self.is_unit() || self.is_zst() {
             return 0;
         }
-        if self.is_bool() || self.is_integer() || self.is_any_ptr() {
+        if self.is_bool()
+            || self.is_char()
+            || self.is_integer()
+            || self.is_float()
+            || self.is_any_ptr()
+        {
             return 1;
         }
 
@@ -208,45 +446,95 @@ impl Type {
         }
 
         if self.is_enum() {
-            let mut mx = 1;
-            for variant in self.enum_def().1 {
-                mx = std::cmp::max(mx, variant.1.len());
+            // Discriminant plus the largest variant. See `variant_field_offset`
+            let mut mx = 0;
+            for variant in self.variant_defs().1 {
+                mx = std::cmp::max(mx, variant.1.iter().fold(0, |acc, x| acc + x.1.num_fields()));
+            }
+            return 1 + mx;
+        }
+
+        unsupported!("num_fields of {self}")
+    }
+
+    /// Byte offsets of fields in the layout, following the order of
+    /// definition. Fields may be reordered in memory.
+    pub fn field_byte_offsets(&self) -> Vec<u64> {
+        match self.0.layout().unwrap().shape().fields {
+            FieldsShape::Arbitrary { offsets } => {
+                offsets.iter().map(|o| o.bytes() as u64).collect()
             }
-            return mx;
+            _ => panic!("{self} does not have fields"),
+        }
+    }
+
+    /// Zero-sized types, such as unit, `[T; 0]` and empty structs
+    pub fn is_zst(&self) -> bool {
+        self.size_in_bytes() == Some(0)
+    }
+
+    /// Size in bytes from the layout. `None` for unsized types.
+    pub fn size_in_bytes(&self) -> Option<u64> {
+        let shape = self.0.layout().ok()?.shape();
+        if shape.is_unsized() { None } else { Some(shape.size.bytes() as u64) }
+    }
+
+    /// The minimum and maximum values of an integer type
+    pub fn integer_range(&self) -> (BigInt, BigInt) {
+        assert!(self.is_integer());
+        let bits = self.size_in_bytes().expect("Integer must be sized") * 8;
+        if self.is_signed() {
+            let bound = BigInt::from(1) << (bits - 1);
+            (-bound.clone(), bound - 1)
+        } else {
+            (BigInt::ZERO, (BigInt::from(1) << bits) - 1)
         }
+    }
 
-        todo!("{self:?}")
+    /// Alignment in bytes from the layout
+    pub fn align(&self) -> Option<u64> {
+        Some(self.0.layout().ok()?.shape().abi_align)
     }
 
     pub fn pointee_ty(&self) -> Self {
         assert!(self.is_any_ptr());
-        match self.0.kind() {
+        match self.kind() {
             TyKind::RigidTy(r) => match r {
-                RigidTy::Adt(def, args) => {
-                    let elem_ty = match &args.0[0] {
-                        GenericArgKind::Type(ty) => Type::from(ty.clone()),
-                        _ => panic!(),
-                    };
-                    if self.is_box() { elem_ty } else { Type::infinite_array_type(elem_ty) }
+                RigidTy::Adt(_, args) => {
+                    // The first type argument is the pointee. The rest of
+                    // them, e.g. the allocator in `Box<T, A>`, are skipped.
+                    let elem_ty = args
+                        .0
+                        .iter()
+                        .find_map(|arg| match arg {
+                            GenericArgKind::Type(ty) => Some(Type::from(ty)),
+                            _ => None,
+                        })
+                        .expect(format!("{self} has no pointee type").as_str());
+                    if self.is_vec() { Type::infinite_array_type(elem_ty) } else { elem_ty }
                 }
                 RigidTy::RawPtr(ty, ..) | RigidTy::Ref(_, ty, ..) => Type::from(ty),
-                _ => panic!(),
+                _ => unsupported!("pointee type of {self}"),
             },
-            _ => panic!(),
+            _ => unsupported!("pointee type of {self}"),
         }
     }
 
+    /// `None` for infinite array
     pub fn array_size(&self) -> Option<u64> {
+        if self.is_infinite_array() { None } else { Some(self.array_len()) }
+    }
+
+    fn array_len(&self) -> u64 {
         assert!(self.is_array());
-        let size = match self.0.kind() {
+        match self.kind() {
             TyKind::RigidTy(r) => match r {
                 RigidTy::Array(_, c) => c.eval_target_usize(),
                 _ => panic!("Not array"),
             },
             _ => panic!("Not array"),
         }
-        .expect("Wrong array size");
-        if size == 0 { None } else { Some(size) }
+        .expect("Wrong array size")
     }
 
     /// Assume that all index is integer.
@@ -255,12 +543,13 @@ impl Type {
         Type::unsigned_type(UintTy::Usize)
     }
 
-    /// Range for array/slice
+    /// Range for array/slice/str
     pub fn elem_type(&self) -> Type {
-        assert!(self.is_array() || self.is_slice());
-        if let TyKind::RigidTy(r) = self.0.kind() {
+        assert!(self.is_array() || self.is_slice() || self.is_str());
+        if let TyKind::RigidTy(r) = self.kind() {
             return match r {
                 RigidTy::Array(t, _) | RigidTy::Slice(t) => Type::from(t),
+                RigidTy::Str => Type::unsigned_type(UintTy::U8),
                 _ => panic!("Impossible"),
             };
         }
@@ -270,7 +559,7 @@ impl Type {
     pub fn enum_def(&self) -> EnumDef {
         assert!(self.is_enum());
         let mut def = (self.name(), Vec::new());
-        if let TyKind::RigidTy(r) = self.0.kind() {
+        if let TyKind::RigidTy(r) = self.kind() {
             if let RigidTy::Adt(adt, args) = r {
                 def.0 = NString::from(adt.trimmed_name());
                 for variant in adt.variants() {
@@ -292,9 +581,64 @@ impl Type {
         def
     }
 
+    /// The type of the discriminant, e.g., `isize` by default and `u8`
+    /// for `#[repr(u8)]`
+    pub fn discriminant_type(&self) -> Type {
+        assert!(self.is_enum());
+        Type::from(self.kind().discriminant_ty().expect("Enum must be rigid"))
+    }
+
+    /// The discriminant of each variant, which may be given explicitly,
+    /// e.g., `A = 3`. They are evaluated by the compiler, for Stable MIR
+    /// does not provide them.
+    pub fn discriminant_values(&self) -> Vec<BigInt> {
+        let TyKind::RigidTy(RigidTy::Adt(adt, _)) = self.kind() else {
+            panic!("{self} is not an enum")
+        };
+        let discr_ty = self.discriminant_type();
+        let bits = 8 * discr_ty.size_in_bytes().unwrap();
+        let bits_values = rustc_middle::ty::tls::with(|tcx| {
+            let adt = rustc_internal::internal(tcx, adt);
+            adt.discriminants(tcx).map(|(_, discr)| discr.val).collect::<Vec<_>>()
+        });
+        bits_values
+            .into_iter()
+            .map(|val| {
+                let val = BigInt::from(val);
+                // The bits of a negative discriminant
+                if discr_ty.is_signed() && val.bit(bits - 1) {
+                    val - (BigInt::from(1) << bits)
+                } else {
+                    val
+                }
+            })
+            .collect()
+    }
+
+    /// Variants with their original fields
+    pub fn variant_defs(&self) -> EnumDef {
+        assert!(self.is_enum());
+        let mut def = (self.name(), Vec::new());
+        if let TyKind::RigidTy(r) = self.kind() {
+            if let RigidTy::Adt(adt, args) = r {
+                for variant in adt.variants() {
+                    let fields = variant
+                        .fields()
+                        .iter()
+                        .map(|fdef| {
+                            (NString::from(fdef.name.clone()), Type(fdef.ty_with_args(&args)))
+                        })
+                        .collect::<Vec<_>>();
+                    def.1.push((NString::from(variant.name()), fields));
+                }
+            }
+        }
+        def
+    }
+
     pub fn enum_variant_data_type(&self, variant_idx: usize) -> Self {
         assert!(self.is_enum());
-        if let TyKind::RigidTy(r) = self.0.kind() {
+        if let TyKind::RigidTy(r) = self.kind() {
             if let RigidTy::Adt(adt, args) = r {
                 let variants = adt.variants();
                 assert!(variant_idx < variants.len());
@@ -311,10 +655,16 @@ impl Type {
         panic!("Impossible")
     }
 
+    /// Field-level offset of the `i`-th field in the data of the `k`-th
+    /// variant. The discriminant is at offset 0 and the data follows it.
+    pub fn variant_field_offset(&self, k: usize, i: usize) -> usize {
+        1 + self.enum_variant_data_type(k).tuple_elem_offset(i)
+    }
+
     pub fn struct_def(&self) -> StructDef {
         assert!(self.is_struct());
         let mut def = (self.name(), Vec::new());
-        if let TyKind::RigidTy(r) = self.0.kind() {
+        if let TyKind::RigidTy(r) = self.kind() {
             if let RigidTy::Adt(adt, args) = r {
                 for field in adt.variants()[0].fields() {
                     let fty = field.ty_with_args(&args);
@@ -322,12 +672,48 @@ impl Type {
                 }
             }
         }
-        assert!(!def.1.is_empty());
+        assert!(!def.1.is_empty() || self.is_zst());
         def
     }
 
+    /// Field-level offset of `field` in the struct
+    pub fn field_offset(&self, field: NString) -> usize {
+        let def = self.struct_def();
+        let i = def
+            .1
+            .iter()
+            .position(|(name, _)| *name == field)
+            .expect(format!("{field:?} is not a field of {:?}", def.0).as_str());
+        self.struct_field_offset(i)
+    }
+
+    /// Indices of fields in the struct or tuple, sorted by their byte
+    /// offsets. Fields are reordered in memory unless the struct is `repr(C)`.
+    pub fn field_order(&self) -> Vec<usize> {
+        let offsets = self.field_byte_offsets();
+        let mut order = (0..offsets.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| offsets[i]);
+        order
+    }
+
+    /// Field-level offset of the `i`-th field in the struct, that is, the sum
+    /// of `num_fields` of the fields placed before it in memory
+    pub fn struct_field_offset(&self, i: usize) -> usize {
+        let def = self.struct_def();
+        let order = self.field_order();
+        let pos = order.iter().position(|&j| j == i).expect("Field out of range");
+        order[..pos].iter().fold(0, |acc, &j| acc + def.1[j].1.num_fields())
+    }
+
+    /// Index of the field of the struct at the beginning of its memory
+    pub fn struct_first_field(&self) -> usize {
+        let def = self.struct_def();
+        let order = self.field_order();
+        *order.iter().find(|&&j| !def.1[j].1.is_zst()).unwrap_or(&order[0])
+    }
+
     pub fn tuple_def(&self) -> TupleDef {
-        match self.0.kind().rigid() {
+        match self.kind().rigid() {
             Some(r) => match r {
                 RigidTy::Tuple(fields) => fields.iter().map(|t| Type::from(t)).collect::<Vec<_>>(),
                 _ => panic!("Not tuple"),
@@ -336,44 +722,107 @@ impl Type {
         }
     }
 
+    /// Field-level offset of the `index`-th element in the tuple, where the
+    /// elements are placed in the order of the layout as fields of a struct
+    pub fn tuple_elem_offset(&self, index: usize) -> usize {
+        let def = self.tuple_def();
+        assert!(index < def.len(), "{index} is out of {self}");
+        let order = self.field_order();
+        let pos = order.iter().position(|&j| j == index).unwrap();
+        order[..pos].iter().fold(0, |acc, &j| acc + def[j].num_fields())
+    }
+
     pub fn fn_def(&self) -> FunctionDef {
         assert!(self.is_fn());
-        let kind = self.0.kind();
+        let kind = self.kind();
         let _def = kind.fn_def().unwrap();
         (_def.0, _def.1.clone())
     }
 
     pub fn name(&self) -> NString {
-        match self.0.kind().rigid().unwrap() {
+        self.try_name().unwrap_or_else(|| unsupported!("name of {self:?}"))
+    }
+
+    /// The resolved name, or `None` if the type or any type in it has no
+    /// name. It never panics, thus it is safe to use in formatting.
+    fn try_name(&self) -> Option<NString> {
+        let name = match self.kind().rigid()? {
             RigidTy::Bool => "bool".into(),
             RigidTy::Char => "char".into(),
+            RigidTy::Str => "str".into(),
             RigidTy::Int(i) => format!("{i:?}").to_lowercase().into(),
             RigidTy::Uint(i) => format!("{i:?}").to_lowercase().into(),
+            RigidTy::Float(f) => format!("{f:?}").to_lowercase().into(),
             RigidTy::Adt(def, _) => def.trimmed_name().into(),
-            RigidTy::Array(ty, ..) => format!("Array({:?})", Type(*ty).name()).into(),
-            RigidTy::Slice(ty) => format!("Slice({:?})", Type(*ty).name()).into(),
-            RigidTy::RawPtr(ty, ..) => format!("Ptr({:?})", Type(*ty).name()).into(),
-            RigidTy::Ref(_, ty, _) => format!("Ref({:?})", Type(*ty).name()).into(),
+            RigidTy::Array(ty, ..) => format!("Array({:?})", Type(*ty).try_name()?).into(),
+            RigidTy::Slice(ty) => format!("Slice({:?})", Type(*ty).try_name()?).into(),
+            RigidTy::RawPtr(ty, ..) => format!("Ptr({:?})", Type(*ty).try_name()?).into(),
+            RigidTy::Ref(_, ty, _) => format!("Ref({:?})", Type(*ty).try_name()?).into(),
+            // Instances of a generic function differ in the generic arguments
+            RigidTy::FnDef(def, args) => {
+                format!("FnDef({}{})", def.name(), Type::generic_args_name(args)?).into()
+            }
+            RigidTy::FnPtr(sig) => {
+                let sig = sig.clone().skip_binder();
+                let inputs = sig
+                    .inputs()
+                    .iter()
+                    .map(|ty| Some(format!("{:?}", Type(*ty).try_name()?)))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("FnPtr({})->{:?}", inputs.join(","), Type(sig.output()).try_name()?).into()
+            }
+            // Closures are distinguished by their def id
+            RigidTy::Closure(def, _) => {
+                format!("Closure({}#{})", def.name(), def.def_id().to_index()).into()
+            }
             RigidTy::Never => "never".into(),
             RigidTy::Tuple(f) => {
                 if f.is_empty() {
                     "unit".into()
                 } else {
-                    let mut name = NString::from("_tuple");
+                    let mut name = NStringBuilder::new("_tuple");
                     for ty in f {
-                        name += format!("_{:?}", Type(*ty).name());
+                        name.push_str("_").push(Type(*ty).try_name()?);
                     }
-                    name
+                    name.build()
                 }
             }
-            _ => todo!(),
-        }
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// The names of the type and const arguments, e.g., `<u8,4>`. It is
+    /// empty if there is no such argument.
+    fn generic_args_name(args: &GenericArgs) -> Option<String> {
+        let names = args
+            .0
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgKind::Type(ty) => Some(Type(*ty).try_name().map(|n| format!("{n:?}"))),
+                GenericArgKind::Const(c) => Some(c.eval_target_usize().ok().map(|n| n.to_string())),
+                GenericArgKind::Lifetime(_) => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(if names.is_empty() { String::new() } else { format!("<{}>", names.join(",")) })
     }
 }
 
+/// `{:?}` prints the raw `Ty` and `{:#?}` prints the resolved name
 impl Debug for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        if f.alternate() { write!(f, "{self}") } else { write!(f, "{}", self.0) }
+    }
+}
+
+/// The resolved name, e.g., `Ptr(u8)` and `_tuple_i32_bool`. Types
+/// without a name fall back to the raw `Ty`.
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.try_name() {
+            Some(name) => write!(f, "{name:?}"),
+            None => write!(f, "{}", self.0),
+        }
     }
 }
 
@@ -389,3 +838,119 @@ impl From<&Ty> for Type {
         Type::from(*value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rustc_smir::{run, run_driver, rustc_internal};
+    use stable_mir::CrateItem;
+
+    use super::*;
+
+    /// Run `check` on the crate of `src`. Types are only available with
+    /// the compiler.
+    fn check_in_crate(test: &str, src: &str, check: fn()) {
+        let file = std::env::temp_dir().join(format!("rbmc_{test}_test.rs"));
+        std::fs::write(&file, src).unwrap();
+        let args = vec![
+            "rbmc".to_string(),
+            file.to_str().unwrap().to_string(),
+            "--crate-type=lib".to_string(),
+        ];
+        let res = run!(args, || {
+            check();
+            ControlFlow::<()>::Break(())
+        });
+        assert!(matches!(res, Err(stable_mir::CompilerError::Interrupted(()))));
+    }
+
+    fn local_item(name: &str) -> CrateItem {
+        let items = stable_mir::all_local_items();
+        *items.iter().find(|item| item.trimmed_name() == name).unwrap()
+    }
+
+    /// Types of the arguments of the local function `name`
+    fn arg_types(name: &str) -> Vec<Type> {
+        local_item(name).body().arg_locals().iter().map(|local| Type::from(local.ty)).collect()
+    }
+
+    #[test]
+    fn field_offset_of_nested_struct() {
+        let src = "
+            #[repr(C)]
+            pub struct Inner { pub a: u8, pub b: u16 }
+            #[repr(C)]
+            pub struct Outer { pub inner: Inner, pub lo: u16, pub hi: u32 }
+            pub struct Reordered { pub a: u8, pub b: u32, pub c: u8 }
+            pub fn f(_o: Outer, _r: Reordered) {}
+        ";
+        check_in_crate("field_offset", src, || {
+            let types = arg_types("f");
+            let outer = types[0];
+            assert_eq!(outer.field_byte_offsets(), [0, 4, 8]);
+            // `inner` takes the first two fields
+            assert_eq!(outer.field_offset("inner".into()), 0);
+            assert_eq!(outer.field_offset("lo".into()), 2);
+            assert_eq!(outer.field_offset("hi".into()), 3);
+            // `b` is placed first in memory
+            let reordered = types[1];
+            assert_eq!(reordered.field_byte_offsets(), [4, 0, 5]);
+            assert_eq!(reordered.field_offset("a".into()), 1);
+            assert_eq!(reordered.field_offset("b".into()), 0);
+            assert_eq!(reordered.field_offset("c".into()), 2);
+        });
+    }
+
+    #[test]
+    fn elem_offset_of_tuple() {
+        let src = "
+            pub struct Inner { pub a: u8, pub b: u16 }
+            pub fn f(_t: (Inner, i32), _u: (u8, u32, u8)) {}
+        ";
+        check_in_crate("elem_offset", src, || {
+            let types = arg_types("f");
+            // `Inner` takes the first two fields
+            let t = types[0];
+            assert_eq!(t.field_byte_offsets(), [0, 4]);
+            assert_eq!(t.tuple_elem_offset(0), 0);
+            assert_eq!(t.tuple_elem_offset(1), 2);
+            // The `u32` is placed first in memory
+            let u = types[1];
+            assert_eq!(u.field_byte_offsets(), [4, 0, 5]);
+            assert_eq!(u.tuple_elem_offset(0), 1);
+            assert_eq!(u.tuple_elem_offset(1), 0);
+            assert_eq!(u.tuple_elem_offset(2), 2);
+        });
+    }
+
+    #[test]
+    fn pointee_of_boxed_slice() {
+        let src = "
+            #![feature(allocator_api)]
+            pub fn f(_b: Box<[u8]>, _a: Box<[u8], std::alloc::Global>, _r: &[u8]) {}
+        ";
+        check_in_crate("boxed_slice", src, || {
+            let types = arg_types("f");
+            for b in &types[..2] {
+                assert!(b.is_box());
+                assert!(b.pointee_ty().is_slice());
+                assert!(b.pointee_ty().elem_type() == Type::unsigned_type(UintTy::U8));
+                // Only raw pointers and references are fat pointers
+                assert!(!b.is_slice_ptr());
+            }
+            assert!(types[2].is_slice_ptr());
+        });
+    }
+
+    #[test]
+    fn fn_def_name_has_generic_args() {
+        check_in_crate("fn_def_name", "pub fn id<T>(x: T) -> T { x }", || {
+            let (def, _) = local_item("id").ty().kind().fn_def().unwrap();
+            let name = |ty: Ty| {
+                let args = GenericArgs(vec![GenericArgKind::Type(ty)]);
+                Type::from(Ty::from_rigid_kind(RigidTy::FnDef(def, args))).name()
+            };
+            assert!(name(Ty::unsigned_ty(UintTy::U8)) == "FnDef(id<u8>)");
+            assert!(name(Ty::unsigned_ty(UintTy::U32)) == "FnDef(id<u32>)");
+        });
+    }
+}
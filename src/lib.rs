@@ -15,3 +15,18 @@ mod solvers;
 mod symbol;
 mod symex;
 mod vc;
+
+pub use expr::ty::FunctionDef;
+
+use bmc::bmc::Bmc;
+use bmc::result::VerificationResult;
+use config::cli::Cli;
+use config::config::Config;
+
+/// Verify the function `def` with the options `cli`, where `def` is
+/// instantiated with its generic arguments. It must be called in the
+/// callback of `rustc_smir::run!`, which provides Stable MIR.
+pub fn verify_function(def: FunctionDef, cli: Cli) -> VerificationResult {
+    let config = Config::with_entry(cli, def);
+    Bmc::new(&config).do_bmc()
+}
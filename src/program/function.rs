@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use stable_mir::mir::mono::Instance;
 use stable_mir::mir::*;
-use stable_mir::ty::FnDef;
+use stable_mir::ty::{FnDef, GenericArgKind, GenericArgs};
 use stable_mir::*;
 
 use crate::expr::ty::*;
@@ -14,6 +15,27 @@ pub type Pc = BasicBlockIdx;
 pub type Loop = HashSet<Pc>;
 pub type LoopSet = HashMap<Pc, Loop>;
 
+/// Whether there are type or const arguments. Lifetimes are erased.
+pub fn is_generic(args: &GenericArgs) -> bool {
+    args.0.iter().any(|arg| !matches!(arg, GenericArgKind::Lifetime(_)))
+}
+
+/// Whether the function has generic parameters
+pub fn is_generic_def(def: FnDef) -> bool {
+    let ty = Type::from(def.ty());
+    is_generic(&ty.fn_def().1)
+}
+
+/// The name of the function instantiated with its generic arguments,
+/// e.g., `id::<i32>`. It is the trimmed name for non-generic functions.
+pub fn instance_name(def: &FunctionDef) -> NString {
+    if !is_generic(&def.1) {
+        return NString::from(def.0.trimmed_name());
+    }
+    let instance = Instance::resolve(def.0, &def.1).expect("Fail to resolve the instance");
+    NString::from(instance.trimmed_name())
+}
+
 /// A wrapper for functiom item in MIR
 #[derive(Debug)]
 pub struct Function {
@@ -29,10 +51,23 @@ pub struct Function {
 
 impl Function {
     pub fn new(def: FnDef) -> Self {
-        let body = def.body().unwrap();
+        Function::with_body(NString::from(def.trimmed_name()), def.body().unwrap())
+    }
+
+    /// The function instantiated with its generic arguments
+    pub fn instance(def: FunctionDef) -> Self {
+        if !is_generic(&def.1) {
+            return Function::new(def.0);
+        }
+        let instance = Instance::resolve(def.0, &def.1).expect("Fail to resolve the instance");
+        let body = instance.body().expect("No body for the instance");
+        Function::with_body(NString::from(instance.trimmed_name()), body)
+    }
+
+    fn with_body(name: NString, body: Body) -> Self {
         let mut function = Function {
-            name: NString::from(def.trimmed_name()),
-            args: (1..def.body().unwrap().arg_locals().len() + 1).collect(),
+            name,
+            args: (1..body.arg_locals().len() + 1).collect(),
             body,
             _local_alive: HashSet::new(),
             _loops: HashMap::new(),
@@ -146,6 +181,20 @@ impl Function {
     pub fn rvalue_type(&self, rvalue: &Rvalue) -> Type {
         Type::from(rvalue.ty(self.body.locals()).expect("Wrong rvalue"))
     }
+
+    /// Generic functions called in the body with their generic arguments
+    pub fn generic_callees(&self) -> Vec<FunctionDef> {
+        let mut callees = Vec::new();
+        for bb in self.body.blocks.iter() {
+            if let TerminatorKind::Call { func, .. } = &bb.terminator.kind {
+                let ty = self.operand_type(func);
+                if ty.is_fn() && is_generic(&ty.fn_def().1) {
+                    callees.push(ty.fn_def());
+                }
+            }
+        }
+        callees
+    }
 }
 
 impl PartialEq for Function {
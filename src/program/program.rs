@@ -8,6 +8,7 @@ use stable_mir::target::*;
 use stable_mir::*;
 
 use super::function::*;
+use crate::expr::ty::FunctionDef;
 use crate::symbol::nstring::NString;
 
 pub struct Program {
@@ -18,19 +19,43 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn new(_crate: Crate) -> Self {
-        let mut functions = Vec::new();
-        let mut idx = HashMap::new();
-        for def in _crate.fn_defs() {
-            if def.trimmed_name() == "main" {
-                functions.push(Function::new(def.clone()));
+    /// The entry is `main` unless the path of another function is given,
+    /// e.g., `path::to::fn`. The path may start with the crate name. It is
+    /// an error if the given entry is not found or is generic.
+    pub fn new(_crate: Crate, entry: NString) -> std::result::Result<Self, String> {
+        let path = if entry.is_empty() { NString::from("main") } else { entry };
+        let full_path = format!("{}::{path:?}", _crate.name);
+        let def = _crate.fn_defs().into_iter().find(|def| {
+            path == def.trimmed_name() || path == def.name() || def.name() == full_path
+        });
+        if !entry.is_empty() {
+            let Some(def) = def else {
+                return Err(format!("can not find the entry function `{entry:?}`"));
+            };
+            if is_generic_def(def) {
+                return Err(format!("the entry function `{entry:?}` must not be generic"));
             }
         }
+        Ok(Program::build(_crate, def.map(Function::new)))
+    }
+
+    /// The program whose entry is the function `entry`
+    pub fn with_entry(_crate: Crate, entry: FunctionDef) -> Self {
+        Program::build(_crate, Some(Function::instance(entry)))
+    }
+
+    /// The entry function is the first one
+    fn build(_crate: Crate, entry: Option<Function>) -> Self {
+        let mut functions = Vec::new();
+        let mut idx = HashMap::new();
+        let entry_name = entry.as_ref().map(|f| f.name());
+        functions.extend(entry);
         for def in _crate.fn_defs() {
-            if def.trimmed_name() == "main" {
+            if entry_name == Some(NString::from(def.trimmed_name())) {
                 continue;
             }
-            if !def.has_body() {
+            // Generic functions are analyzed by their instances
+            if !def.has_body() || is_generic_def(def) {
                 continue;
             }
             functions.push(Function::new(def));
@@ -38,6 +63,23 @@ impl Program {
         for (i, function) in functions.iter_mut().enumerate() {
             idx.insert(function.name().clone(), i);
         }
+        // Monomorphize generic functions of the crate at call sites,
+        // including the ones called by instances
+        let mut i = 0;
+        while i < functions.len() {
+            for callee in functions[i].generic_callees() {
+                if !callee.0.krate().is_local || !callee.0.has_body() {
+                    continue;
+                }
+                let name = instance_name(&callee);
+                if idx.contains_key(&name) {
+                    continue;
+                }
+                idx.insert(name, functions.len());
+                functions.push(Function::instance(callee));
+            }
+            i += 1;
+        }
         Program {
             crate_name: _crate.name.clone().into(),
             static_variables: _crate.statics(),
@@ -59,12 +101,13 @@ impl Program {
         self.functions.len()
     }
 
-    pub fn function_idx(&self, name: NString) -> FunctionIdx {
-        *self.idx.get(&name).expect("Not exists")
-    }
-
-    pub fn contains_function(&self, name: NString) -> bool {
-        self.idx.contains_key(&name)
+    /// The function of the crate called by `def`. Generic functions are
+    /// identified by their instances.
+    pub fn callee(&self, def: &FunctionDef) -> Option<FunctionIdx> {
+        if is_generic(&def.1) && !def.0.krate().is_local {
+            return None;
+        }
+        self.idx.get(&instance_name(def)).copied()
     }
 
     pub fn show(&self) {
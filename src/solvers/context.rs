@@ -1,4 +1,4 @@
-use crate::config::cli::Cli;
+use crate::config::cli::{Cli, SolverKind};
 
 pub enum SolverCtx {
     Z3(z3::Context),
@@ -6,10 +6,8 @@ pub enum SolverCtx {
 
 impl SolverCtx {
     pub fn new(cli: &Cli) -> Self {
-        if cli.solver == "z3" {
-            SolverCtx::Z3(z3::Context::new(&z3::Config::new()))
-        } else {
-            panic!("Not support for solve {:?}", cli.solver)
+        match cli.solver {
+            SolverKind::Z3 => SolverCtx::Z3(z3::Context::new(&z3::Config::new())),
         }
     }
 }
@@ -1,13 +1,15 @@
 use std::fmt::Debug;
+use std::path::Path;
 
 use num_bigint::BigInt;
 
+use crate::bmc::error::unsupported;
 use crate::expr::constant::Constant;
 use crate::expr::expr::*;
 use crate::expr::op::*;
 use crate::expr::ty::*;
 use crate::program::program::bigint_to_usize;
-use crate::solvers::solver::PResult;
+use crate::solvers::solver::{PResult, SolverStats};
 use crate::symbol::nstring::NString;
 
 pub(crate) trait SmtSolver<'ctx> {
@@ -15,9 +17,28 @@ pub(crate) trait SmtSolver<'ctx> {
     fn assert_assign(&mut self, lhs: Expr, rhs: Expr);
     fn assert_expr(&mut self, expr: Expr);
     fn reset(&mut self);
-    fn check(&self) -> PResult;
+    /// Open a scope. Assertions after it are removed by `pop`.
+    fn push(&mut self);
+    fn pop(&mut self);
+    fn check(&mut self) -> PResult;
+    /// Statistics accumulated over all checks
+    fn stats(&self) -> SolverStats;
     fn eval_bool(&self, expr: Expr) -> bool;
+    /// Value of expr in the model. `None` if it is not in solver.
+    fn eval_expr(&self, expr: Expr) -> Option<NString>;
+    /// Value of a condition in the model. It is converted if it is not
+    /// in solver, but never asserted.
+    fn eval_cond(&mut self, expr: Expr) -> Option<bool>;
+    /// The tracked assertions in the unsat core of the last check. It
+    /// is empty if they are not tracked.
+    fn unsat_core(&self) -> Vec<NString>;
     fn show_model(&self);
+    /// Each object space with its base, length, liveness and contents
+    fn show_object_spaces(&self);
+    /// Current assertions in SMT-LIB2 format
+    fn smtlib(&self) -> String;
+    /// Write current assertions in SMT-LIB2 format
+    fn dump_smtlib(&self, path: &Path);
 }
 
 pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
@@ -30,7 +51,8 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.mk_bool_sort();
         }
 
-        if ty.is_integer() {
+        // A `char` is its code point
+        if ty.is_integer() || ty.is_char() {
             return self.mk_int_sort();
         }
 
@@ -38,7 +60,8 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.mk_pointer_sort();
         }
 
-        if ty.is_box() {
+        // An rc is a box with a strong count in symex
+        if ty.is_box() || ty.is_rc() {
             return self.mk_box_sort();
         }
 
@@ -64,7 +87,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.convert_enum_sort(ty);
         }
 
-        panic!("Not support {:?} yet", ty);
+        unsupported!("Not support {ty} yet");
     }
 
     fn convert_struct_sort(&mut self, ty: Type) -> Sort;
@@ -84,6 +107,8 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             && !expr.is_store()
             && !expr.is_match_variant()
             && !expr.is_as_variant()
+            && !expr.is_object_size()
+            && !expr.is_copy_region()
         {
             if let Some(sub_exrps) = expr.sub_exprs() {
                 for e in sub_exrps {
@@ -99,7 +124,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
 
         if expr.is_address_of() {
             let object = expr.extract_object();
-            a = Some(self.convert_address_of(object));
+            a = Some(self.convert_address_of(object, expr.ty()));
         }
 
         if expr.is_aggregate() {
@@ -112,7 +137,10 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             };
         }
 
-        if expr.is_binary() {
+        if expr.is_binary() && expr.sub_exprs().unwrap()[0].ty().is_primitive_ptr() {
+            let ty = expr.sub_exprs().unwrap()[0].ty();
+            a = Some(self.convert_pointer_compare(expr.extract_bin_op(), &args[0], &args[1], ty));
+        } else if expr.is_binary() {
             let lhs = &args[0];
             let rhs = &args[1];
             a = Some(match expr.extract_bin_op() {
@@ -120,6 +148,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
                 BinOp::Sub => self.mk_sub(lhs, rhs),
                 BinOp::Mul => self.mk_mul(lhs, rhs),
                 BinOp::Div => self.mk_div(lhs, rhs),
+                BinOp::Rem => self.mk_rem(lhs, rhs),
                 BinOp::Eq => self.mk_eq(lhs, rhs),
                 BinOp::Ne => self.mk_ne(lhs, rhs),
                 BinOp::Ge => self.mk_ge(lhs, rhs),
@@ -135,7 +164,8 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
         if expr.is_unary() {
             a = Some(match expr.extract_un_op() {
                 UnOp::Not => self.mk_not(&args[0]),
-                _ => panic!("Not support"),
+                UnOp::Neg => self.mk_neg(&args[0]),
+                _ => unsupported!("Not support"),
             });
         }
 
@@ -173,14 +203,13 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             a = Some(self.convert_store(object, index, value));
         }
 
+        if expr.is_repeat() {
+            let domain = self.convert_sort(expr.ty().array_domain());
+            a = Some(self.mk_smt_const_array(&domain, &args[0]));
+        }
+
         if expr.is_offset() {
-            let pt = args[0].clone();
-            let base = self.convert_pointer_base(&pt);
-            let o1 = self.convert_pointer_offset(&pt);
-            let o2 = args[1].clone();
-            let offset = self.mk_add(&o1, &o2);
-            let meta = self.convert_pointer_meta(&pt);
-            a = Some(self.convert_pointer(&base, &offset, Some(&meta)));
+            a = Some(self.convert_pointer_add(&args[0], &args[1], expr.ty().is_fat_pointer()));
         }
 
         if expr.is_pointer_base() {
@@ -216,6 +245,15 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             a = Some(self.convert_inner_pointer(&args[0], ty));
         }
 
+        if expr.is_object_size() {
+            a = Some(self.convert_object_size(&expr.extract_object()));
+        }
+
+        if expr.is_copy_region() {
+            let sub_exprs = expr.sub_exprs().unwrap();
+            a = Some(self.convert_copy_region(&sub_exprs[0], &sub_exprs[1], sub_exprs[2].clone()));
+        }
+
         if expr.is_enum() {
             let ty = expr.ty();
             let idx = expr.extract_variant_idx();
@@ -240,7 +278,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
                 self.cache_ast(expr, ast.clone());
                 ast
             }
-            None => panic!("Not implememt: {expr:?}"),
+            None => unsupported!("Not implememt: {expr:?}"),
         }
     }
 
@@ -289,6 +327,8 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
     fn convert_pointer_base(&self, pt: &Ast) -> Ast;
     fn convert_pointer_offset(&self, pt: &Ast) -> Ast;
     fn convert_pointer_meta(&self, pt: &Ast) -> Ast;
+    /// Move the offset of a pointer by an integer
+    fn convert_pointer_add(&self, pt: &Ast, offset: &Ast, fat: bool) -> Ast;
     fn convert_box(&self, _box: &Ast) -> Ast;
     fn convert_vec(&self, _vec: &Ast, len: &Ast, cap: &Ast) -> Ast;
     fn convert_vec_len(&self, _vec: &Ast) -> Ast;
@@ -312,7 +352,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
         if ty.is_bool() {
             return self.mk_bool_symbol(name);
         }
-        if ty.is_integer() {
+        if ty.is_integer() || ty.is_char() {
             return self.mk_int_symbol(name);
         }
         if ty.is_any_ptr() {
@@ -329,24 +369,43 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.mk_tuple_symbol(name, &sort);
         }
         if ty.is_tuple() {
-            let sort = self.convert_enum_sort(ty);
+            let sort = self.convert_tuple_sort(ty);
             return self.mk_tuple_symbol(name, &sort);
         }
         if ty.is_enum() {
             let sort = self.convert_enum_sort(ty);
             return self.mk_enum_symbol(name, &sort);
         }
-        panic!("{name:?} {ty:?} symbol is not support?")
+        unsupported!("{name:?} {ty} symbol")
     }
 
-    fn convert_address_of(&mut self, object: Expr) -> Ast {
+    /// Only fat pointers carry metadata
+    fn convert_address_of(&mut self, object: Expr, ty: Type) -> Ast {
         assert!(object.is_object());
         let inner_expr = object.extract_inner_expr();
+        assert!(
+            inner_expr.is_slice() || !ty.is_fat_pointer(),
+            "Fat pointer {ty:?} to {object:?} has no metadata"
+        );
         if inner_expr.is_index() {
             let inner_object = inner_expr.extract_object();
             let inner_offset = inner_expr.extract_index();
             let base = self.convert_object_space(&inner_object);
-            let offset = self.convert_ast(inner_offset);
+            // Fields of a struct or a tuple are placed in the order of the
+            // layout. Fields of a variant are placed after the discriminant.
+            let offset = if inner_object.ty().is_struct() && inner_offset.is_constant() {
+                let i = bigint_to_usize(&inner_offset.extract_constant().to_integer());
+                self.mk_smt_int(BigInt::from(inner_object.ty().struct_field_offset(i)))
+            } else if inner_object.ty().is_tuple() && inner_offset.is_constant() {
+                let i = bigint_to_usize(&inner_offset.extract_constant().to_integer());
+                self.mk_smt_int(BigInt::from(inner_object.ty().tuple_elem_offset(i)))
+            } else if inner_object.ty().is_enum() && inner_offset.is_constant() {
+                let k = inner_object.extract_inner_expr().extract_variant_idx();
+                let i = bigint_to_usize(&inner_offset.extract_constant().to_integer());
+                self.mk_smt_int(BigInt::from(inner_object.ty().variant_field_offset(k, i)))
+            } else {
+                self.convert_ast(inner_offset)
+            };
             return self.convert_pointer(&base, &offset, None);
         }
 
@@ -361,18 +420,36 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             let base = self.convert_object_space(&inner_object);
             let start = inner_expr.extract_slice_start();
             let offset = self.convert_ast(start);
+            if !ty.is_fat_pointer() {
+                return self.convert_pointer(&base, &offset, None);
+            }
             let len = inner_expr.extract_slice_len();
             let meta = self.convert_ast(len);
             return self.convert_pointer(&base, &offset, Some(&meta));
         }
 
-        panic!("Do not support address_of {object:?}")
+        unsupported!("Do not support address_of {object:?}")
     }
 
     fn convert_object_space(&mut self, object: &Expr) -> Ast;
 
+    /// The length of the space of an object
+    fn convert_object_size(&mut self, object: &Expr) -> Ast;
+
+    /// The address of a pointer, `base + offset`, as an integer
+    fn convert_pointer_address(&mut self, pt: Expr) -> Ast;
+
+    /// Copy the first `len` elements of the region `src` to `dst`
+    fn convert_copy_region(&mut self, dst: &Expr, src: &Expr, len: Expr) -> Ast;
+
+    /// Two pointers are equal iff they have the same base and offset. Thus,
+    /// pointers to distinct objects are never equal. Metadata is compared
+    /// for fat pointers. Other comparisons use the addresses.
+    fn convert_pointer_compare(&mut self, op: BinOp, lhs: &Ast, rhs: &Ast, ty: Type) -> Ast;
+
     fn convert_cast(&mut self, expr: Expr, target_ty: Type) -> Ast {
-        if expr.ty().is_integer() && target_ty.is_integer() {
+        let is_int = |ty: Type| ty.is_integer() || ty.is_char();
+        if is_int(expr.ty()) && is_int(target_ty) {
             return self.convert_ast(expr.clone());
         }
 
@@ -380,7 +457,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.convert_cast_from_ptr(expr, target_ty);
         }
 
-        panic!("Do not support cast {:?} to {target_ty:?}", expr.ty())
+        unsupported!("Do not support cast {} to {target_ty}", expr.ty())
     }
 
     fn convert_cast_from_ptr(&mut self, pt: Expr, target_ty: Type) -> Ast {
@@ -389,24 +466,34 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
                 let inner_ptr = pt.ctx.inner_pointer(pt.clone());
                 return self.convert_ast(inner_ptr);
             }
+            // `Rc::new` makes an rc from a box
+            if pt.ty().is_box() && target_ty.is_rc() {
+                return self.convert_ast(pt);
+            }
         }
 
         if pt.ty().is_primitive_ptr() {
+            // Metadata is dropped by casting to a thin pointer
+            if target_ty.is_primitive_ptr()
+                && pt.ty().is_fat_pointer()
+                && !target_ty.is_fat_pointer()
+            {
+                let fat_ptr = self.convert_ast(pt);
+                let base = self.convert_pointer_base(&fat_ptr);
+                let offset = self.convert_pointer_offset(&fat_ptr);
+                return self.convert_pointer(&base, &offset, None);
+            }
             if target_ty.is_primitive_ptr() {
                 return self.convert_ast(pt);
             }
 
             // cast pointer to integer
             if target_ty.is_integer() {
-                let pointer_base = pt.ctx.pointer_base(pt.clone());
-                let pointer_offset = pt.ctx.pointer_offset(pt.clone());
-                let base = self.convert_ast(pointer_base);
-                let offset = self.convert_ast(pointer_offset);
-                return self.mk_add(&base, &offset);
+                return self.convert_pointer_address(pt);
             }
         }
 
-        panic!("Do not support cast {:?} to {target_ty:?}", pt.ty())
+        unsupported!("Do not support cast {} to {target_ty}", pt.ty())
     }
 
     fn convert_index(&mut self, object: Expr, index: Expr) -> Ast {
@@ -424,7 +511,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.convert_index_enum(object.clone(), index.clone());
         }
 
-        panic!("Do not support load {object:?} with {:?}", object.ty())
+        unsupported!("Do not support load {object:?} with {}", object.ty())
     }
 
     fn convert_index_tuple(&mut self, object: Expr, field: Expr) -> Ast;
@@ -448,7 +535,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
             return self.convert_variant_update(inner_expr, index.clone(), value.clone());
         }
 
-        panic!("Do not support store {object:?} with {:?}", object.ty())
+        unsupported!("Do not support store {object:?} with {}", object.ty())
     }
 
     fn convert_tuple_update(&mut self, object: Expr, field: Expr, value: Expr) -> Ast;
@@ -492,6 +579,7 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
     fn mk_sub(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_mul(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_div(&self, lhs: &Ast, rhs: &Ast) -> Ast;
+    fn mk_rem(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_eq(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_ne(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_ge(&self, lhs: &Ast, rhs: &Ast) -> Ast;
@@ -501,6 +589,11 @@ pub(crate) trait Convert<Sort, Ast: Clone + Debug> {
     fn mk_and(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_or(&self, lhs: &Ast, rhs: &Ast) -> Ast;
     fn mk_not(&self, operand: &Ast) -> Ast;
+    fn mk_neg(&self, operand: &Ast) -> Ast;
     fn mk_implies(&self, cond: &Ast, conseq: &Ast) -> Ast;
     fn mk_ite(&self, cond: &Ast, true_value: &Ast, false_value: &Ast) -> Ast;
+
+    // Conversions between integers and bit-vectors of `width` bits
+    fn mk_int_to_bv(&self, operand: &Ast, width: u32) -> Ast;
+    fn mk_bv_to_int(&self, operand: &Ast, width: u32, signed: bool) -> Ast;
 }
@@ -1,51 +1,62 @@
 use std::collections::HashMap;
 
 use crate::expr::expr::*;
+use crate::expr::op::BinOp;
 
 pub type ObjectSpace<Ast> = (Ast, Ast);
 
+/// Null is the space `[NULL_BASE, NULL_BASE + 1)`. Spaces of real objects
+/// are disjoint with it, thus their bases are at least `NULL_BASE + 1`.
+pub const NULL_BASE: u64 = 0;
+
 /// The space of an object is identified by `(base, len)`,
-/// where `base > 0`.
+/// where `base > NULL_BASE`. Spaces are kept in insertion order so that
+/// the encoding is reproducible.
+#[derive(Clone)]
 pub struct PointerLogic<Ast: Clone> {
-    _object_spaces: HashMap<Expr, ObjectSpace<Ast>>,
+    _object_spaces: Vec<(Expr, ObjectSpace<Ast>)>,
+    _index: HashMap<Expr, usize>,
 }
 
 impl<Ast: Clone> PointerLogic<Ast> {
     pub fn new() -> Self {
-        PointerLogic { _object_spaces: HashMap::new() }
+        PointerLogic { _object_spaces: Vec::new(), _index: HashMap::new() }
     }
 
     pub fn contains(&self, object: &Expr) -> bool {
-        self._object_spaces.contains_key(object)
+        self._index.contains_key(object)
     }
 
     pub fn clear(&mut self) {
         self._object_spaces.clear();
+        self._index.clear();
     }
 
     pub fn set_object_space(&mut self, object: Expr, space: ObjectSpace<Ast>) {
         assert!(!self.contains(&object));
-        self._object_spaces.insert(object, space);
+        self._index.insert(object.clone(), self._object_spaces.len());
+        self._object_spaces.push((object, space));
     }
 
-    pub fn object_spaces(&self) -> &HashMap<Expr, ObjectSpace<Ast>> {
+    /// Object spaces in the order of creation
+    pub fn object_spaces(&self) -> &Vec<(Expr, ObjectSpace<Ast>)> {
         &self._object_spaces
     }
 
-    pub fn get_object_space_base(&self, object: &Expr) -> Ast {
-        self._object_spaces
+    fn get_object_space(&self, object: &Expr) -> &ObjectSpace<Ast> {
+        let i = self
+            ._index
             .get(object)
-            .expect(format!("Object space dose not have {object:?}").as_str())
-            .0
-            .clone()
+            .expect(format!("Object space dose not have {object:?}").as_str());
+        &self._object_spaces[*i].1
+    }
+
+    pub fn get_object_space_base(&self, object: &Expr) -> Ast {
+        self.get_object_space(object).0.clone()
     }
 
     pub fn get_object_space_len(&self, object: &Expr) -> Ast {
-        self._object_spaces
-            .get(object)
-            .expect(format!("Object space dose not have {object:?}").as_str())
-            .1
-            .clone()
+        self.get_object_space(object).1.clone()
     }
 }
 
@@ -58,15 +69,30 @@ pub trait MemSpace<Sort, Ast> {
 
     fn create_object_space(&mut self, object: &Expr) -> Ast;
     fn init_pointer_space(&mut self, object: &Expr);
+    /// Make all object spaces disjoint once addresses are observed
+    fn expose_addresses(&mut self);
 
     fn mk_pointer(&self, base: &Ast, offset: &Ast, meta: Option<&Ast>) -> Ast;
     fn mk_pointer_base(&self, pt: &Ast) -> Ast;
     fn mk_pointer_offset(&self, pt: &Ast) -> Ast;
     fn mk_pointer_meta(&self, pt: &Ast) -> Ast;
+    /// Move the offset of a pointer by an integer. Metadata is kept for
+    /// fat pointers.
+    fn mk_pointer_add(&self, pt: &Ast, offset: &Ast, fat: bool) -> Ast;
+    /// The address `base + offset * elem_size` as an unsigned integer,
+    /// where the offset in fields is scaled to bytes
+    fn mk_pointer_address(&self, pt: &Ast, elem_size: u64) -> Ast;
+    /// Compare two pointers. `Eq` and `Ne` compare the fields, the others
+    /// compare the addresses.
+    fn mk_pointer_compare(&self, op: BinOp, lhs: &Ast, rhs: &Ast, fat: bool) -> Ast;
     fn mk_box(&self, inner_pt: &Ast) -> Ast;
     fn mk_box_ptr(&self, _box: &Ast) -> Ast;
     fn mk_vec(&self, inner_pt: &Ast, len: &Ast, cap: &Ast) -> Ast;
     fn mk_vec_ptr(&self, _vec: &Ast) -> Ast;
     fn mk_vec_len(&self, _vec: &Ast) -> Ast;
     fn mk_vec_cap(&self, _vec: &Ast) -> Ast;
+
+    /// The array of `dst` after copying the first `len` elements of `src`.
+    /// `dst` and `src` are arrays or slices of arrays.
+    fn copy_region(&mut self, dst: &Expr, src: &Expr, len: Expr) -> Ast;
 }
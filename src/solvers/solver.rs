@@ -1,4 +1,8 @@
+use std::path::Path;
+
+use crate::config::cli::Cli;
 use crate::expr::expr::Expr;
+use crate::symbol::nstring::NString;
 
 use super::context::SolverCtx;
 use super::smt::smt_conv::*;
@@ -8,40 +12,115 @@ use super::z3::z3_conv::*;
 pub enum PResult {
     PSat,
     PUnknow,
+    /// Unknown since the solver reaches the timeout
+    PTimeout,
     PUnsat,
 }
 
+/// Work done by the SMT solver
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SolverStats {
+    /// Number of `check-sat` calls
+    pub checks: usize,
+    pub conflicts: u64,
+    pub decisions: u64,
+    /// Total solver time in seconds
+    pub time: f64,
+}
+
+impl SolverStats {
+    pub fn add(&mut self, rhs: &SolverStats) {
+        self.checks += rhs.checks;
+        self.conflicts += rhs.conflicts;
+        self.decisions += rhs.decisions;
+        self.time += rhs.time;
+    }
+
+    pub fn show(&self) {
+        println!(
+            "Solver statistics: {} check-sat call(s), {} conflicts, {} decisions, {}s",
+            self.checks, self.conflicts, self.decisions, self.time
+        );
+    }
+}
+
 pub struct Solver<'ctx> {
     smt_solver: Box<dyn SmtSolver<'ctx> + 'ctx>,
 }
 
 impl<'ctx> Solver<'ctx> {
-    pub fn new(solver_ctx: &'ctx SolverCtx) -> Self {
+    pub fn new(solver_ctx: &'ctx SolverCtx, cli: &Cli) -> Self {
         let mut smt_solver = match solver_ctx {
-            SolverCtx::Z3(ctx) => Box::new(Z3Conv::new(ctx)),
+            SolverCtx::Z3(ctx) => Box::new(Z3Conv::new(ctx, cli)),
         };
         smt_solver.init();
         Solver { smt_solver }
     }
 
-    pub fn check(&self) -> PResult {
+    pub fn check(&mut self) -> PResult {
         self.smt_solver.check()
     }
 
+    pub fn stats(&self) -> SolverStats {
+        self.smt_solver.stats()
+    }
+
     pub fn reset(&mut self) {
         self.smt_solver.reset();
     }
 
+    pub fn push(&mut self) {
+        self.smt_solver.push();
+    }
+
+    pub fn pop(&mut self) {
+        self.smt_solver.pop();
+    }
+
     pub fn eval_bool(&self, expr: Expr) -> bool {
         assert!(expr.ty().is_bool());
         self.smt_solver.eval_bool(expr)
     }
 
+    pub fn eval_expr(&self, expr: Expr) -> Option<NString> {
+        self.smt_solver.eval_expr(expr)
+    }
+
+    pub fn eval_cond(&mut self, expr: Expr) -> Option<bool> {
+        assert!(expr.ty().is_bool());
+        self.smt_solver.eval_cond(expr)
+    }
+
+    pub fn show_unsat_core(&self) {
+        println!("Unsat core:");
+        for assertion in self.smt_solver.unsat_core() {
+            println!("  {assertion:?}");
+        }
+    }
+
     pub fn show_model(&self) {
         println!("Model:");
         self.smt_solver.show_model();
     }
 
+    pub fn show_object_spaces(&self) {
+        println!("Object spaces:");
+        self.smt_solver.show_object_spaces();
+    }
+
+    pub fn smtlib(&self) -> String {
+        self.smt_solver.smtlib()
+    }
+
+    /// Only `z3` is supported now
+    pub fn check_smtlib(smt: &str, timeout: u32) -> (PResult, SolverStats) {
+        Z3Conv::check_smtlib(smt, timeout)
+    }
+
+    pub fn dump_smtlib(&self, path: &Path) {
+        self.smt_solver.dump_smtlib(path);
+    }
+
     pub fn assert_assign(&mut self, lhs: Expr, rhs: Expr) {
         self.smt_solver.assert_assign(lhs, rhs);
     }
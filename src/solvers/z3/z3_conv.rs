@@ -1,43 +1,152 @@
+use std::cell::{Cell, OnceCell, RefCell};
 use std::collections::HashMap;
+use std::path::Path;
 
 use num_bigint::BigInt;
+use stable_mir::target::MachineInfo;
 
 use z3;
 use z3::ast::Ast;
 
+use crate::bmc::error::unsupported;
+use crate::config::cli::{Cli, PointerEncoding};
 use crate::expr::expr::*;
+use crate::expr::op::BinOp;
 use crate::expr::ty::Type;
 use crate::program::program::bigint_to_usize;
 use crate::solvers::smt::smt_conv::*;
 use crate::solvers::smt::smt_datatype::*;
 use crate::solvers::smt::smt_memspace::*;
-use crate::solvers::solver::PResult;
+use crate::solvers::solver::{PResult, SolverStats};
 use crate::symbol::nstring::NString;
 
 pub struct Z3Conv<'ctx> {
     pub(super) z3_ctx: &'ctx z3::Context,
-    z3_solver: z3::Solver<'ctx>,
+    pub(super) z3_solver: z3::Solver<'ctx>,
     pub(super) fresh_count: HashMap<NString, usize>,
     pub(super) datatypes: HashMap<DataTypeSign, z3::DatatypeSort<'ctx>>,
+    /// Datatypes of pointers. They never change after `set_pointer_logic`
+    pub(super) pointer_datatype: OnceCell<z3::DatatypeSort<'ctx>>,
+    pub(super) box_datatype: OnceCell<z3::DatatypeSort<'ctx>>,
+    pub(super) vec_datatype: OnceCell<z3::DatatypeSort<'ctx>>,
     pub(super) pointer_logic: PointerLogic<z3::ast::Dynamic<'ctx>>,
     /// Cache Ast
-    cache: HashMap<Expr, z3::ast::Dynamic<'ctx>>,
+    pub(super) cache: HashMap<Expr, z3::ast::Dynamic<'ctx>>,
+    /// Fields of pointers accessed. Terms of the same pointer are built
+    /// once, e.g., `base` of a pointer in many comparisons. They are
+    /// terms of the context, so they are kept across scopes.
+    pub(super) pointer_fields: RefCell<PointerFields<'ctx>>,
     /// Cache current alloc.
     pub(super) cur_alloc_expr: Option<z3::ast::Dynamic<'ctx>>,
+    /// Width of fields in pointer. `None` for integer encoding.
+    pub(super) pointer_width: Option<u32>,
+    /// Skip disjointness for objects that can not alias
+    pub(super) assume_type_disjoint: bool,
+    /// Addresses are observed by casting pointers to integers. Then all
+    /// object spaces are disjoint.
+    pub(super) exposed: bool,
+    /// Skip disjointness if the liveness of objects is unknown
+    pub(super) no_disjoint_allocs: bool,
+    /// The warning of unknown liveness is shown
+    pub(super) liveness_warned: Cell<bool>,
+    /// Track each assertion by a name for unsat cores
+    unsat_core: bool,
+    /// Names of tracked assertions and what they are
+    tracked: Vec<(z3::ast::Bool<'ctx>, NString)>,
+    /// Saved states of each scope
+    scopes: Vec<Z3Scope<'ctx>>,
+    stats: SolverStats,
+}
+
+/// The i-th field of each pointer
+type PointerFields<'ctx> = HashMap<(z3::ast::Dynamic<'ctx>, usize), z3::ast::Dynamic<'ctx>>;
+
+/// Constraints of object spaces are asserted while converting. They
+/// are removed when poping a scope. So the caches must be restored.
+struct Z3Scope<'ctx> {
+    cache: HashMap<Expr, z3::ast::Dynamic<'ctx>>,
+    pointer_logic: PointerLogic<z3::ast::Dynamic<'ctx>>,
+    cur_alloc_expr: Option<z3::ast::Dynamic<'ctx>>,
+    exposed: bool,
+    num_tracked: usize,
 }
 
 impl<'ctx> Z3Conv<'ctx> {
-    pub fn new(z3_ctx: &'ctx z3::Context) -> Self {
-        let z3_solver = z3::Solver::new(z3_ctx);
+    pub fn new(z3_ctx: &'ctx z3::Context, cli: &Cli) -> Self {
+        let z3_solver = Z3Conv::new_solver(z3_ctx, cli.solver_timeout);
+        let pointer_width = match cli.pointer_encoding {
+            PointerEncoding::Int => None,
+            PointerEncoding::Bv => Some(MachineInfo::target_pointer_width().bits() as u32),
+        };
         Z3Conv {
             z3_ctx,
             z3_solver,
             fresh_count: HashMap::new(),
             datatypes: HashMap::new(),
+            pointer_datatype: OnceCell::new(),
+            box_datatype: OnceCell::new(),
+            vec_datatype: OnceCell::new(),
             pointer_logic: PointerLogic::new(),
             cache: HashMap::new(),
+            pointer_fields: RefCell::new(HashMap::new()),
             cur_alloc_expr: None,
+            pointer_width,
+            assume_type_disjoint: cli.assume_type_disjoint,
+            exposed: false,
+            no_disjoint_allocs: cli.no_disjoint_allocs,
+            liveness_warned: Cell::new(false),
+            unsat_core: cli.unsat_core,
+            tracked: Vec::new(),
+            scopes: Vec::new(),
+            stats: SolverStats::default(),
+        }
+    }
+
+    /// Check a formula in SMT-LIB2 format within a new context. It does
+    /// not rely on the program, so it can be called in any thread.
+    pub fn check_smtlib(smt: &str, timeout: u32) -> (PResult, SolverStats) {
+        let z3_ctx = z3::Context::new(&z3::Config::new());
+        let z3_solver = Z3Conv::new_solver(&z3_ctx, timeout);
+        z3_solver.from_string(smt);
+        Z3Conv::check_solver(&z3_solver)
+    }
+
+    /// `timeout` is in milliseconds. '0' indicates no timeout.
+    fn new_solver(z3_ctx: &z3::Context, timeout: u32) -> z3::Solver {
+        let z3_solver = z3::Solver::new(z3_ctx);
+        if timeout > 0 {
+            let mut params = z3::Params::new(z3_ctx);
+            params.set_u32("timeout", timeout);
+            z3_solver.set_params(&params);
         }
+        z3_solver
+    }
+
+    fn check_solver(z3_solver: &z3::Solver) -> (PResult, SolverStats) {
+        let solver_time = std::time::Instant::now();
+        let res = match z3_solver.check() {
+            z3::SatResult::Sat => PResult::PSat,
+            z3::SatResult::Unknown => match z3_solver.get_reason_unknown() {
+                Some(r) if r.contains("timeout") || r.contains("canceled") => PResult::PTimeout,
+                _ => PResult::PUnknow,
+            },
+            z3::SatResult::Unsat => PResult::PUnsat,
+        };
+        let time = solver_time.elapsed().as_secs_f64();
+
+        let z3_stats = z3_solver.get_statistics();
+        let value = |key| match z3_stats.value(key) {
+            Some(z3::StatisticsValue::UInt(x)) => x as u64,
+            Some(z3::StatisticsValue::Double(x)) => x as u64,
+            None => 0,
+        };
+        let stats = SolverStats {
+            checks: 1,
+            conflicts: value("conflicts"),
+            decisions: value("decisions"),
+            time,
+        };
+        (res, stats)
     }
 
     pub(super) fn fresh_symbol(&mut self, prefix: NString) -> NString {
@@ -48,6 +157,19 @@ impl<'ctx> Z3Conv<'ctx> {
     pub(super) fn assert(&self, e: z3::ast::Dynamic<'ctx>) {
         self.z3_solver.assert(&e.as_bool().unwrap());
     }
+
+    /// Assertions of VCs are tracked by fresh names if unsat cores are
+    /// required. Constraints of object spaces are never tracked.
+    fn assert_tracked(&mut self, e: z3::ast::Dynamic<'ctx>, desc: impl FnOnce() -> NString) {
+        if !self.unsat_core {
+            self.assert(e);
+            return;
+        }
+        let name = self.fresh_symbol("vc".into());
+        let label = z3::ast::Bool::new_const(self.z3_ctx, name.to_string());
+        self.z3_solver.assert_and_track(&e.as_bool().unwrap(), &label);
+        self.tracked.push((label, desc()));
+    }
 }
 
 impl<'ctx> SmtSolver<'ctx> for Z3Conv<'ctx> {
@@ -64,14 +186,14 @@ impl<'ctx> SmtSolver<'ctx> for Z3Conv<'ctx> {
 
         let res = a._eq(&b);
 
-        self.assert(z3::ast::Dynamic::from(res));
+        self.assert_tracked(z3::ast::Dynamic::from(res), || format!("{lhs:?} = {rhs:?}").into());
 
         self.cache_ast(lhs, b);
     }
 
     fn assert_expr(&mut self, expr: Expr) {
-        let e = self.convert_ast(expr);
-        self.assert(e);
+        let e = self.convert_ast(expr.clone());
+        self.assert_tracked(e, || format!("{expr:?}").into());
     }
 
     fn reset(&mut self) {
@@ -83,14 +205,39 @@ impl<'ctx> SmtSolver<'ctx> for Z3Conv<'ctx> {
         self.pointer_logic.clear();
         // Reset alloc array
         self.cur_alloc_expr = None;
+        self.tracked.clear();
+        self.scopes.clear();
     }
 
-    fn check(&self) -> PResult {
-        match self.z3_solver.check() {
-            z3::SatResult::Sat => PResult::PSat,
-            z3::SatResult::Unknown => PResult::PUnknow,
-            z3::SatResult::Unsat => PResult::PUnsat,
-        }
+    fn push(&mut self) {
+        self.scopes.push(Z3Scope {
+            cache: self.cache.clone(),
+            pointer_logic: self.pointer_logic.clone(),
+            cur_alloc_expr: self.cur_alloc_expr.clone(),
+            exposed: self.exposed,
+            num_tracked: self.tracked.len(),
+        });
+        self.z3_solver.push();
+    }
+
+    fn pop(&mut self) {
+        let scope = self.scopes.pop().expect("No scope to pop");
+        self.z3_solver.pop(1);
+        self.cache = scope.cache;
+        self.pointer_logic = scope.pointer_logic;
+        self.cur_alloc_expr = scope.cur_alloc_expr;
+        self.exposed = scope.exposed;
+        self.tracked.truncate(scope.num_tracked);
+    }
+
+    fn check(&mut self) -> PResult {
+        let (res, stats) = Z3Conv::check_solver(&self.z3_solver);
+        self.stats.add(&stats);
+        res
+    }
+
+    fn stats(&self) -> SolverStats {
+        self.stats
     }
 
     fn eval_bool(&self, expr: Expr) -> bool {
@@ -106,12 +253,56 @@ impl<'ctx> SmtSolver<'ctx> for Z3Conv<'ctx> {
             .expect("Wrong result")
     }
 
+    fn eval_expr(&self, expr: Expr) -> Option<NString> {
+        let ast = self.get_cache_ast(&expr)?;
+        let model = self.z3_solver.get_model()?;
+        let ty = expr.ty();
+        if ty.is_primitive_ptr() || ty.is_box() || ty.is_rc() {
+            let pt = if ty.is_primitive_ptr() { ast } else { self.mk_box_ptr(&ast) };
+            let base = model.eval(&self.mk_pointer_base(&pt), true)?;
+            let offset = model.eval(&self.mk_pointer_offset(&pt), true)?;
+            return Some(format!("pointer(base: {base}, offset: {offset})").into());
+        }
+        Some(format!("{}", model.eval(&ast, true)?).into())
+    }
+
+    fn eval_cond(&mut self, expr: Expr) -> Option<bool> {
+        let ast = self.convert_ast(expr);
+        let model = self.z3_solver.get_model()?;
+        model.eval(&ast, true)?.as_bool()?.as_bool()
+    }
+
+    fn unsat_core(&self) -> Vec<NString> {
+        let core = self.z3_solver.get_unsat_core();
+        self.tracked
+            .iter()
+            .filter(|(label, _)| core.contains(label))
+            .map(|(_, desc)| *desc)
+            .collect()
+    }
+
     fn show_model(&self) {
         match self.z3_solver.get_model() {
             Some(m) => println!("{m:?}"),
             None => println!("None"),
         };
     }
+
+    fn show_object_spaces(&self) {
+        for info in self.object_spaces_info() {
+            println!("  {info}");
+        }
+    }
+
+    fn smtlib(&self) -> String {
+        // Declarations, e.g. the pointer datatype, are printed by z3
+        format!("{}", self.z3_solver)
+    }
+
+    fn dump_smtlib(&self, path: &Path) {
+        let smt = format!("{}(check-sat)\n", self.smtlib());
+        std::fs::write(path, smt).expect(format!("Fail to write {path:?}").as_str());
+    }
 }
 
 impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
@@ -140,14 +331,14 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
     }
 
     fn convert_null(&self, ty: Type) -> z3::ast::Dynamic<'ctx> {
-        let null_pt =
-            self.mk_pointer(&self.mk_smt_int(BigInt::ZERO), &self.mk_smt_int(BigInt::ZERO), None);
+        let null_base = self.mk_smt_int(BigInt::from(NULL_BASE));
+        let null_pt = self.mk_pointer(&null_base, &self.mk_smt_int(BigInt::ZERO), None);
         if ty.is_primitive_ptr() {
             null_pt
-        } else if ty.is_box() {
+        } else if ty.is_box() || ty.is_rc() {
             self.mk_box(&null_pt)
         } else {
-            panic!("Not support null({ty:?})")
+            unsupported!("Not support null({ty})")
         }
     }
 
@@ -172,6 +363,15 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         self.mk_pointer_meta(pt)
     }
 
+    fn convert_pointer_add(
+        &self,
+        pt: &z3::ast::Dynamic<'ctx>,
+        offset: &z3::ast::Dynamic<'ctx>,
+        fat: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        self.mk_pointer_add(pt, offset, fat)
+    }
+
     fn convert_box(&self, _box: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
         self.mk_box(_box)
     }
@@ -198,8 +398,8 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         pt: &z3::ast::Dynamic<'ctx>,
         ty: Type,
     ) -> z3::ast::Dynamic<'ctx> {
-        assert!(ty.is_box() || ty.is_vec());
-        if ty.is_box() { self.mk_box_ptr(pt) } else { self.mk_vec_ptr(pt) }
+        assert!(ty.is_smart_ptr());
+        if ty.is_vec() { self.mk_vec_ptr(pt) } else { self.mk_box_ptr(pt) }
     }
 
     fn convert_struct(
@@ -236,6 +436,39 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         self.create_object_space(&inner_expr)
     }
 
+    fn convert_object_size(&mut self, object: &Expr) -> z3::ast::Dynamic<'ctx> {
+        if object.ty().is_zst() {
+            return self.mk_smt_int(BigInt::ZERO);
+        }
+        self.convert_object_space(object);
+        let mut inner_expr = object.extract_inner_expr();
+        if inner_expr.is_as_variant() {
+            inner_expr = inner_expr.extract_enum();
+        }
+        self.pointer_logic.get_object_space_len(&inner_expr)
+    }
+
+    fn convert_pointer_address(&mut self, pt: Expr) -> z3::ast::Dynamic<'ctx> {
+        self.expose_addresses();
+        let elem_size = pt.ty().pointee_ty().size_in_bytes().unwrap_or(1);
+        let pt = self.convert_ast(pt);
+        self.mk_pointer_address(&pt, elem_size)
+    }
+
+    fn convert_pointer_compare(
+        &mut self,
+        op: BinOp,
+        lhs: &z3::ast::Dynamic<'ctx>,
+        rhs: &z3::ast::Dynamic<'ctx>,
+        ty: Type,
+    ) -> z3::ast::Dynamic<'ctx> {
+        self.mk_pointer_compare(op, lhs, rhs, ty.is_fat_pointer())
+    }
+
+    fn convert_copy_region(&mut self, dst: &Expr, src: &Expr, len: Expr) -> z3::ast::Dynamic<'ctx> {
+        self.copy_region(dst, src, len)
+    }
+
     /// Select from struct/tuple
     fn convert_index_tuple(&mut self, object: Expr, field: Expr) -> z3::ast::Dynamic<'ctx> {
         let i = bigint_to_usize(&field.extract_integer());
@@ -244,17 +477,15 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         self.mk_tuple_select(object, i, ty)
     }
 
+    /// Select the `field`-th field from the data of a variant
     fn convert_index_enum(&mut self, object: Expr, field: Expr) -> z3::ast::Dynamic<'ctx> {
         let ty = object.ty();
-        let sign = self.create_datatype_sign(ty);
         let as_variant = object.extract_inner_expr();
         assert!(as_variant.is_as_variant());
         let idx = as_variant.extract_variant_idx();
         let i = bigint_to_usize(&field.extract_integer());
-        let args = &[&self.convert_ast(object) as &dyn Ast];
-        let variant_data_type = ty.enum_variant_data_type(idx);
-        let sign = self.create_datatype_sign(variant_data_type);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[0].apply(args)
+        let data = self.convert_ast(object);
+        self.mk_tuple_select(data, i, ty.enum_variant_data_type(idx))
     }
 
     fn convert_tuple_update(
@@ -431,9 +662,22 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         lhs: &z3::ast::Dynamic<'ctx>,
         rhs: &z3::ast::Dynamic<'ctx>,
     ) -> z3::ast::Dynamic<'ctx> {
-        z3::ast::Dynamic::from(
-            lhs.as_int().expect("lhs is not integer") / rhs.as_int().expect("rhs is not integer"),
-        )
+        // Division in Rust rounds towards zero. But z3 is euclidean division.
+        let a = lhs.as_int().expect("lhs is not integer");
+        let b = rhs.as_int().expect("rhs is not integer");
+        let zero = z3::ast::Int::from_i64(self.z3_ctx, 0);
+        let is_neg = a.lt(&zero);
+        z3::ast::Dynamic::from(is_neg.ite(&-(-a.clone() / b.clone()), &(a / b)))
+    }
+
+    fn mk_rem(
+        &self,
+        lhs: &z3::ast::Dynamic<'ctx>,
+        rhs: &z3::ast::Dynamic<'ctx>,
+    ) -> z3::ast::Dynamic<'ctx> {
+        // The sign of remainder is same as the dividend in Rust
+        let q = self.mk_div(lhs, rhs);
+        self.mk_sub(lhs, &self.mk_mul(rhs, &q))
     }
 
     fn mk_eq(
@@ -526,6 +770,10 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         z3::ast::Dynamic::from(operand.as_bool().expect("operand is no bool").not())
     }
 
+    fn mk_neg(&self, operand: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
+        z3::ast::Dynamic::from(operand.as_int().expect("operand is not integer").unary_minus())
+    }
+
     fn mk_implies(
         &self,
         cond: &z3::ast::Dynamic<'ctx>,
@@ -546,4 +794,80 @@ impl<'ctx> Convert<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
     ) -> z3::ast::Dynamic<'ctx> {
         cond.as_bool().expect("condition must be bool").ite(true_value, false_value)
     }
+
+    fn mk_int_to_bv(&self, operand: &z3::ast::Dynamic<'ctx>, width: u32) -> z3::ast::Dynamic<'ctx> {
+        let i = operand.as_int().expect("operand is not integer");
+        z3::ast::Dynamic::from(z3::ast::BV::from_int(&i, width))
+    }
+
+    fn mk_bv_to_int(
+        &self,
+        operand: &z3::ast::Dynamic<'ctx>,
+        width: u32,
+        signed: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        let bv = operand.simplify();
+        // `bv2int(int2bv(x))` is hard for solver. Use modulo instead.
+        if bv.is_app() && bv.decl().kind() == z3::DeclKind::INT2BV {
+            let x = bv.children()[0].as_int().unwrap();
+            let modulus =
+                z3::ast::Int::from_str(self.z3_ctx, &(BigInt::from(1) << width).to_string())
+                    .unwrap();
+            let res = if signed {
+                let half = z3::ast::Int::from_str(
+                    self.z3_ctx,
+                    &(BigInt::from(1) << (width - 1)).to_string(),
+                )
+                .unwrap();
+                (x + &half).modulo(&modulus) - half
+            } else {
+                x.modulo(&modulus)
+            };
+            return z3::ast::Dynamic::from(res.simplify());
+        }
+        let bv = bv.as_bv().expect("operand is not bit-vector");
+        assert!(bv.get_size() == width, "{bv:?} is not a bit-vector of {width} bits");
+        z3::ast::Dynamic::from(bv.to_int(signed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `bv_to_int(int_to_bv(x))` is `x` for the constant `x` and for a
+    /// symbol that equals `x`
+    fn assert_round_trip(width: u32, signed: bool, values: &[BigInt]) {
+        let z3_ctx = z3::Context::new(&z3::Config::new());
+        let conv = Z3Conv::new(&z3_ctx, &Cli::default());
+        let symbol = z3::ast::Dynamic::from(z3::ast::Int::new_const(&z3_ctx, "x"));
+        for value in values {
+            let x = conv.mk_smt_int(value.clone());
+            for operand in [&x, &symbol] {
+                let bv = conv.mk_int_to_bv(operand, width);
+                let res = conv.mk_bv_to_int(&bv, width, signed);
+                let solver = z3::Solver::new(&z3_ctx);
+                solver.assert(&symbol._eq(&x));
+                solver.assert(&res._eq(&x).not());
+                assert_eq!(solver.check(), z3::SatResult::Unsat, "{value} in {width} bits");
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_unsigned() {
+        for width in [8u32, 64] {
+            let max = (BigInt::from(1) << width) - 1;
+            assert_round_trip(width, false, &[BigInt::ZERO, BigInt::from(1), max]);
+        }
+    }
+
+    #[test]
+    fn round_trip_signed() {
+        for width in [8u32, 64] {
+            let half = BigInt::from(1) << (width - 1);
+            let values = [-half.clone(), BigInt::from(-1), BigInt::ZERO, half - 1];
+            assert_round_trip(width, true, &values);
+        }
+    }
 }
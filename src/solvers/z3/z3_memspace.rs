@@ -3,25 +3,203 @@ use z3::DatatypeAccessor;
 use z3::ast::*;
 
 use super::z3_conv::*;
+use crate::bmc::error::unsupported;
 use crate::expr::expr::*;
+use crate::expr::op::BinOp;
+use crate::program::program::bigint_to_usize;
 use crate::solvers::smt::smt_conv::*;
 use crate::solvers::smt::smt_memspace::*;
 use crate::symbol::nstring::NString;
 
+impl<'ctx> Z3Conv<'ctx> {
+    fn pointer_datatype(&self) -> &z3::DatatypeSort<'ctx> {
+        self.pointer_datatype.get().expect("Pointer tuple is not initialized")
+    }
+
+    fn box_datatype(&self) -> &z3::DatatypeSort<'ctx> {
+        self.box_datatype.get().expect("Box pointer tuple is not initialized")
+    }
+
+    fn vec_datatype(&self) -> &z3::DatatypeSort<'ctx> {
+        self.vec_datatype.get().expect("Vec pointer tuple is not initialized")
+    }
+
+    fn pointer_field_sort(&self) -> z3::Sort<'ctx> {
+        match self.pointer_width {
+            Some(w) => z3::Sort::bitvector(&self.z3_ctx, w),
+            None => z3::Sort::int(&self.z3_ctx),
+        }
+    }
+
+    /// Integer to the field of pointer
+    fn to_pointer_field(&self, i: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
+        match self.pointer_width {
+            Some(w) => self.mk_int_to_bv(i, w),
+            None => i.clone(),
+        }
+    }
+
+    /// The field of pointer to integer. Only offset is signed.
+    fn from_pointer_field(
+        &self,
+        f: z3::ast::Dynamic<'ctx>,
+        signed: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        match self.pointer_width {
+            Some(w) => self.mk_bv_to_int(&f, w, signed),
+            None => f,
+        }
+    }
+
+    /// Two object spaces do not overlap. `None` if it is not encoded.
+    fn disjoint(
+        &self,
+        (b1, l1): (&z3::ast::Dynamic<'ctx>, &z3::ast::Dynamic<'ctx>),
+        (b2, l2): (&z3::ast::Dynamic<'ctx>, &z3::ast::Dynamic<'ctx>),
+    ) -> Option<z3::ast::Dynamic<'ctx>> {
+        let r1 = self.mk_add(b1, l1);
+        let r2 = self.mk_add(b2, l2);
+        let no_overlap = self.mk_or(&self.mk_le(&r1, b2), &self.mk_le(&r2, b1));
+        // No alloc array is active. That means the liveness of objects is not
+        // observed in the slice, and objects are only related by pointer
+        // comparisons. Distinct objects are assumed to never share an address
+        // unless `--no-disjoint-allocs` is given.
+        match self.cur_alloc_expr.as_ref() {
+            Some(alloc_array_ast) => {
+                let alive = alloc_array_ast.as_array().unwrap().select(b2);
+                Some(self.mk_implies(&alive, &no_overlap))
+            }
+            None => {
+                self.warn_unknown_liveness();
+                (!self.no_disjoint_allocs).then_some(no_overlap)
+            }
+        }
+    }
+
+    /// Both choices without liveness are imprecise. Warn once.
+    fn warn_unknown_liveness(&self) {
+        if self.liveness_warned.replace(true) {
+            return;
+        }
+        if self.no_disjoint_allocs {
+            println!(
+                "Warning: disjointness of objects is skipped since their liveness is not in \
+                 the slice. Distinct objects may share an address, so failures may be spurious."
+            );
+        } else {
+            println!(
+                "Warning: objects are assumed disjoint since their liveness is not in the \
+                 slice. The result may be unsound if a freed address is reused. \
+                 Use `--no-disjoint-allocs` to skip the assumption."
+            );
+        }
+    }
+
+    /// The array and the start of a region
+    fn region(&mut self, region: &Expr) -> (Expr, z3::ast::Dynamic<'ctx>) {
+        if region.is_slice() {
+            let start = self.convert_ast(region.extract_slice_start());
+            (region.extract_object(), start)
+        } else {
+            (region.clone(), self.mk_smt_int(BigInt::ZERO))
+        }
+    }
+
+    /// The i-th field of pointer as an integer. For bit-vectors, the field is
+    /// pushed into `ite` so that `bv2int` only applies to plain pointers.
+    fn pointer_field(
+        &self,
+        pt: &z3::ast::Dynamic<'ctx>,
+        i: usize,
+        signed: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        let key = (pt.clone(), i);
+        if let Some(field) = self.pointer_fields.borrow().get(&key) {
+            return field.clone();
+        }
+        let field =
+            if self.pointer_width.is_some() && pt.is_app() && pt.decl().kind() == z3::DeclKind::ITE
+            {
+                let children = pt.children();
+                let t = self.pointer_field(&children[1], i, signed);
+                let e = self.pointer_field(&children[2], i, signed);
+                children[0].as_bool().unwrap().ite(&t, &e)
+            } else {
+                self.from_pointer_field(self.raw_pointer_field(pt, i), signed)
+            };
+        self.pointer_fields.borrow_mut().insert(key, field.clone());
+        field
+    }
+
+    /// The i-th field of pointer in the sort of the encoding
+    fn raw_pointer_field(&self, pt: &z3::ast::Dynamic<'ctx>, i: usize) -> z3::ast::Dynamic<'ctx> {
+        self.pointer_datatype().variants[0].accessors[i].apply(&[pt as &dyn Ast])
+    }
+
+    /// `base + offset` in the sort of the encoding. Bit-vectors wrap around.
+    fn raw_pointer_address(&self, pt: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
+        let base = self.raw_pointer_field(pt, 0);
+        let offset = self.raw_pointer_field(pt, 1);
+        match self.pointer_width {
+            Some(_) => {
+                z3::ast::Dynamic::from(base.as_bv().unwrap().bvadd(&offset.as_bv().unwrap()))
+            }
+            None => self.mk_add(&base, &offset),
+        }
+    }
+
+    /// Object spaces in the order of creation with resolved types. The
+    /// base, the length, the liveness in the current alloc array and the
+    /// latest contents are evaluated in the model if there is one.
+    pub(super) fn object_spaces_info(&self) -> Vec<std::string::String> {
+        let model = self.z3_solver.get_model();
+        let show = |ast: &z3::ast::Dynamic<'ctx>| match model.as_ref() {
+            Some(m) => m.eval(ast, true).map_or(ast.to_string(), |v| v.to_string()),
+            None => ast.to_string(),
+        };
+        let mut infos = Vec::new();
+        for (object, (base, len)) in self.pointer_logic.object_spaces() {
+            let ident = object.extract_symbol().ident();
+            let alive = match self.cur_alloc_expr.as_ref() {
+                Some(alloc_array_ast) => show(&alloc_array_ast.as_array().unwrap().select(base)),
+                None => "unknown".to_string(),
+            };
+            // The contents of the last l2 symbol in the solver
+            let contents = self
+                .cache
+                .iter()
+                .filter(|(e, _)| e.is_symbol() && e.extract_symbol().ident() == ident)
+                .max_by_key(|(e, _)| {
+                    let symbol = e.extract_symbol();
+                    (symbol.l1_num(), symbol.l2_num())
+                })
+                .map_or("unknown".to_string(), |(_, ast)| show(ast));
+            infos.push(format!(
+                "{ident:?}: {}, base: {}, len: {}, alive: {alive}, contents: {contents}",
+                object.ty(),
+                show(base),
+                show(len),
+            ));
+        }
+        infos
+    }
+}
+
 impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
     fn set_pointer_logic(&mut self) {
-        // A pointer is a tuple (base, offset)
+        // A pointer is a tuple (base, offset, meta)
+        let field_sort = self.pointer_field_sort();
         let pointer_tuple_sort = z3::DatatypeBuilder::new(&self.z3_ctx, "pointer")
             .variant(
                 "pointer",
                 vec![
-                    ("base", DatatypeAccessor::Sort(z3::Sort::int(&self.z3_ctx))),
-                    ("offset", DatatypeAccessor::Sort(z3::Sort::int(&self.z3_ctx))),
-                    ("meta", DatatypeAccessor::Sort(z3::Sort::int(&self.z3_ctx))),
+                    ("base", DatatypeAccessor::Sort(field_sort.clone())),
+                    ("offset", DatatypeAccessor::Sort(field_sort.clone())),
+                    ("meta", DatatypeAccessor::Sort(field_sort)),
                 ],
             )
             .finish();
-        self.datatypes.insert((NString::from("pointer"), vec![]), pointer_tuple_sort);
+        self.pointer_datatype.set(pointer_tuple_sort).expect("Pointer tuple is initialized");
 
         // General pointer sort
         let pointer_sort = self.pointer_sort();
@@ -30,7 +208,7 @@ impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         let box_tuple_sort = z3::DatatypeBuilder::new(&self.z3_ctx, "box")
             .variant("box", vec![("box_ptr", DatatypeAccessor::Sort(pointer_sort.clone()))])
             .finish();
-        self.datatypes.insert((NString::from("box"), vec![]), box_tuple_sort);
+        self.box_datatype.set(box_tuple_sort).expect("Box pointer tuple is initialized");
 
         // A vec pointer is a tuple (pointer, len, cap)
         let vec_tuple_sort = z3::DatatypeBuilder::new(&self.z3_ctx, "vec")
@@ -43,35 +221,29 @@ impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
                 ],
             )
             .finish();
-        self.datatypes.insert((NString::from("vec"), vec![]), vec_tuple_sort);
+        self.vec_datatype.set(vec_tuple_sort).expect("Vec pointer tuple is initialized");
     }
 
     fn pointer_sort(&self) -> z3::Sort<'ctx> {
-        self.datatypes
-            .get(&(NString::from("pointer"), vec![]))
-            .expect("Pointer tuple is not initialized")
-            .sort
-            .clone()
+        self.pointer_datatype().sort.clone()
     }
 
     fn box_sort(&self) -> z3::Sort<'ctx> {
-        self.datatypes
-            .get(&(NString::from("box"), vec![]))
-            .expect("Box pointer tuple is not initialized")
-            .sort
-            .clone()
+        self.box_datatype().sort.clone()
     }
 
     fn vec_sort(&self) -> z3::Sort<'ctx> {
-        self.datatypes
-            .get(&(NString::from("vec"), vec![]))
-            .expect("Vec pointer tuple is not initialized")
-            .sort
-            .clone()
+        self.vec_datatype().sort.clone()
     }
 
     fn create_object_space(&mut self, object: &Expr) -> z3::ast::Dynamic<'ctx> {
         assert!(object.is_symbol());
+        // Zero-sized objects have no space. Pointers to them are dangling
+        // and well-aligned.
+        if object.ty().is_zst() {
+            let align = object.ty().align().expect("Must be sized");
+            return self.mk_smt_int(BigInt::from(align));
+        }
         if self.pointer_logic.contains(object) {
             return self.pointer_logic.get_object_space_base(object);
         }
@@ -87,86 +259,181 @@ impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         // Use l0 as identifier
         let space_base = NString::from(object.extract_symbol().ident()) + "_base";
         let base = self.mk_int_symbol(space_base);
-        let len = if ty.is_array() && ty.array_size() == None {
+        let len = if ty.is_infinite_array() {
             let sym = object.extract_symbol().ident() + "_size";
             self.mk_int_symbol(sym)
         } else {
             self.mk_smt_int(BigInt::from(ty.num_fields()))
         };
 
-        // Base is greater than 0
-        self.assert(self.mk_gt(&base, &self.mk_smt_int(BigInt::ZERO)));
+        // Disjoint with null, even for an empty object
+        self.assert(self.mk_ge(&base, &self.mk_smt_int(BigInt::from(NULL_BASE + 1))));
         // Size is greater or eqaul to 0
         self.assert(self.mk_ge(&len, &self.mk_smt_int(BigInt::ZERO)));
+        // Object space is in the address space
+        if let Some(w) = self.pointer_width {
+            let end = self.mk_add(&base, &len);
+            self.assert(self.mk_le(&end, &self.mk_smt_int(BigInt::from(1) << w)));
+        }
         // Disjoint relationship
         // TODO: remove own object?
-        for (b, l) in self.pointer_logic.object_spaces().values() {
+        for (other, (b, l)) in self.pointer_logic.object_spaces() {
             if space_base == NString::from(b.to_string()) {
                 continue;
             }
-            // No alloc array is active. That means we know the allocation of current
-            // object in symex. No need to encode disjointness.
-            // Be careful for this design.
-            if self.cur_alloc_expr == None {
+            // No pointer points into both objects
+            if self.assume_type_disjoint && !self.exposed && !ty.may_alias(other.ty()) {
                 continue;
             }
-
-            let alloc_array_ast = self.cur_alloc_expr.as_ref().unwrap();
-            let alive = alloc_array_ast.as_array().unwrap().select(b);
-
-            let l1 = base.clone();
-            let r1 = self.mk_add(&l1, &len);
-            let l2 = b.clone();
-            let r2 = self.mk_add(&l2, &l);
-            let no_overlap = self.mk_or(&self.mk_le(&r1, &l2), &self.mk_le(&r2, &l1));
-            let disj = self.mk_implies(&alive, &no_overlap);
-            self.assert(disj);
+            if let Some(disjoint) = self.disjoint((&base, &len), (b, l)) {
+                self.assert(disjoint);
+            }
         }
 
         self.pointer_logic.set_object_space(object.clone(), (base, len));
     }
 
+    fn expose_addresses(&mut self) {
+        if self.exposed {
+            return;
+        }
+        self.exposed = true;
+        if !self.assume_type_disjoint {
+            return;
+        }
+        // The spaces skipped for types that can not alias
+        let spaces = self.pointer_logic.object_spaces();
+        for (i, (x, (b1, l1))) in spaces.iter().enumerate() {
+            for (y, (b2, l2)) in spaces.iter().skip(i + 1) {
+                if !x.ty().may_alias(y.ty()) {
+                    if let Some(disjoint) = self.disjoint((b2, l2), (b1, l1)) {
+                        self.assert(disjoint);
+                    }
+                }
+            }
+        }
+    }
+
     fn mk_pointer(
         &self,
         base: &z3::ast::Dynamic<'ctx>,
         offset: &z3::ast::Dynamic<'ctx>,
         meta: Option<&z3::ast::Dynamic<'ctx>>,
     ) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("pointer"), vec![]);
         let metadata = match meta {
             Some(x) => x.clone(),
             None => self.mk_smt_int(0.into()),
         };
-        self.datatypes.get(&sign).unwrap().variants[0].constructor.apply(&[
-            base as &dyn Ast,
-            offset as &dyn Ast,
-            &metadata as &dyn Ast,
+        self.pointer_datatype().variants[0].constructor.apply(&[
+            &self.to_pointer_field(base) as &dyn Ast,
+            &self.to_pointer_field(offset) as &dyn Ast,
+            &self.to_pointer_field(&metadata) as &dyn Ast,
         ])
     }
 
     fn mk_pointer_base(&self, pt: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("pointer"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[0].apply(&[pt as &dyn Ast])
+        self.pointer_field(pt, 0, false)
     }
 
     fn mk_pointer_offset(&self, pt: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("pointer"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[1].apply(&[pt as &dyn Ast])
+        self.pointer_field(pt, 1, true)
     }
 
     fn mk_pointer_meta(&self, pt: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("pointer"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[2].apply(&[pt as &dyn Ast])
+        self.pointer_field(pt, 2, false)
+    }
+
+    /// For bit-vectors, the offset is moved by `bvadd`, so it wraps around
+    /// as pointer arithmetic in the target.
+    fn mk_pointer_add(
+        &self,
+        pt: &z3::ast::Dynamic<'ctx>,
+        offset: &z3::ast::Dynamic<'ctx>,
+        fat: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        let base = self.raw_pointer_field(pt, 0);
+        let o = self.raw_pointer_field(pt, 1);
+        let new_offset = match self.pointer_width {
+            Some(_) => z3::ast::Dynamic::from(
+                o.as_bv().unwrap().bvadd(&self.to_pointer_field(offset).as_bv().unwrap()),
+            ),
+            None => self.mk_add(&o, offset),
+        };
+        let meta = match fat {
+            true => self.raw_pointer_field(pt, 2),
+            false => self.to_pointer_field(&self.mk_smt_int(BigInt::ZERO)),
+        };
+        self.pointer_datatype().variants[0].constructor.apply(&[
+            &base as &dyn Ast,
+            &new_offset as &dyn Ast,
+            &meta as &dyn Ast,
+        ])
+    }
+
+    fn mk_pointer_address(
+        &self,
+        pt: &z3::ast::Dynamic<'ctx>,
+        elem_size: u64,
+    ) -> z3::ast::Dynamic<'ctx> {
+        let base = self.raw_pointer_field(pt, 0);
+        let offset = self.raw_pointer_field(pt, 1);
+        let size = self.mk_smt_int(BigInt::from(elem_size));
+        match self.pointer_width {
+            Some(w) => {
+                let bytes =
+                    offset.as_bv().unwrap().bvmul(&self.mk_int_to_bv(&size, w).as_bv().unwrap());
+                let address = base.as_bv().unwrap().bvadd(&bytes);
+                self.mk_bv_to_int(&z3::ast::Dynamic::from(address), w, false)
+            }
+            None => self.mk_add(&base, &self.mk_mul(&offset, &size)),
+        }
+    }
+
+    /// For bit-vectors, the fields are compared without converting them to
+    /// integers, and the addresses are compared unsigned.
+    fn mk_pointer_compare(
+        &self,
+        op: BinOp,
+        lhs: &z3::ast::Dynamic<'ctx>,
+        rhs: &z3::ast::Dynamic<'ctx>,
+        fat: bool,
+    ) -> z3::ast::Dynamic<'ctx> {
+        if op == BinOp::Eq || op == BinOp::Ne {
+            let n = if fat { 3 } else { 2 };
+            let eq = (0..n).fold(self.mk_smt_bool(true), |acc, i| {
+                let field_eq =
+                    self.mk_eq(&self.raw_pointer_field(lhs, i), &self.raw_pointer_field(rhs, i));
+                self.mk_and(&acc, &field_eq)
+            });
+            return if op == BinOp::Eq { eq } else { self.mk_not(&eq) };
+        }
+        let a1 = self.raw_pointer_address(lhs);
+        let a2 = self.raw_pointer_address(rhs);
+        if self.pointer_width.is_none() {
+            return match op {
+                BinOp::Ge => self.mk_ge(&a1, &a2),
+                BinOp::Gt => self.mk_gt(&a1, &a2),
+                BinOp::Le => self.mk_le(&a1, &a2),
+                BinOp::Lt => self.mk_lt(&a1, &a2),
+                _ => unsupported!("Not support {op:?} for pointers"),
+            };
+        }
+        let (a1, a2) = (a1.as_bv().unwrap(), a2.as_bv().unwrap());
+        z3::ast::Dynamic::from(match op {
+            BinOp::Ge => a1.bvuge(&a2),
+            BinOp::Gt => a1.bvugt(&a2),
+            BinOp::Le => a1.bvule(&a2),
+            BinOp::Lt => a1.bvult(&a2),
+            _ => unsupported!("Not support {op:?} for pointers"),
+        })
     }
 
     fn mk_box(&self, inner_pt: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("box"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].constructor.apply(&[inner_pt as &dyn Ast])
+        self.box_datatype().variants[0].constructor.apply(&[inner_pt as &dyn Ast])
     }
 
     fn mk_box_ptr(&self, _box: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("box"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[0].apply(&[_box as &dyn Ast])
+        self.box_datatype().variants[0].accessors[0].apply(&[_box as &dyn Ast])
     }
 
     fn mk_vec(
@@ -175,8 +442,7 @@ impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
         len: &z3::ast::Dynamic<'ctx>,
         cap: &z3::ast::Dynamic<'ctx>,
     ) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("vec"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].constructor.apply(&[
+        self.vec_datatype().variants[0].constructor.apply(&[
             inner_pt as &dyn Ast,
             len as &dyn Ast,
             cap as &dyn Ast,
@@ -184,17 +450,47 @@ impl<'ctx> MemSpace<z3::Sort<'ctx>, z3::ast::Dynamic<'ctx>> for Z3Conv<'ctx> {
     }
 
     fn mk_vec_ptr(&self, _vec: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("vec"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[0].apply(&[_vec as &dyn Ast])
+        self.vec_datatype().variants[0].accessors[0].apply(&[_vec as &dyn Ast])
     }
 
     fn mk_vec_len(&self, _vec: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("vec"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[1].apply(&[_vec as &dyn Ast])
+        self.vec_datatype().variants[0].accessors[1].apply(&[_vec as &dyn Ast])
     }
 
     fn mk_vec_cap(&self, _vec: &z3::ast::Dynamic<'ctx>) -> z3::ast::Dynamic<'ctx> {
-        let sign = (NString::from("vec"), vec![]);
-        self.datatypes.get(&sign).unwrap().variants[0].accessors[2].apply(&[_vec as &dyn Ast])
+        self.vec_datatype().variants[0].accessors[2].apply(&[_vec as &dyn Ast])
+    }
+
+    /// A chain of stores of the copied elements. For a symbolic `len`,
+    /// each element of `dst` is copied only if it is in the region, so the
+    /// array of `dst` must be finite.
+    fn copy_region(&mut self, dst: &Expr, src: &Expr, len: Expr) -> z3::ast::Dynamic<'ctx> {
+        let (dst_array, dst_start) = self.region(dst);
+        let (src_array, src_start) = self.region(src);
+        let n = if len.is_constant() {
+            bigint_to_usize(&len.extract_constant().to_integer())
+        } else {
+            match dst_array.ty().array_size() {
+                Some(n) => n as usize,
+                None => unsupported!("Not support copying {len:?} elements to {dst:?}"),
+            }
+        };
+
+        let dst_ast = self.convert_ast(dst_array);
+        let src_ast = self.convert_ast(src_array);
+        let len_ast = self.convert_ast(len.clone());
+        let mut array = dst_ast.clone();
+        for k in 0..n {
+            let k = self.mk_smt_int(BigInt::from(k));
+            let i = self.mk_add(&dst_start, &k);
+            let j = self.mk_add(&src_start, &k);
+            let mut value = self.mk_select(&src_ast, &j);
+            if !len.is_constant() {
+                let in_region = self.mk_lt(&k, &len_ast);
+                value = self.mk_ite(&in_region, &value, &self.mk_select(&dst_ast, &i));
+            }
+            array = self.mk_store(&array, &i, &value);
+        }
+        array
     }
 }
@@ -1,22 +1,26 @@
 use std::alloc::*;
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::{Add, AddAssign};
 
+use rustc_hash::FxHashMap;
+
 /// Used to manage String. Reduce allocation for String
 struct StringManager {
     strings: Vec<String>,
-    map: HashMap<String, usize>,
+    map: FxHashMap<String, usize>,
+    /// Buffer for concatenation. A String is allocated only if the
+    /// result is not interned yet.
+    buffer: String,
 }
 
 impl StringManager {
     fn new() -> Self {
         let strings = vec!["".to_string(), "alloc".to_string(), "INVALID-OBJECT".to_string()];
-        let mut map = HashMap::new();
+        let mut map = FxHashMap::default();
         map.insert("".to_string(), 0);
         map.insert("alloc".to_string(), 1);
         map.insert("INVALID-OBJECT".to_string(), 2);
-        StringManager { strings, map }
+        StringManager { strings, map, buffer: String::new() }
     }
 
     fn get_string(&self, i: usize) -> &String {
@@ -24,12 +28,28 @@ impl StringManager {
         &self.strings[i]
     }
 
-    fn get_id(&mut self, s: &String) -> usize {
-        if !self.map.contains_key(s) {
-            self.strings.push(s.clone());
-            self.map.insert(s.clone(), self.strings.len() - 1);
+    fn get_id(&mut self, s: &str) -> usize {
+        if let Some(i) = self.map.get(s) {
+            return *i;
         }
-        *self.map.get(s).expect("Do not exists")
+        self.strings.push(s.to_string());
+        self.map.insert(s.to_string(), self.strings.len() - 1);
+        self.strings.len() - 1
+    }
+
+    fn concat(&mut self, i: usize, rhs: &str) -> usize {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        buffer.push_str(self.get_string(i));
+        buffer.push_str(rhs);
+        let id = self.get_id(&buffer);
+        self.buffer = buffer;
+        id
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.strings.reserve(additional);
+        self.map.reserve(additional);
     }
 }
 
@@ -80,6 +100,16 @@ impl NString {
         NString::from(&str[l..r])
     }
 
+    /// The number of distinct strings interned so far
+    pub fn intern_count() -> usize {
+        string_m().strings.len()
+    }
+
+    /// Reserve capacity for at least `additional` more strings
+    pub fn reserve(additional: usize) {
+        string_m().reserve(additional);
+    }
+
     /// During the symex, we do not delete any NString.
     /// Thus, returning static str is safe.
     pub fn as_str(&self) -> &'static str {
@@ -108,7 +138,7 @@ impl PartialEq<&String> for NString {
 impl Add for NString {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        NString::from(self + string_m().get_string(rhs.0).as_str())
+        self + rhs.as_str()
     }
 }
 
@@ -122,14 +152,13 @@ impl Add<String> for NString {
 impl Add<&str> for NString {
     type Output = Self;
     fn add(self, rhs: &str) -> Self::Output {
-        let new_string = string_m().get_string(self.0).clone() + rhs;
-        NString(string_m().get_id(&new_string))
+        NString(string_m().concat(self.0, rhs))
     }
 }
 
 impl AddAssign for NString {
     fn add_assign(&mut self, rhs: Self) {
-        self.0 = NString::from(*self + string_m().get_string(rhs.0).as_str()).0
+        self.0 = (*self + rhs).0
     }
 }
 
@@ -153,13 +182,13 @@ impl Debug for NString {
 
 impl From<String> for NString {
     fn from(value: String) -> Self {
-        NString(string_m().get_id(&value))
+        NString::from(value.as_str())
     }
 }
 
 impl From<&str> for NString {
     fn from(value: &str) -> Self {
-        NString::from(value.to_string())
+        NString(string_m().get_id(value))
     }
 }
 
@@ -168,3 +197,26 @@ impl ToString for NString {
         string_m().get_string(self.0).clone()
     }
 }
+
+/// Build a NString piece by piece. Only the result is interned.
+#[derive(Default)]
+pub struct NStringBuilder(String);
+
+impl NStringBuilder {
+    pub fn new(s: &str) -> Self {
+        NStringBuilder(s.to_string())
+    }
+
+    pub fn push_str(&mut self, s: &str) -> &mut Self {
+        self.0.push_str(s);
+        self
+    }
+
+    pub fn push(&mut self, s: NString) -> &mut Self {
+        self.push_str(s.as_str())
+    }
+
+    pub fn build(&self) -> NString {
+        NString::from(self.0.as_str())
+    }
+}
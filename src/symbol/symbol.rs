@@ -68,6 +68,10 @@ impl Symbol {
         self.l1_num
     }
 
+    pub fn l2_num(&self) -> usize {
+        self.l2_num
+    }
+
     pub fn l1_name(&self) -> NString {
         self.ident + "::" + self.l1_num.to_string()
     }
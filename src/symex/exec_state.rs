@@ -1,4 +1,6 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
 
 use num_bigint::BigInt;
 use stable_mir::CrateDef;
@@ -9,7 +11,9 @@ use super::frame::*;
 use super::namespace::Namespace;
 use super::renaming::*;
 use super::state::*;
+use super::symex_borrow::Borrows;
 use super::value_set::ObjectSet;
+use crate::bmc::error::unsupported;
 use crate::config::config::Config;
 use crate::expr::context::*;
 use crate::expr::expr::*;
@@ -32,8 +36,29 @@ pub struct ExecutionState<'cfg> {
     pub(super) ns: Namespace,
     func_cnt: Vec<usize>,
     frames: Vec<Frame<'cfg>>,
-    pub(super) objects: Vec<Expr>,
+    /// Heap objects with their allocation sites
+    pub(super) objects: Vec<(Expr, Option<Span>)>,
+    /// Heap objects of infinite array types with runtime lengths
+    pub(super) sized_objects: HashSet<NString>,
+    /// Loans of objects for the aliasing check
+    pub(super) borrows: Borrows,
+    /// Shadow arrays of `written` flags of alloced objects for the
+    /// uninitialized-read check
+    pub(super) written_flags: HashMap<NString, Expr>,
+    /// Shadow strong counts of the objects shared by `Rc` and `Arc`
+    pub(super) rc_counts: HashMap<NString, Expr>,
+    /// Shadow capacities of the buffers of vecs, which bound the raw
+    /// pointers into them
+    pub(super) vec_caps: HashMap<NString, Expr>,
+    /// Shadow `moved` flags of non-`Copy` locals for the use-after-move
+    /// check
+    pub(super) moved_flags: HashMap<NString, Expr>,
     pub(super) renaming: RefCell<Renaming>,
+    /// Number of paths explored and cut by `--max-paths`
+    pub(super) num_paths: usize,
+    pub(super) cut_paths: usize,
+    /// Number of auxiliary variables
+    tmp_cnt: usize,
 }
 
 impl<'cfg> ExecutionState<'cfg> {
@@ -46,7 +71,16 @@ impl<'cfg> ExecutionState<'cfg> {
             func_cnt: vec![0; config.program.size()],
             frames: Vec::new(),
             objects: Vec::new(),
+            sized_objects: HashSet::new(),
+            borrows: Borrows::default(),
+            written_flags: HashMap::new(),
+            rc_counts: HashMap::new(),
+            vec_caps: HashMap::new(),
+            moved_flags: HashMap::new(),
             renaming: RefCell::new(Renaming::default()),
+            num_paths: 1,
+            cut_paths: 0,
+            tmp_cnt: 0,
         }
     }
 
@@ -64,6 +98,20 @@ impl<'cfg> ExecutionState<'cfg> {
         self.frames.len() > 1 || self.frames.len() == 1 && self.top().cur_pc() != None
     }
 
+    /// A fresh auxiliary variable, e.g., for keeping a value in swapping
+    pub fn new_tmp(&mut self, ty: Type) -> Expr {
+        let ident = NString::from("tmp_") + self.tmp_cnt.to_string();
+        self.tmp_cnt += 1;
+        self.l0_symbol(ident, ty)
+    }
+
+    /// Whether the length of an infinite array object is known
+    pub fn is_sized_object(&self, object: &Expr) -> bool {
+        let root_object = object.extract_root_object();
+        let ident = root_object.extract_inner_expr().extract_symbol().ident();
+        self.sized_objects.contains(&ident)
+    }
+
     pub fn new_object(&mut self, ty: Type) -> Expr {
         let name = NString::from("heap_object_") + self.objects.len().to_string();
         let symbol = Symbol::from(name);
@@ -72,7 +120,7 @@ impl<'cfg> ExecutionState<'cfg> {
         self.ns.insert_symbol(sym_expr.clone());
         // Create an object not being owned by any variable.
         let object = self.ctx.object(sym_expr);
-        self.objects.push(object.clone());
+        self.objects.push((object.clone(), self.span));
         object
     }
 
@@ -116,6 +164,12 @@ impl<'cfg> ExecutionState<'cfg> {
         }
     }
 
+    /// The number of frames of the function in the stack
+    pub fn recursion_depth(&self, i: FunctionIdx) -> usize {
+        let function = self.config.program.function(i);
+        self.frames.iter().filter(|frame| std::ptr::eq(frame.function, function)).count()
+    }
+
     pub fn pop_frame(&mut self) -> Frame<'cfg> {
         assert!(!self.frames.is_empty());
         self.frames.pop().unwrap()
@@ -259,7 +313,7 @@ impl<'cfg> ExecutionState<'cfg> {
             return;
         }
 
-        panic!("Do not support place state: {place:?}");
+        unsupported!("Do not support place state: {place:?}");
     }
 
     pub fn assignment(&mut self, mut lhs: Expr, rhs: Expr) {
@@ -269,10 +323,16 @@ impl<'cfg> ExecutionState<'cfg> {
             self.rename(&mut lhs, Level::Level1);
         }
 
+        // `Layout` is only used for allocation. It is always propagated,
+        // even if its size is symbolic.
+        if lhs.ty().contains_layout() {
+            self.renaming.borrow_mut().constant_propagate(lhs, Some(rhs));
+            return;
+        }
+
         // Constant propagation
         self.constant_propagate(lhs.clone(), rhs.clone());
 
-        // `Layout` is only used for allocation
         if rhs.is_type() {
             return;
         }
@@ -310,6 +370,28 @@ impl<'cfg> ExecutionState<'cfg> {
             return;
         }
 
+        if lhs.ty().is_enum() {
+            // Pointers in the data of each variant
+            for (k, (_, fields)) in lhs.ty().enum_def().1.iter().enumerate() {
+                let Some((_, data_ty)) = fields.first() else {
+                    continue;
+                };
+                let k = self.ctx.constant_usize(k);
+                let lhs_data = self.ctx.object(self.ctx.as_variant(lhs.clone(), k.clone()));
+                let rhs_data = self.ctx.object(self.ctx.as_variant(rhs.clone(), k));
+                for (i, ty) in data_ty.tuple_def().iter().enumerate() {
+                    if !ty.is_any_ptr() {
+                        continue;
+                    }
+                    let i = self.ctx.constant_isize(i as isize);
+                    let new_lhs = self.ctx.index(lhs_data.clone(), i.clone(), *ty);
+                    let new_rhs = self.ctx.index(rhs_data.clone(), i.clone(), *ty);
+                    self.update_value_set_rec(new_lhs, new_rhs);
+                }
+            }
+            return;
+        }
+
         if lhs.ty().is_array() {
             if lhs.ty().elem_type().is_any_ptr() {
                 // TODO
@@ -68,7 +68,9 @@ impl<'func> Frame<'func> {
         }
 
         if self.state_map.is_empty() {
-            panic!("We stuck in a loop, please increase the loop bound");
+            // All paths are cut by the bounds
+            self.pc = self.function.size();
+            return;
         }
 
         self.pc = *self.state_map.keys().min().unwrap();
@@ -10,6 +10,7 @@ mod value_set;
 pub mod symex;
 pub(super) mod symex_assert;
 pub(super) mod symex_assign;
+pub(super) mod symex_borrow;
 pub(super) mod symex_branch;
 pub(super) mod symex_builtin;
 pub(super) mod symex_drop;
@@ -17,4 +18,5 @@ pub(super) mod symex_function;
 pub(super) mod symex_memory;
 mod symex_modules;
 pub(super) mod symex_move;
+pub(super) mod symex_uninit;
 pub(super) mod symex_util;
@@ -5,6 +5,8 @@ use stable_mir::ty::IndexedVal;
 use super::place_state::PlaceState;
 use super::symex::Symex;
 use super::value_set::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::context::ExprCtx;
 use crate::expr::expr::*;
 use crate::expr::guard::*;
@@ -15,6 +17,8 @@ use crate::symbol::symbol::*;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Mode {
     Read,
+    /// The place is only written, e.g. the destination of `ptr::write`
+    Write,
     Drop,
     Dealloc,
     Slice(Option<usize>, Option<usize>),
@@ -44,12 +48,15 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
 
         for elem in project_elem {
             ret = match elem {
-                ProjectionElem::Deref => self.project_deref(
-                    ret.clone(),
-                    Mode::Read,
-                    Guard::new(self._ctx.clone()),
-                    ret.ty().pointee_ty(),
-                ),
+                ProjectionElem::Deref => {
+                    self._callback_symex.aliasing_check(&ret);
+                    self.project_deref(
+                        ret.clone(),
+                        Mode::Read,
+                        Guard::new(self._ctx.clone()),
+                        ret.ty().pointee_ty(),
+                    )
+                }
                 ProjectionElem::Field(i, ty) => self.project_field(ret.clone(), i, Type::from(ty)),
                 ProjectionElem::Index(local) => {
                     let mut index =
@@ -68,7 +75,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
                     let def = ret.ty().enum_def();
                     self._ctx.as_variant(ret, self._ctx.constant_usize(idx))
                 }
-                _ => panic!("Not support {elem:?} for {ret:?}"),
+                _ => unsupported!("Not support {elem:?} for {ret:?}"),
             };
         }
 
@@ -93,6 +100,11 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
                 continue;
             }
 
+            // A well-aligned pointer to a zero-sized object is always valid
+            if ty.is_zst() && object.is_unknown() {
+                continue;
+            }
+
             if object.is_unknown() {
                 self.dereference_invalid_ptr(pt.clone(), mode, guard.clone());
                 continue;
@@ -109,15 +121,42 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
 
             // Valid check
             let place_state = self._callback_symex.exec_state.get_place_state(&root_object);
-            if place_state.is_unknown() || place_state.is_dead() {
-                self.valid_check(root_object.clone(), place_state, mode, pointer_guard.clone());
+            if (place_state.is_unknown() || place_state.is_dead()) && !ty.is_zst() {
+                self.valid_check(
+                    pt.clone(),
+                    root_object.clone(),
+                    place_state,
+                    mode,
+                    pointer_guard.clone(),
+                );
             }
 
+            // A non-constant offset is only known by the pointer
+            let offset = offset.map(|o| {
+                if o.is_constant() {
+                    return o;
+                }
+                let mut pointer_offset = self._ctx.pointer_offset(pt.clone());
+                self._callback_symex.rename(&mut pointer_offset);
+                pointer_offset
+            });
+
             if mode == Mode::Drop || mode == Mode::Dealloc {
                 self.dealloc_check(object.clone(), offset, ty, mode, pointer_guard.clone());
                 continue;
             }
 
+            if let Some(o) = &offset {
+                if !self.pointer_bound_check(
+                    root_object.clone(),
+                    o.clone(),
+                    ty,
+                    pointer_guard.clone(),
+                ) {
+                    continue;
+                }
+            }
+
             let new_ret = self.build_ret(object, offset, mode, pointer_guard.clone(), ty);
             if new_ret == None {
                 continue;
@@ -157,25 +196,49 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
         assert!(ty.is_array() || ty.is_slice());
         let elem_ty = ty.elem_type();
 
+        // Visit the root object of a slice with the index shifted by
+        // the start of the slice.
+        let inner_expr =
+            if object.is_object() { object.extract_inner_expr() } else { object.clone() };
+        if inner_expr.is_slice() {
+            let slice = inner_expr;
+            self.bound_check(
+                self._ctx.object(slice.clone()),
+                index.clone(),
+                self._ctx._true().into(),
+            );
+            let start = slice.extract_slice_start();
+            let start = if start.ty() == index.ty() {
+                start
+            } else {
+                self._ctx.cast(start, self._ctx.mk_type(index.ty()))
+            };
+            let mut new_index = self._ctx.add(start, index);
+            new_index.simplify();
+            return self.project_index(slice.extract_object(), new_index);
+        }
+
         if index.is_constant() {
             let offset = index.extract_constant().to_integer();
             self.build_with_const_offset(object, offset, self._ctx._true().into(), elem_ty, false)
                 .unwrap()
         } else {
-            todo!("Non-const index")
+            let new_object = if object.is_object() { object } else { self._ctx.object(object) };
+            self.bound_check(new_object.clone(), index.clone(), self._ctx._true().into());
+            self._ctx.index(new_object, index, elem_ty)
         }
     }
 
     fn build_ret(
         &mut self,
         object: Expr,
-        offset: Option<BigInt>,
+        offset: Option<Expr>,
         mode: Mode,
         guard: Guard,
         ty: Type,
     ) -> Option<Expr> {
         match mode {
-            Mode::Read => self.build_read(object, offset, guard, ty),
+            Mode::Read | Mode::Write => self.build_read(object, offset, guard, ty),
             Mode::Slice(l, r) => self.build_slice(object, offset, l, r),
             _ => todo!(),
         }
@@ -184,7 +247,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
     fn build_read(
         &mut self,
         object: Expr,
-        offset: Option<BigInt>,
+        offset: Option<Expr>,
         guard: Guard,
         ty: Type,
     ) -> Option<Expr> {
@@ -194,24 +257,32 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
             offset
         } else {
             // Access part of object
-            let range = if object.ty().is_array() {
+            let (range, i) = if object.ty().is_array() {
                 // Access one index of an array
-                object.ty().elem_type()
+                (object.ty().elem_type(), 0)
             } else if object.ty().is_slice() {
                 // Access one index of a slice
-                object.ty().elem_type()
+                (object.ty().elem_type(), 0)
             } else if object.ty().is_struct() {
-                // Access the first field of a stuct
-                object.ty().struct_def().1[0].1
+                // Access the first field of a stuct in memory
+                let i = object.ty().struct_first_field();
+                (object.ty().struct_def().1[i].1, i)
             } else {
-                panic!("Impossible for access {:?} with {ty:?}", object.ty())
+                panic!("Impossible for access {} with {ty}", object.ty())
             };
             assert!(range == ty);
-            Some(BigInt::ZERO)
+            Some(self._ctx.constant_isize(i as isize))
         };
 
         if let Some(x) = final_offset {
-            self.build_with_const_offset(object, x, guard, ty, true)
+            if x.is_constant() {
+                let offset = x.extract_constant().to_integer();
+                self.build_with_const_offset(object, offset, guard, ty, false)
+            } else {
+                assert!(object.ty().is_array() && object.ty().elem_type() == ty);
+                let new_object = if object.is_object() { object } else { self._ctx.object(object) };
+                Some(self._ctx.index(new_object, x, ty))
+            }
         } else {
             Some(object.extract_inner_expr())
         }
@@ -236,7 +307,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
     fn build_slice(
         &mut self,
         object: Expr,
-        offset: Option<BigInt>,
+        offset: Option<Expr>,
         l: Option<usize>,
         r: Option<usize>,
     ) -> Option<Expr> {
@@ -244,7 +315,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
         assert!(object.ty().is_array() || inner_expr.is_slice());
         let (root_object, start, len) = if object.ty().is_array() {
             let start = match offset {
-                Some(o) => o,
+                Some(o) => o.extract_constant().to_integer(),
                 None => BigInt::ZERO,
             };
             let len = object.ty().array_size().expect("array must has len");
@@ -273,7 +344,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
                 "slicing fail: [{:?}, {:?}) must be in {:?}[{:?}, {:?})",
                 &new_start, &new_end, root_object, &start, &end
             ));
-            self._callback_symex.claim(msg, self._ctx._false().into());
+            self._callback_symex.claim(Check::Bound, msg, self._ctx._false().into());
             None
         } else {
             let slice_start = self._ctx.constant_integer(new_start, Type::usize_type());
@@ -288,54 +359,154 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
         self._ctx.object(l1_symbol)
     }
 
-    fn valid_check(&mut self, object: Expr, state: PlaceState, mode: Mode, guard: Guard) {
+    fn valid_check(&mut self, pt: Expr, object: Expr, state: PlaceState, mode: Mode, guard: Guard) {
         assert!(object.is_object());
-        let invalid =
-            if state.is_unknown() { self._ctx.invalid(object.clone()) } else { self._ctx._true() };
-        let msg = match mode {
-            Mode::Read | Mode::Slice(..) => {
-                format!("dereference failure: {object:?} is dead").into()
+        let is_heap = object.extract_inner_expr().extract_symbol().is_heap_symbol();
+        let invalid = if is_heap {
+            // A heap object is dead only if it has been dealloced. The base of
+            // a dealloced object may be reused by a new object. Thus, check the
+            // alloc array with the base of the pointer instead of the object.
+            let alloc_array = self._callback_symex.exec_state.ns.lookup_object(NString::ALLOC_SYM);
+            let pointer_base = self._ctx.pointer_base(pt);
+            self._ctx.not(self._ctx.index(alloc_array, pointer_base, Type::bool_type()))
+        } else if state.is_unknown() {
+            self._ctx.invalid(object.clone())
+        } else {
+            self._ctx._true()
+        };
+        let (check, msg) = match mode {
+            Mode::Read | Mode::Write | Mode::Slice(..) => {
+                if is_heap {
+                    (
+                        Check::UseAfterFree,
+                        format!("dereference failure: use after free of {object:?}").into(),
+                    )
+                } else {
+                    (Check::UseAfterFree, format!("dereference failure: {object:?} is dead").into())
+                }
             }
             Mode::Dealloc | Mode::Drop => {
-                format!("{} failure: {object:?} is dead", format!("{mode:?}").to_lowercase()).into()
+                let mode = format!("{mode:?}").to_lowercase();
+                if is_heap {
+                    (Check::DoubleFree, format!("{mode} failure: double free of {object:?}").into())
+                } else {
+                    (Check::InvalidFree, format!("{mode} failure: {object:?} is dead").into())
+                }
             }
         };
         let mut error = guard.clone();
         error.add(invalid);
-        self._callback_symex.claim(msg, error.to_expr());
+        self._callback_symex.claim(check, msg, error.to_expr());
     }
 
     fn bound_check(&mut self, object: Expr, index: Expr, guard: Guard) {
         assert!(object.is_object());
-        let array_ty = object.ty();
-        let s = array_ty.array_size();
-        if let Some(len) = s {
-            let mut out_of_bound = self._ctx.or(
-                self._ctx.lt(index.clone(), self._ctx.constant_isize(0)),
-                self._ctx.ge(index.clone(), self._ctx.constant_isize(len as isize)),
-            );
-            self._callback_symex.rename(&mut out_of_bound);
-            out_of_bound.simplify();
-            let msg = NString::from(format!("dereference failure: index out of array bound"));
-            let mut error = guard.clone();
-            error.add(out_of_bound);
-            self._callback_symex.claim(msg, error.to_expr());
+        let ty = object.ty();
+        let (len, kind) = if ty.is_infinite_array() {
+            if !self._callback_symex.exec_state.is_sized_object(&object) {
+                return;
+            }
+            let len = self._ctx.object_size(object.clone());
+            (self._ctx.cast(len, self._ctx.mk_type(index.ty())), "array")
+        } else if ty.is_array() {
+            match ty.array_size() {
+                Some(n) => (self._ctx.constant_integer(BigInt::from(n), index.ty()), "array"),
+                None => return,
+            }
+        } else if ty.is_slice() && object.extract_inner_expr().is_slice() {
+            let len = object.extract_inner_expr().extract_slice_len();
+            let len = if len.is_constant() {
+                self._ctx.constant_integer(len.extract_constant().to_integer(), index.ty())
+            } else if len.ty() != index.ty() {
+                self._ctx.cast(len, self._ctx.mk_type(index.ty()))
+            } else {
+                len
+            };
+            (len, "slice")
+        } else {
+            return;
+        };
+        let zero = self._ctx.constant_integer(BigInt::ZERO, index.ty());
+        let mut out_of_bound =
+            self._ctx.or(self._ctx.lt(index.clone(), zero), self._ctx.ge(index.clone(), len));
+        self._callback_symex.rename(&mut out_of_bound);
+        out_of_bound.simplify();
+        let msg = NString::from(format!("dereference failure: index out of {kind} bound"));
+        let mut error = guard.clone();
+        error.add(out_of_bound);
+        self._callback_symex.claim(Check::Bound, msg, error.to_expr());
+    }
+
+    /// The offset of a pointer must be in the object, where the length of
+    /// object is the number of fields, the same as its space in SMT. Return
+    /// false if the offset is out of bound for sure.
+    fn pointer_bound_check(&mut self, object: Expr, offset: Expr, ty: Type, guard: Guard) -> bool {
+        let msg =
+            NString::from(format!("dereference failure: pointer out of bounds of {object:?}"));
+        if object.ty().is_infinite_array() {
+            // The length is symbolic if it is known. The buffer of a vec is
+            // bounded by its capacity, except that the whole buffer is
+            // accessed by the vec itself.
+            let ident = object.extract_inner_expr().extract_symbol().ident();
+            let len = if let Some(cap) = self._callback_symex.exec_state.vec_caps.get(&ident) {
+                (ty != object.ty()).then(|| cap.clone())
+            } else if self._callback_symex.exec_state.is_sized_object(&object) {
+                Some(self._ctx.object_size(object.clone()))
+            } else {
+                None
+            };
+            if let Some(len) = len {
+                let len = self._ctx.cast(len, self._ctx.mk_type(offset.ty()));
+                let zero = self._ctx.constant_integer(BigInt::ZERO, offset.ty());
+                let mut out_of_bound =
+                    self._ctx.or(self._ctx.lt(offset.clone(), zero), self._ctx.ge(offset, len));
+                self._callback_symex.rename(&mut out_of_bound);
+                let mut error = guard.clone();
+                error.add(out_of_bound);
+                self._callback_symex.claim(Check::Bound, msg, error.to_expr());
+            }
+            return true;
+        }
+        if object.ty().is_slice() {
+            return true;
         }
+        let len = BigInt::from(object.ty().num_fields());
+        if offset.is_constant() {
+            let o = offset.extract_constant().to_integer();
+            if BigInt::ZERO <= o && o < len {
+                return true;
+            }
+            self._callback_symex.claim(Check::Bound, msg, guard.to_expr());
+            return false;
+        }
+        let zero = self._ctx.constant_integer(BigInt::ZERO, offset.ty());
+        let len = self._ctx.constant_integer(len, offset.ty());
+        let mut out_of_bound =
+            self._ctx.or(self._ctx.lt(offset.clone(), zero), self._ctx.ge(offset, len));
+        out_of_bound.simplify();
+        let mut error = guard.clone();
+        error.add(out_of_bound);
+        self._callback_symex.claim(Check::Bound, msg, error.to_expr());
+        true
     }
 
     fn dereference_null(&mut self, pt: Expr, guard: Guard, mode: Mode) {
         assert!(pt.ty().is_any_ptr());
         let null = self._ctx.null(pt.ty());
         let msg = match mode {
-            Mode::Read => "dereference failure: null pointer dereference".into(),
+            Mode::Read | Mode::Write | Mode::Slice(..) => {
+                "dereference failure: null pointer dereference".into()
+            }
+            Mode::Drop => "drop failure: drop a null pointer".into(),
             Mode::Dealloc => "dealloc failure: dealloce a null pointer".into(),
-            _ => todo!(),
         };
-        let mut is_null = self._ctx.eq(pt, null);
+        // Base 0 is reserved for null. A pointer is null as long as its
+        // base is 0, no matter what the offset is.
+        let mut is_null = self._ctx.eq(self._ctx.pointer_base(pt), self._ctx.pointer_base(null));
         self._callback_symex.rename(&mut is_null);
         let mut error = guard.clone();
         error.add(is_null);
-        self._callback_symex.claim(msg, error.to_expr());
+        self._callback_symex.claim(Check::Null, msg, error.to_expr());
     }
 
     fn dereference_invalid_ptr(&mut self, pt: Expr, mode: Mode, guard: Guard) {
@@ -347,40 +518,56 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
             self._ctx.not(self._ctx.index(alloc_array, pointer_base, Type::bool_type()));
         self._callback_symex.rename(&mut not_alloced);
         let msg = match mode {
-            Mode::Read => NString::from("dereference failure: invalid pointer"),
+            Mode::Read | Mode::Write => NString::from("dereference failure: invalid pointer"),
             // TODO: support more smart pointer
             Mode::Drop => format!("drop failure: uninitilized {:?} pointer", pt.ty().name()).into(),
             Mode::Dealloc => NString::from("dealloc failure: invalid pointer"),
-            _ => todo!(),
+            _ => unsupported!("{mode:?} an invalid pointer"),
+        };
+        let check = if matches!(mode, Mode::Read | Mode::Write) {
+            Check::InvalidPointer
+        } else {
+            Check::InvalidFree
         };
         let mut error = guard.clone();
         error.add(not_null);
         error.add(not_alloced);
-        self._callback_symex.claim(msg, error.to_expr());
+        self._callback_symex.claim(check, msg, error.to_expr());
     }
 
     fn dealloc_check(
         &mut self,
         object: Expr,
-        offset: Option<BigInt>,
+        offset: Option<Expr>,
         ty: Type,
         mode: Mode,
         guard: Guard,
     ) {
+        // Only heap objects can be dealloced
+        let root_object = object.extract_root_object();
+        if !root_object.extract_inner_expr().extract_symbol().is_heap_symbol() {
+            let msg = format!(
+                "{} failure: {root_object:?} is not allocated on heap",
+                format!("{mode:?}").to_lowercase()
+            )
+            .into();
+            self._callback_symex.claim(Check::InvalidFree, msg, guard.to_expr());
+            return;
+        }
+
         let object_ty = object.ty();
         // Offset check
-        let tmp_object = if object_ty.is_primitive() || object_ty.is_any_ptr() {
-            object.clone()
-        } else {
-            self._ctx.index(
-                object.clone(),
-                if let Some(x) = offset {
-                    self._ctx.constant_integer(x, Type::isize_type())
+        let tmp_object = match offset {
+            Some(x) if !object_ty.is_primitive() && !object_ty.is_any_ptr() => {
+                let index = if x.is_constant() {
+                    self._ctx
+                        .constant_integer(x.extract_constant().to_integer(), Type::isize_type())
                 } else {
-                    self._ctx.constant_integer(BigInt::ZERO, Type::isize_type())
-                },
-                ty,
-            )
+                    self._ctx.cast(x, self._ctx.mk_type(Type::isize_type()))
+                };
+                self._ctx.index(object.clone(), index, ty)
+            }
+            _ => object.clone(),
         };
         let total_offset = tmp_object.compute_offset();
         let msg = format!(
@@ -391,10 +578,32 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
         let mut new_guard = guard.clone();
         let zero = self._ctx.constant_isize(0);
         new_guard.add(self._ctx.ne(total_offset, zero));
-        self._callback_symex.claim(msg, new_guard.to_expr());
-
-        // Check layout
-        if object_ty != ty {
+        self._callback_symex.claim(Check::InvalidFree, msg, new_guard.to_expr());
+
+        // Check layout. The size and alignment must be same as the allocation.
+        // The size of an infinite array with a known length is symbolic.
+        let layout = |t: Type| (t.size_in_bytes(), t.align());
+        let sized = object_ty.is_infinite_array()
+            && self._callback_symex.exec_state.is_sized_object(&object)
+            && object_ty.elem_type().align() == ty.align();
+        if sized && ty.size_in_bytes().is_some() {
+            let msg = format!(
+                "{} failure: the layout is {ty:?} where [{:?}; size({object:?})] is required",
+                format!("{mode:?}").to_lowercase(),
+                object_ty.elem_type()
+            )
+            .into();
+            let elem_size = object_ty.elem_type().size_in_bytes().unwrap() as usize;
+            let size = self
+                ._ctx
+                .mul(self._ctx.object_size(object.clone()), self._ctx.constant_usize(elem_size));
+            let required = self._ctx.constant_usize(ty.size_in_bytes().unwrap() as usize);
+            let mut mismatch = self._ctx.ne(size, required);
+            self._callback_symex.rename(&mut mismatch);
+            let mut new_guard = guard.clone();
+            new_guard.add(mismatch);
+            self._callback_symex.claim(Check::InvalidFree, msg, new_guard.to_expr());
+        } else if object_ty != ty && layout(object_ty) != layout(ty) {
             let msg = format!(
                 "{} failure: the layout is {ty:?} where {:?} is required",
                 format!("{mode:?}").to_lowercase(),
@@ -403,7 +612,7 @@ impl<'a, 'cfg> Projection<'a, 'cfg> {
             .into();
             let mut new_guard = guard.clone();
             new_guard.add(self._ctx._true());
-            self._callback_symex.claim(msg, new_guard.to_expr());
+            self._callback_symex.claim(Check::InvalidFree, msg, new_guard.to_expr());
         }
     }
 }
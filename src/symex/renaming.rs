@@ -52,6 +52,13 @@ impl Renaming {
         }
     }
 
+    /// Use the current l1 numbers of `rhs`
+    pub fn restore_l1(&mut self, rhs: &Renaming) {
+        for (ident, n) in rhs.l1_renaming.iter() {
+            self.l1_renaming.insert(*ident, *n);
+        }
+    }
+
     pub fn current_l1_symbol(&mut self, ident: NString) -> Symbol {
         let l1_num = self.l1_num(ident, false);
         Symbol::new(ident, l1_num, 0, Level::Level1)
@@ -120,7 +127,7 @@ impl Renaming {
     }
 
     pub fn l2_rename(&mut self, expr: &mut Expr, propagate: bool) {
-        if expr.is_address_of() {
+        if expr.is_address_of() || expr.is_object_size() {
             self.l1_rename(expr);
             return;
         }
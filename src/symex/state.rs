@@ -5,9 +5,11 @@ use std::fmt::Debug;
 use super::place_state::*;
 use super::renaming::Renaming;
 use super::value_set::*;
+use crate::bmc::error::unsupported;
 use crate::expr::context::*;
 use crate::expr::expr::*;
 use crate::expr::guard::Guard;
+use crate::expr::ty::Type;
 use crate::program::program::bigint_to_usize;
 use crate::symbol::nstring::NString;
 
@@ -101,6 +103,12 @@ impl State {
             return;
         }
 
+        if expr.is_as_variant() {
+            let i = expr.extract_variant_idx();
+            self.assign_rec(expr.extract_enum(), suffix + format!("#{i}"), values);
+            return;
+        }
+
         assert!(expr.ty().is_any_ptr());
 
         if expr.is_index() {
@@ -115,7 +123,7 @@ impl State {
             return;
         }
 
-        todo!("assign value set for {expr:?}");
+        unsupported!("assign value set for {expr:?}");
     }
 
     pub fn merge(&mut self, rhs: &State) {
@@ -165,11 +173,10 @@ impl State {
                 values.insert((object, None));
             } else if inner_expr.is_index() {
                 let root_object = inner_expr.extract_object();
-                let index = inner_expr.extract_index().extract_constant();
-                let offset = index.to_integer();
-                values.insert((root_object, Some(offset)));
+                let index = inner_expr.extract_index();
+                values.insert((root_object, Some(index)));
             } else {
-                todo!("get value set from addressof({object:?})");
+                unsupported!("get value set from addressof({object:?})");
             }
             return;
         }
@@ -177,16 +184,19 @@ impl State {
         if expr.is_offset() {
             let pt = expr.extract_root_pointer();
             let off = expr.extract_offset();
-            // TODO: support dynamic offset
-            assert!(off.is_constant());
-            let offset = off.extract_constant().to_integer();
             let mut objects = HashSet::new();
             self.get_value_set_rec(pt, suffix, &mut objects);
             // Compute new offset
             for (object, o) in objects {
                 let new_offset = match o {
-                    Some(x) => offset.clone() + x,
-                    None => offset.clone(),
+                    Some(x) if x.is_constant() && off.is_constant() => {
+                        let offset =
+                            x.extract_constant().to_integer() + off.extract_constant().to_integer();
+                        expr.ctx.constant_integer(offset, Type::isize_type())
+                    }
+                    // Dynamic offset
+                    Some(x) if !x.is_constant() => x,
+                    _ => off.clone(),
                 };
                 values.insert((object, Some(new_offset)));
             }
@@ -236,6 +246,9 @@ impl State {
                 }
             } else if inner_expr.is_ite() {
                 self.get_value_set_rec(inner_expr, new_suffix, values);
+            } else if inner_expr.is_as_variant() {
+                let k = inner_expr.extract_variant_idx();
+                self.get_variant_value_set(inner_expr.extract_enum(), k, i, suffix, values);
             } else if inner_expr.is_unknown() {
                 values.insert((expr.ctx.unknown(expr.ty().pointee_ty()), None));
             } else {
@@ -255,7 +268,48 @@ impl State {
             return;
         }
 
-        panic!("Do not support dereferencing:\n{expr:?}");
+        unsupported!("Do not support dereferencing:\n{expr:?}");
+    }
+
+    /// The value set of the `i`-th field in the data of the `k`-th variant
+    fn get_variant_value_set(
+        &self,
+        _enum: Expr,
+        k: usize,
+        i: usize,
+        suffix: NString,
+        values: &mut ObjectSet,
+    ) {
+        if _enum.is_object() {
+            self.get_variant_value_set(_enum.extract_inner_expr(), k, i, suffix, values);
+            return;
+        }
+
+        if _enum.is_move() {
+            self.get_variant_value_set(_enum.extract_object(), k, i, suffix, values);
+            return;
+        }
+
+        if _enum.is_ite() {
+            let true_value = _enum.extract_true_value();
+            let false_value = _enum.extract_false_value();
+            self.get_variant_value_set(true_value, k, i, suffix, values);
+            self.get_variant_value_set(false_value, k, i, suffix, values);
+            return;
+        }
+
+        // A constructed variant. Other variants have no data.
+        if _enum.is_enum() {
+            if _enum.extract_variant_idx() == k {
+                let data = _enum.sub_exprs().unwrap()[1].clone();
+                let fields = data.extract_fields();
+                assert!(i < fields.len());
+                self.get_value_set_rec(fields[i].clone(), suffix, values);
+            }
+            return;
+        }
+
+        self.get_value_set_rec(_enum, suffix + format!(".{i}#{k}"), values);
     }
 }
 
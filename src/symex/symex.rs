@@ -61,6 +61,9 @@ impl<'cfg> Symex<'cfg> {
         let alloc_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
         let const_array = self.ctx.constant_array(self.ctx.constant_bool(false), None);
         self.assign(alloc_array, const_array, self.ctx._true().into());
+        self.track_moved_locals();
+        // Parameters of the entry
+        self.make_entry_args();
         // Register the initial state
         self.goto(0, self.ctx._true());
     }
@@ -94,6 +97,11 @@ impl<'cfg> Symex<'cfg> {
         self.symex_end_function();
     }
 
+    /// The paths explored and the paths cut by `--max-paths`
+    pub fn paths(&self) -> (usize, usize) {
+        (self.exec_state.num_paths, self.exec_state.cut_paths)
+    }
+
     pub(super) fn top(&self) -> &Frame<'cfg> {
         self.exec_state.top()
     }
@@ -131,6 +139,7 @@ impl<'cfg> Symex<'cfg> {
         let l1_local = self.exec_state.new_local(local, Level::Level1);
         let nplace = NPlace(l1_local.extract_symbol().l1_name());
         self.top_mut().cur_state.update_place_state(nplace, PlaceState::Own);
+        self.mark_assigned(&Place::from(local));
     }
 
     fn symex_storagedead(&mut self, local: Local) {
@@ -160,8 +169,7 @@ impl<'cfg> Symex<'cfg> {
         let is_unwind = match &terminator.kind {
             TerminatorKind::Call { func, .. } => {
                 let fndef = self.top_mut().function.operand_type(func).fn_def();
-                let trimmed_name = NString::from(fndef.0.trimmed_name());
-                self.program.contains_function(trimmed_name)
+                self.program.callee(&fndef).is_some()
             }
             _ => false,
         };
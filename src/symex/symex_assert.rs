@@ -1,6 +1,7 @@
 use stable_mir::mir::*;
 
 use super::symex::*;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
 use crate::symbol::nstring::NString;
 
@@ -12,18 +13,25 @@ impl<'cfg> Symex<'cfg> {
         msg: &AssertMessage,
         target: &usize,
     ) {
+        let check = match msg {
+            AssertMessage::BoundsCheck { .. } => Check::Bound,
+            AssertMessage::Overflow(..) | AssertMessage::OverflowNeg(..) => Check::Overflow,
+            AssertMessage::DivisionByZero(..) | AssertMessage::RemainderByZero(..) => {
+                Check::DivByZero
+            }
+            _ => Check::Assertion,
+        };
         let msg = NString::from("built-in check: ") + msg.description().unwrap();
 
         let expr = self.make_operand(cond);
 
         let mut cond = expr.clone();
-        self.replace_predicates(&mut cond);
-        self.rename(&mut cond);
         // Make assert fail and continue check other assertions
         if *expected == true {
             cond = self.ctx.not(cond);
         }
-        self.vc_system.borrow_mut().assert(msg, cond, self.exec_state.span);
+        // The check is only reachable under current path
+        self.claim(check, msg, cond);
 
         // self.symex_move(expr);
 
@@ -1,11 +1,13 @@
+use num_bigint::BigInt;
 use stable_mir::mir::*;
-use stable_mir::ty::IndexedVal;
+use stable_mir::ty::{IndexedVal, UintTy};
 
+use super::projection::Mode;
 use super::symex::*;
+use crate::bmc::error::unsupported;
 use crate::expr::expr::*;
 use crate::expr::guard::*;
 use crate::expr::ty::*;
-use crate::program::program::bigint_to_u64;
 use crate::symbol::symbol::*;
 
 type BinOp = crate::expr::op::BinOp;
@@ -16,11 +18,24 @@ impl<'cfg> Symex<'cfg> {
         // construct lhs expr and rhs expr from MIR
         let lhs = self.make_project(place);
         let rhs = self.make_rvalue(rvalue);
+        self.symex_borrow(place, rvalue);
         self.assign(lhs, rhs.clone(), self.ctx._true().into());
+
+        let operands = match rvalue {
+            Rvalue::Use(op)
+            | Rvalue::Cast(_, op, _)
+            | Rvalue::Repeat(op, _)
+            | Rvalue::ShallowInitBox(op, _) => std::slice::from_ref(op),
+            Rvalue::Aggregate(_, ops) => ops.as_slice(),
+            _ => &[],
+        };
+        self.mark_moved(operands);
+        self.mark_assigned(place);
     }
 
     pub(super) fn assign(&mut self, lhs: Expr, rhs: Expr, guard: Guard) {
         assert!(lhs.ty().is_layout() || lhs.ty() == rhs.ty());
+        self.mark_written(&lhs, guard.clone());
         self.assign_rec(lhs, rhs.clone(), guard);
         // move semantic
         // self.symex_move(rhs);
@@ -43,7 +58,7 @@ impl<'cfg> Symex<'cfg> {
         // New l2 symbol
         lhs = self.exec_state.new_symbol(&lhs, Level::Level2);
 
-        if rhs.is_type() {
+        if rhs.is_type() || lhs.ty().contains_layout() {
             return;
         }
 
@@ -51,7 +66,7 @@ impl<'cfg> Symex<'cfg> {
         self.vc_system.borrow_mut().assign(lhs, rhs, self.exec_state.span);
     }
 
-    fn assign_rec(&mut self, lhs: Expr, rhs: Expr, guard: Guard) {
+    pub(super) fn assign_rec(&mut self, lhs: Expr, rhs: Expr, guard: Guard) {
         if lhs.is_symbol() {
             self.assign_symbol(lhs, rhs, guard);
             return;
@@ -105,7 +120,7 @@ impl<'cfg> Symex<'cfg> {
             return;
         }
 
-        panic!("Do not support assignment:\n{lhs:?} = {rhs:?}");
+        unsupported!("Do not support assignment:\n{lhs:?} = {rhs:?}");
     }
 
     fn make_rvalue(&mut self, rvalue: &Rvalue) -> Expr {
@@ -118,6 +133,23 @@ impl<'cfg> Symex<'cfg> {
                 address_of
             }
             Rvalue::Aggregate(k, operands) => self.make_aggregate(k, operands, ty),
+            Rvalue::BinaryOp(
+                mir_op @ (stable_mir::mir::BinOp::Shl
+                | stable_mir::mir::BinOp::ShlUnchecked
+                | stable_mir::mir::BinOp::Shr
+                | stable_mir::mir::BinOp::ShrUnchecked),
+                lop,
+                rop,
+            ) => {
+                let value = self.make_operand(lop);
+                let amount = self.make_operand(rop);
+                self.shift_overflow_check(value.ty(), amount.clone());
+                let left = matches!(
+                    mir_op,
+                    stable_mir::mir::BinOp::Shl | stable_mir::mir::BinOp::ShlUnchecked
+                );
+                self.make_shift(value, amount, left)
+            }
             Rvalue::BinaryOp(mir_op, lop, rop) => {
                 let op = BinOp::from(mir_op.clone());
                 let lhs = self.make_operand(lop);
@@ -126,7 +158,8 @@ impl<'cfg> Symex<'cfg> {
                     BinOp::Add => self.ctx.add(lhs, rhs),
                     BinOp::Sub => self.ctx.sub(lhs, rhs),
                     BinOp::Mul => self.ctx.mul(lhs, rhs),
-                    BinOp::Div => self.ctx.div(lhs, rhs),
+                    BinOp::Div => self.ctx.div(lhs.clone(), rhs.clone()),
+                    BinOp::Rem => self.ctx.rem(lhs.clone(), rhs.clone()),
                     BinOp::Eq => self.ctx.eq(lhs, rhs),
                     BinOp::Ne => self.ctx.ne(lhs, rhs),
                     BinOp::Ge => self.ctx.ge(lhs, rhs),
@@ -137,6 +170,12 @@ impl<'cfg> Symex<'cfg> {
                     BinOp::Or => self.ctx.or(lhs, rhs),
                     BinOp::Implies => self.ctx.implies(lhs, rhs),
                 };
+                if matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul) && ty.is_integer() {
+                    self.overflow_check(op, expr.clone());
+                }
+                if matches!(op, BinOp::Div | BinOp::Rem) && ty.is_integer() {
+                    self.division_check(op, expr.extract_lhs(), expr.extract_rhs());
+                }
                 expr
             }
             Rvalue::UnaryOp(mir_op, o) => {
@@ -149,11 +188,31 @@ impl<'cfg> Symex<'cfg> {
                 };
                 expr
             }
-            Rvalue::Cast(_, operand, t) => {
+            Rvalue::Cast(kind, operand, t) => {
                 // TODO: handle cast kind
                 let op = self.make_operand(operand);
-                let target_ty = self.ctx.mk_type(Type::from(t.clone()));
-                let cast = self.ctx.cast(op, target_ty);
+                let target_ty = Type::from(t.clone());
+                if *kind == CastKind::Transmute {
+                    return self.make_transmute(op, target_ty);
+                }
+                let is_int = |ty: Type| ty.is_integer() || ty.is_char();
+                if *kind == CastKind::IntToInt && is_int(op.ty()) && is_int(target_ty) {
+                    return self.make_int_cast(op, target_ty);
+                }
+                if *kind == CastKind::PointerCoercion(PointerCoercion::Unsize)
+                    && op.ty().pointee_ty().is_array()
+                    && target_ty.is_slice_ptr()
+                {
+                    // The metadata of the slice pointer is the length of the array
+                    let slice = self.make_deref(
+                        op.clone(),
+                        Mode::Slice(None, None),
+                        self.ctx._true().into(),
+                        op.ty(),
+                    );
+                    return self.ctx.address_of(self.ctx.object(slice), target_ty);
+                }
+                let cast = self.ctx.cast(op, self.ctx.mk_type(target_ty));
                 cast
             }
             Rvalue::Ref(_, _, p) => {
@@ -163,27 +222,32 @@ impl<'cfg> Symex<'cfg> {
                 address_of
             }
             Rvalue::Use(operand) => self.make_operand(operand),
-            Rvalue::Repeat(operand, tyconst) => {
+            Rvalue::Repeat(operand, _) => {
                 let value = self.make_operand(operand);
-                let len_expr = self.make_tyconst(tyconst);
-                // Carefully for bits
-                let bigint = len_expr.extract_constant().to_integer();
-                let len = bigint_to_u64(&bigint);
-                self.ctx.constant_array(value, Some(len))
+                let len = ty.array_size().expect("Repeat for an infinite array");
+                if value.is_constant() {
+                    self.ctx.constant_array(value, Some(len))
+                } else {
+                    self.ctx.repeat(value, ty)
+                }
             }
+            // `SwitchInt` branches on the discriminant values, which may be
+            // given explicitly. Variants are still encoded by their indices.
             Rvalue::Discriminant(p) => {
                 let place = self.make_project(p);
                 assert!(place.ty().is_enum());
-                let def = place.ty().enum_def();
-                let mut discr = self.ctx.constant_isize(0);
-                for i in 1..def.1.len() {
+                let values = place.ty().discriminant_values();
+                let discr_ty = place.ty().discriminant_type();
+                let mut discr = self.ctx.constant_integer(values[0].clone(), discr_ty);
+                for (i, value) in values.into_iter().enumerate().skip(1) {
                     let idx = self.ctx.constant_isize(i as isize);
-                    let cond = self.ctx.match_variant(place.clone(), idx.clone());
-                    discr = self.ctx.ite(cond, idx, discr);
+                    let cond = self.ctx.match_variant(place.clone(), idx);
+                    let value = self.ctx.constant_integer(value, discr_ty);
+                    discr = self.ctx.ite(cond, value, discr);
                 }
                 discr
             }
-            _ => todo!("{rvalue:?}"),
+            _ => unsupported!("{rvalue:?}"),
         }
     }
 
@@ -211,7 +275,128 @@ impl<'cfg> Symex<'cfg> {
                     self.ctx.variant(idx, data, ty)
                 }
             }
-            _ => todo!(),
+            AggregateKind::Tuple => {
+                assert!(ty.is_tuple());
+                self.ctx.aggregate(operand_exprs, ty)
+            }
+            _ => unsupported!("aggregate {k:?}"),
+        }
+    }
+
+    /// Reinterpret the bits of `op` as `ty`. Integers are composed of or
+    /// decomposed into the elements of integer arrays in little-endian.
+    fn make_transmute(&mut self, op: Expr, ty: Type) -> Expr {
+        let src_ty = op.ty();
+        if src_ty.size_in_bytes() != ty.size_in_bytes() {
+            unsupported!("Transmute {src_ty} to {ty} of different sizes");
+        }
+        if src_ty == ty {
+            return op;
+        }
+        if src_ty.is_any_ptr() && (ty.is_any_ptr() || ty.is_integer()) {
+            return self.ctx.cast(op, self.ctx.mk_type(ty));
+        }
+        if src_ty.is_integer() && ty.is_integer() {
+            let bits = self.integer_to_bits(op);
+            return self.integer_from_bits(bits, ty);
+        }
+        if src_ty.is_integer() && ty.is_char() {
+            let code = self.integer_to_bits(op);
+            return self.char_from_code(code);
+        }
+        if src_ty.is_char() && ty.is_integer() {
+            let code = self.ctx.cast(op, self.ctx.mk_type(Type::unsigned_type(UintTy::U32)));
+            return self.integer_from_bits(code, ty);
+        }
+        if src_ty.is_integer() && ty.is_array() && ty.elem_type().is_integer() {
+            let elem_ty = ty.elem_type();
+            let width = elem_ty.size_in_bytes().unwrap() * 8;
+            let bits = self.integer_to_bits(op);
+            let modulus = self.ctx.constant_integer(BigInt::from(1) << width, bits.ty());
+            let mut elems = Vec::new();
+            for i in 0..ty.array_size().unwrap() {
+                let shift = self.ctx.constant_integer(BigInt::from(1) << (width * i), bits.ty());
+                let elem = self.ctx.rem(self.ctx.div(bits.clone(), shift), modulus.clone());
+                elems.push(self.integer_from_bits(elem, elem_ty));
+            }
+            return self.ctx.aggregate(elems, ty);
+        }
+        if src_ty.is_array() && src_ty.elem_type().is_integer() && ty.is_integer() {
+            let elem_ty = src_ty.elem_type();
+            let width = elem_ty.size_in_bytes().unwrap() * 8;
+            let array = op.unwrap_predicates();
+            let mut bits = self.ctx.constant_integer(BigInt::ZERO, ty);
+            for i in 0..src_ty.array_size().unwrap() {
+                let elem = if array.is_aggregate() {
+                    array.extract_fields()[i as usize].clone()
+                } else {
+                    self.ctx.index(array.clone(), self.ctx.constant_usize(i as usize), elem_ty)
+                };
+                let shift = self.ctx.constant_integer(BigInt::from(1) << (width * i), elem_ty);
+                let elem = self.ctx.mul(self.integer_to_bits(elem), shift);
+                bits = self.ctx.add(bits, elem);
+            }
+            return self.integer_from_bits(bits, ty);
+        }
+        unsupported!("Not support transmute {src_ty} to {ty}")
+    }
+
+    /// `as` between integers keeps the low bits of the value, and widening
+    /// a signed integer extends its sign. Only `u8` can be cast to `char`,
+    /// which is always valid.
+    fn make_int_cast(&mut self, op: Expr, ty: Type) -> Expr {
+        if ty.is_char() {
+            assert!(op.ty() == Type::unsigned_type(UintTy::U8));
+            return self.ctx.cast(op, self.ctx.mk_type(ty));
+        }
+        let op = if op.ty().is_char() {
+            self.ctx.cast(op, self.ctx.mk_type(Type::unsigned_type(UintTy::U32)))
+        } else {
+            op
+        };
+        let (min, max) = op.ty().integer_range();
+        let (target_min, target_max) = ty.integer_range();
+        if target_min <= min && max <= target_max {
+            return self.ctx.cast(op, self.ctx.mk_type(ty));
+        }
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let modulus = self.ctx.constant_integer(BigInt::from(1) << width, op.ty());
+        if op.ty().size_in_bytes().unwrap() * 8 < width {
+            // Only a negative value of a signed integer does not fit. The
+            // sign-extended bits are its two's complement in `width` bits.
+            let zero = self.ctx.constant_integer(BigInt::ZERO, op.ty());
+            let is_neg = self.ctx.lt(op.clone(), zero);
+            let bits = self.ctx.ite(is_neg, self.ctx.add(op.clone(), modulus), op);
+            return self.integer_from_bits(bits, ty);
+        }
+        let bits = self.integer_to_bits(op);
+        let bits = self.ctx.rem(bits, modulus);
+        self.integer_from_bits(bits, ty)
+    }
+
+    /// The bits of an integer as an unsigned value
+    fn integer_to_bits(&self, i: Expr) -> Expr {
+        let ty = i.ty();
+        if !ty.is_signed() {
+            return i;
+        }
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let zero = self.ctx.constant_integer(BigInt::ZERO, ty);
+        let modulus = self.ctx.constant_integer(BigInt::from(1) << width, ty);
+        let is_neg = self.ctx.lt(i.clone(), zero);
+        self.ctx.ite(is_neg, self.ctx.add(i.clone(), modulus), i)
+    }
+
+    /// The integer of type `ty` with the bits
+    fn integer_from_bits(&self, bits: Expr, ty: Type) -> Expr {
+        let bits = if bits.ty() == ty { bits } else { self.ctx.cast(bits, self.ctx.mk_type(ty)) };
+        if !ty.is_signed() {
+            return bits;
         }
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let half = self.ctx.constant_integer(BigInt::from(1) << (width - 1), ty);
+        let modulus = self.ctx.constant_integer(BigInt::from(1) << width, ty);
+        let is_neg = self.ctx.ge(bits.clone(), half);
+        self.ctx.ite(is_neg, self.ctx.sub(bits.clone(), modulus), bits)
     }
 }
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use stable_mir::mir::*;
+
+use super::symex::*;
+use super::value_set::ObjectSet;
+use crate::config::cli::Check;
+use crate::expr::context::*;
+use crate::expr::expr::*;
+use crate::expr::ty::*;
+use crate::symbol::nstring::NString;
+use crate::symbol::symbol::Level;
+
+/// A loan of the fields `[lo, hi)` of an object created by a borrow
+#[derive(Debug, Clone)]
+struct Loan {
+    id: usize,
+    mutable: bool,
+    range: (usize, usize),
+    /// The path condition under which the loan is invalidated
+    invalidated: Option<Expr>,
+}
+
+/// Loans of objects for the aliasing check, a lite version of Stacked
+/// Borrows. A mutable borrow invalidates all overlapping loans of the
+/// object, and a shared borrow invalidates the overlapping mutable ones.
+/// A reborrow from a reference shares the loan of the reference.
+#[derive(Debug, Default)]
+pub(super) struct Borrows {
+    loans: HashMap<NString, Vec<Loan>>,
+    /// The object and the loan that each reference is derived from
+    refs: HashMap<NString, (NString, usize)>,
+    num_loans: usize,
+}
+
+impl<'cfg> Symex<'cfg> {
+    /// Track the loan of the reference assigned to `place`
+    pub(super) fn symex_borrow(&mut self, place: &Place, rvalue: &Rvalue) {
+        if !self.config.cli.enable_check(Check::Aliasing) || !place.projection.is_empty() {
+            return;
+        }
+
+        let reference = self.top().local_ident(place.local);
+        self.exec_state.borrows.refs.remove(&reference);
+        match rvalue {
+            Rvalue::Ref(_, kind, p) => {
+                // A two-phase borrow is not activated yet. Treat it as shared.
+                let mutable = matches!(
+                    kind,
+                    BorrowKind::Mut {
+                        kind: MutBorrowKind::Default | MutBorrowKind::ClosureCapture
+                    }
+                );
+                if p.projection.first() != Some(&ProjectionElem::Deref) {
+                    let object = self.top().local_ident(p.local);
+                    let range = self.borrow_range(p);
+                    self.new_loan(reference, object, mutable, range);
+                    return;
+                }
+
+                let ty = self.top().function.local_type(p.local);
+                if ty.is_ref() {
+                    self.copy_borrow(reference, self.top().local_ident(p.local));
+                } else if ty.is_ptr() {
+                    // Borrow from the object that the raw pointer points to
+                    let pt = self.exec_state.current_local(p.local, Level::Level1);
+                    let mut objects = ObjectSet::new();
+                    self.top().cur_state.get_value_set(pt, &mut objects);
+                    if objects.len() != 1 {
+                        return;
+                    }
+                    let (object, _) = objects.into_iter().next().unwrap();
+                    if object.is_null_object() || object.is_unknown() {
+                        return;
+                    }
+                    let root = object.extract_root_object();
+                    let ident = root.extract_inner_expr().extract_symbol().ident();
+                    self.new_loan(reference, ident, mutable, (0, root.ty().num_fields()));
+                }
+            }
+            Rvalue::Use(Operand::Copy(p) | Operand::Move(p)) if p.projection.is_empty() => {
+                self.copy_borrow(reference, self.top().local_ident(p.local));
+            }
+            _ => {}
+        }
+    }
+
+    /// Pass the loans of references in `args` to the parameters of
+    /// the callee. Must be called after the frame of callee is pushed.
+    pub(super) fn symex_borrow_args(&mut self, args: &Vec<Operand>, caller_locals: Vec<NString>) {
+        if !self.config.cli.enable_check(Check::Aliasing) {
+            return;
+        }
+        for (i, arg) in args.iter().enumerate() {
+            if let Operand::Copy(p) | Operand::Move(p) = arg {
+                if p.projection.is_empty() {
+                    let param = self.top().local_ident(i + 1);
+                    self.copy_borrow(param, caller_locals[i]);
+                }
+            }
+        }
+    }
+
+    /// Using a reference whose loan is invalidated violates the aliasing rule
+    pub(super) fn aliasing_check(&mut self, reference: &Expr) {
+        if !self.config.cli.enable_check(Check::Aliasing)
+            || !reference.is_symbol()
+            || !reference.ty().is_ref()
+        {
+            return;
+        }
+
+        let ident = reference.extract_symbol().ident();
+        let Some((object, id)) = self.exec_state.borrows.refs.get(&ident).cloned() else {
+            return;
+        };
+        let loan = self.exec_state.borrows.loans[&object].iter().find(|l| l.id == id).unwrap();
+        if let Some(invalidated) = loan.invalidated.clone() {
+            let msg = format!(
+                "aliasing failure: {ident:?} is used after a conflicting borrow of {object:?}"
+            );
+            self.claim(Check::Aliasing, msg.into(), invalidated);
+        }
+    }
+
+    /// The fields of the local borrowed by `place`, in field-level offsets
+    fn borrow_range(&self, place: &Place) -> (usize, usize) {
+        let mut ty = self.top().function.local_type(place.local);
+        let mut lo = 0;
+        for elem in place.projection.iter() {
+            match elem {
+                ProjectionElem::Field(i, fty) if ty.is_struct() => {
+                    lo += ty.struct_field_offset(*i);
+                    ty = Type::from(*fty);
+                }
+                ProjectionElem::Field(i, fty) if ty.is_tuple() => {
+                    lo += ty.tuple_elem_offset(*i);
+                    ty = Type::from(*fty);
+                }
+                _ => break,
+            }
+        }
+        (lo, lo + ty.num_fields())
+    }
+
+    fn new_loan(
+        &mut self,
+        reference: NString,
+        object: NString,
+        mutable: bool,
+        range: (usize, usize),
+    ) {
+        let guard = self.exec_state.cur_state().guard.to_expr();
+        let borrows = &mut self.exec_state.borrows;
+        let loans = borrows.loans.entry(object).or_default();
+        for loan in loans.iter_mut() {
+            let overlap = loan.range.0 < range.1 && range.0 < loan.range.1;
+            if overlap && (mutable || loan.mutable) {
+                loan.invalidated = Some(match loan.invalidated.take() {
+                    Some(cond) => self.ctx.or(cond, guard.clone()),
+                    None => guard.clone(),
+                });
+            }
+        }
+        let id = borrows.num_loans;
+        borrows.num_loans += 1;
+        loans.push(Loan { id, mutable, range, invalidated: None });
+        borrows.refs.insert(reference, (object, id));
+    }
+
+    fn copy_borrow(&mut self, to: NString, from: NString) {
+        if let Some(borrow) = self.exec_state.borrows.refs.get(&from).cloned() {
+            self.exec_state.borrows.refs.insert(to, borrow);
+        }
+    }
+}
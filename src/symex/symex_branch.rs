@@ -2,6 +2,7 @@ use num_bigint::BigInt;
 use stable_mir::mir::*;
 
 use super::symex::*;
+use crate::bmc::error::unsupported;
 use crate::{expr::expr::*, program::function::Pc};
 
 impl<'cfg> Symex<'cfg> {
@@ -12,29 +13,54 @@ impl<'cfg> Symex<'cfg> {
     pub(super) fn symex_switchint(&mut self, discr: &Operand, targets: &SwitchTargets) {
         let mut discr_expr = self.make_operand(discr);
         let mut otherwise_guard = self.ctx._true();
+        let mut forked = false;
         for (i, bb) in targets.branches() {
             // branches
             let branch_guard = self.make_branch_guard(discr_expr.clone(), i);
-            self.goto(bb, branch_guard.clone());
+            self.fork(bb, branch_guard.clone(), &mut forked);
             otherwise_guard = self.ctx.and(otherwise_guard, self.ctx.not(branch_guard));
         }
         // otherwise
-        self.goto(targets.otherwise(), otherwise_guard);
+        self.fork(targets.otherwise(), otherwise_guard, &mut forked);
+    }
+
+    /// Each successor but the first one of a branch is a new path. It is cut
+    /// if the paths reach `--max-paths`.
+    fn fork(&mut self, pc: Pc, branch_guard: Expr, forked: &mut bool) {
+        let max_paths = self.config.cli.max_paths;
+        if *forked && max_paths != 0 && self.exec_state.num_paths >= max_paths {
+            self.exec_state.cut_paths += 1;
+            return;
+        }
+        if self.goto(pc, branch_guard) {
+            if *forked {
+                self.exec_state.num_paths += 1;
+            }
+            *forked = true;
+        }
     }
 
     fn make_branch_guard(&mut self, discr_expr: Expr, i: u128) -> Expr {
         if discr_expr.ty().is_integer() {
-            self.ctx
-                .eq(discr_expr.clone(), self.ctx.constant_integer(BigInt::from(i), discr_expr.ty()))
+            // The value is in two's complement for signed integers
+            let mut value = BigInt::from(i);
+            let (_, max) = discr_expr.ty().integer_range();
+            if discr_expr.ty().is_signed() && value > max {
+                value -= (max + 1) * 2;
+            }
+            self.ctx.eq(discr_expr.clone(), self.ctx.constant_integer(value, discr_expr.ty()))
+        } else if discr_expr.ty().is_char() {
+            let code = self.ctx.constant_integer(BigInt::from(i), discr_expr.ty());
+            self.ctx.eq(discr_expr.clone(), code)
         } else if discr_expr.ty().is_bool() {
             if i == 0 { self.ctx.not(discr_expr) } else { discr_expr }
         } else {
-            panic!("Not support for this type of SwitchInt")
+            unsupported!("Not support for this type of SwitchInt")
         }
     }
 
-    /// Register state in state_map
-    pub fn goto(&mut self, pc: Pc, mut branch_guard: Expr) {
+    /// Register state in state_map. Return whether the state is reachable.
+    pub fn goto(&mut self, pc: Pc, mut branch_guard: Expr) -> bool {
         self.replace_predicates(&mut branch_guard);
         self.rename(&mut branch_guard);
         branch_guard.simplify();
@@ -44,15 +70,20 @@ impl<'cfg> Symex<'cfg> {
             // However, if the branch guard is true, the loop stop unwinding.
             if !_loop.contains(&pc) && !self.top().reach_loop_bound(l.0) && !branch_guard.is_true()
             {
-                return;
+                return false;
             }
         }
         let mut state = self.top().cur_state.clone();
-        state.guard.add(branch_guard);
+        state.guard.add(branch_guard.clone());
         if state.guard.is_false() {
-            return;
+            return false;
+        }
+        if !branch_guard.is_true() {
+            let path = state.guard.to_expr();
+            self.vc_system.borrow_mut().branch(branch_guard, path, self.exec_state.span);
         }
         state.renaming = Some(self.exec_state.renaming.clone());
         self.top_mut().add_state(pc, state);
+        true
     }
 }
@@ -1,12 +1,17 @@
 use stable_mir::CrateDef;
 
 use super::symex::Symex;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
 use crate::expr::ty::*;
 use crate::symbol::nstring::*;
 use crate::symbol::symbol::*;
+use crate::symex::place_state::*;
 
 impl<'cfg> Symex<'cfg> {
+    /// The API in `rbmc` library. A call is dispatched here if the path of
+    /// its `FnDef` contains `rbmc`. Then, it is matched by the trimmed name.
     pub(super) fn symex_builtin_function(
         &mut self,
         fndef: &FunctionDef,
@@ -16,17 +21,134 @@ impl<'cfg> Symex<'cfg> {
         let name = NString::from(fndef.0.trimmed_name());
         if name == "nondet" {
             self.symex_nondet(dest);
+        } else if name == "assume" {
+            self.symex_assume(args[0].clone());
+        } else if name == "assert" {
+            self.symex_rbmc_assert(args[0].clone());
         } else {
-            panic!("Not support for {name:?}");
+            unsupported!("Not support for {name:?}");
         }
     }
 
     fn symex_nondet(&mut self, dest: Expr) {
-        let lhs = dest.clone();
-        let n = self.exec_state.ns.lookup_nondet_count(lhs.ty());
-        let name = NString::from(format!("nondet_{:?}_{n}", lhs.ty()));
+        let nondet = self.make_nondet(dest.ty());
+        self.assign(dest, nondet, self.ctx._true().into());
+    }
+
+    /// Make a nondet value of `ty`. Structs, tuples and arrays are laid
+    /// out field by field such that each field is a fresh symbol.
+    pub(super) fn make_nondet(&mut self, ty: Type) -> Expr {
+        if let Some(fields) = Symex::aggregate_fields(ty) {
+            let values = fields.into_iter().map(|t| self.make_nondet(t)).collect::<Vec<_>>();
+            return self.ctx.aggregate(values, ty);
+        }
+
+        let n = self.exec_state.ns.lookup_nondet_count(ty);
+        let name = NString::from(format!("nondet_{ty:?}_{n}"));
         let symbol = Symbol::from(name);
-        let nondet = self.ctx.mk_symbol(symbol, lhs.ty());
-        self.assign(lhs, nondet, self.ctx._true().into());
+        let nondet = self.ctx.mk_symbol(symbol, ty);
+        // Integers are unbounded in SMT. Assume the value is in the range of its type.
+        if ty.is_integer() {
+            let (min, max) = ty.integer_range();
+            let mut in_range = self.ctx.and(
+                self.ctx.ge(nondet.clone(), self.ctx.constant_integer(min, ty)),
+                self.ctx.le(nondet.clone(), self.ctx.constant_integer(max, ty)),
+            );
+            self.rename(&mut in_range);
+            self.vc_system.borrow_mut().assume(in_range, self.exec_state.span);
+        }
+        if ty.is_char() {
+            let mut is_valid = self.is_valid_char(nondet.clone());
+            self.rename(&mut is_valid);
+            self.vc_system.borrow_mut().assume(is_valid, self.exec_state.span);
+        }
+        nondet
+    }
+
+    /// Types of the fields of a struct, a tuple or a finite array
+    fn aggregate_fields(ty: Type) -> Option<Vec<Type>> {
+        if ty.is_struct() {
+            Some(ty.struct_def().1.into_iter().map(|(_, t)| t).collect::<Vec<_>>())
+        } else if ty.is_tuple() && !ty.is_unit() {
+            Some(ty.tuple_def())
+        } else if ty.is_array() && !ty.is_infinite_array() {
+            let n = ty.array_size().unwrap() as usize;
+            Some(vec![ty.elem_type(); n])
+        } else {
+            None
+        }
+    }
+
+    /// Parameters of the entry are symbolic inputs. It is a no-op for
+    /// `main`, which has no parameters.
+    pub(super) fn make_entry_args(&mut self) {
+        let args = self.top_mut().function.args();
+        if args.is_empty() {
+            return;
+        }
+        for local in self.top().function.locals_alive() {
+            let l1_local = self.exec_state.current_local(*local, Level::Level1);
+            let nplace = NPlace(l1_local.extract_symbol().l1_name());
+            self.top_mut().cur_state.update_place_state(nplace, PlaceState::Own);
+        }
+        for arg_local in args.iter() {
+            let lhs = self.exec_state.l0_local(*arg_local);
+            let input = self.make_input(lhs.ty());
+            self.assign(lhs, input, self.ctx._true().into());
+        }
+    }
+
+    /// A reference or a raw pointer input points to a fresh object owned
+    /// by the caller. A raw pointer may be null. A box owns a fresh object.
+    /// Structs, tuples and arrays are made field by field.
+    fn make_input(&mut self, ty: Type) -> Expr {
+        if let Some(fields) = Symex::aggregate_fields(ty) {
+            let values = fields.into_iter().map(|t| self.make_input(t)).collect::<Vec<_>>();
+            return self.ctx.aggregate(values, ty);
+        }
+        if ty.is_box() {
+            let object = self.make_input_object(ty);
+            let inner_pt = self.ctx.address_of(object.clone(), object.extract_address_type());
+            return self.ctx._box(inner_pt);
+        }
+        if !ty.is_primitive_ptr() {
+            return self.make_nondet(ty);
+        }
+
+        let object = self.make_input_object(ty);
+        let address_of = self.ctx.address_of(object, ty);
+        if ty.is_ref() {
+            return address_of;
+        }
+        let is_null = self.make_nondet(Type::bool_type());
+        self.ctx.ite(is_null, self.ctx.null(ty), address_of)
+    }
+
+    /// The fresh object pointed to by the pointer input of `ty`
+    fn make_input_object(&mut self, ty: Type) -> Expr {
+        let pointee_ty = ty.pointee_ty();
+        if pointee_ty.size_in_bytes().is_none() {
+            unsupported!("Not support input {ty} of unsized type");
+        }
+        let object = self.exec_state.new_object(pointee_ty);
+        self.track_new_object(object.clone());
+        let value = self.make_input(pointee_ty);
+        self.assign(object.clone(), value, self.ctx._true().into());
+        self.exec_state.update_place_state(object.clone(), PlaceState::Own);
+        object
+    }
+
+    /// Restrict current path with `cond`
+    pub(super) fn symex_assume(&mut self, mut cond: Expr) {
+        assert!(cond.ty().is_bool());
+        self.replace_predicates(&mut cond);
+        self.rename(&mut cond);
+        self.top_mut().cur_state.guard.add(cond);
+    }
+
+    fn symex_rbmc_assert(&mut self, cond: Expr) {
+        assert!(cond.ty().is_bool());
+        let msg = NString::from("assertion failure: rbmc::assert");
+        self.claim(Check::Assertion, msg, self.ctx.not(cond));
     }
 }
@@ -2,10 +2,12 @@ use num_bigint::BigInt;
 use stable_mir::mir::*;
 
 use super::symex::*;
+use crate::bmc::error::unsupported;
 use crate::expr::expr::*;
 use crate::expr::guard::*;
 use crate::expr::ty::*;
 use crate::symbol::nstring::NString;
+use crate::symex::place_state::PlaceState;
 use crate::symex::projection::Mode;
 
 impl<'cfg> Symex<'cfg> {
@@ -18,16 +20,31 @@ impl<'cfg> Symex<'cfg> {
         self.goto(*target, self.ctx._true());
     }
 
-    fn symex_drop_rec(&mut self, expr: Expr, guard: Guard) {
+    pub(super) fn symex_drop_rec(&mut self, expr: Expr, guard: Guard) {
+        // Nothing is freed, e.g., `drop_in_place` of integers
+        if !expr.ty().needs_drop() {
+            return;
+        }
+
         if expr.is_object() {
-            if expr.ty().is_box() {
+            if expr.ty().is_rc() {
+                self.drop_rc(expr.clone(), guard.clone());
+            } else if expr.ty().is_box() {
                 self.drop_box(expr.clone(), guard.clone());
             } else if expr.ty().is_vec() {
                 self.drop_vec(expr.clone(), guard.clone());
             } else if expr.ty().is_struct() {
-                self.drop_struct(expr.clone(), guard.clone());
+                let def = expr.ty().struct_def();
+                let fields = def.1.iter().map(|(_, ty)| *ty).collect::<Vec<_>>();
+                self.drop_fields(expr.clone(), fields, guard.clone());
+            } else if expr.ty().is_tuple() {
+                self.drop_fields(expr.clone(), expr.ty().tuple_def(), guard.clone());
+            } else if expr.ty().is_array() {
+                self.drop_array(expr.clone(), guard.clone());
+            } else if expr.ty().is_enum() {
+                self.drop_enum(expr.clone(), guard.clone());
             } else {
-                todo!("drop {:?}", expr.ty());
+                unsupported!("drop {}", expr.ty());
             }
             return;
         }
@@ -49,15 +66,19 @@ impl<'cfg> Symex<'cfg> {
             return;
         }
 
-        panic!("Not implement drop {:?}", expr.ty());
+        unsupported!("Not implement drop {}", expr.ty());
     }
 
-    /// Drop a box will free the memory it points to
+    /// Drop a box will free the memory it points to. The box still points
+    /// to the dead object, thus dropping it again is a double free.
     fn drop_box(&mut self, _box: Expr, guard: Guard) {
+        // A box of zero-sized type does not allocate
+        if _box.ty().pointee_ty().is_zst() {
+            return;
+        }
         // Check whethe the box is uninitilized
         self.make_deref(_box.clone(), Mode::Drop, guard.clone(), _box.ty().pointee_ty());
         self.top_mut().cur_state.dealloc_objects(_box.clone());
-        self.top_mut().cur_state.remove_pointer(_box.clone());
 
         let pointer_base = self.ctx.pointer_base(_box);
         let alloc_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
@@ -69,7 +90,6 @@ impl<'cfg> Symex<'cfg> {
         // Check whethe the vec is uninitilized
         self.make_deref(_vec.clone(), Mode::Drop, guard.clone(), _vec.ty().pointee_ty());
         self.top_mut().cur_state.dealloc_objects(_vec.clone());
-        self.top_mut().cur_state.remove_pointer(_vec.clone());
 
         let pointer_base = self.ctx.pointer_base(_vec);
         let alloc_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
@@ -77,19 +97,75 @@ impl<'cfg> Symex<'cfg> {
         self.assign(index, self.ctx._false(), guard.clone());
     }
 
-    /// Drop a struct may drop the inner box pointer
-    fn drop_struct(&mut self, st: Expr, guard: Guard) {
-        let def = st.ty().struct_def();
-        for (i, (_, ty)) in def.1.iter().enumerate() {
-            if !ty.is_box() && !ty.is_struct() {
+    /// Drop an rc decreases the strong count of its object. The last rc
+    /// drops the shared value and frees the object.
+    fn drop_rc(&mut self, rc: Expr, guard: Guard) {
+        for (object, count, mut guard) in self.strong_counts(rc, guard) {
+            let dec = self.ctx.sub(count.clone(), self.ctx.constant_usize(1));
+            self.assign(count.clone(), dec, guard.clone());
+
+            let mut is_zero = self.ctx.eq(count, self.ctx.constant_usize(0));
+            self.rename(&mut is_zero);
+            guard.add(is_zero);
+            self.symex_drop_rec(object.clone(), guard.clone());
+
+            let address_of = self.ctx.address_of(object.clone(), object.extract_address_type());
+            let pointer_base = self.ctx.pointer_base(address_of);
+            let alloc_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
+            let index = self.ctx.index(alloc_array, pointer_base, Type::bool_type());
+            self.assign(index, self.ctx._false(), guard);
+
+            // Whether the object is freed is left to SMT
+            let mut state = PlaceState::Dead;
+            state.meet(self.exec_state.get_place_state(&object));
+            self.exec_state.update_place_state(object, state);
+        }
+    }
+
+    /// Drop the fields of a struct or a tuple in order. Fields owning no
+    /// memory are skipped.
+    fn drop_fields(&mut self, object: Expr, fields: Vec<Type>, guard: Guard) {
+        for (i, ty) in fields.into_iter().enumerate() {
+            if !ty.needs_drop() {
                 continue;
             }
-            let object = self.ctx.object(self.ctx.index(
-                st.clone(),
+            let field = self.ctx.object(self.ctx.index(
+                object.clone(),
                 self.ctx.constant_isize(i as isize),
-                *ty,
+                ty,
+            ));
+            self.symex_drop_rec(field, guard.clone());
+        }
+    }
+
+    fn drop_array(&mut self, array: Expr, guard: Guard) {
+        let elem_ty = array.ty().elem_type();
+        for i in 0..array.ty().array_size().unwrap() {
+            let elem = self.ctx.object(self.ctx.index(
+                array.clone(),
+                self.ctx.constant_usize(i as usize),
+                elem_ty,
             ));
-            self.symex_drop_rec(object, guard.clone());
+            self.symex_drop_rec(elem, guard.clone());
+        }
+    }
+
+    /// Drop the data of each variant under the guard that the enum
+    /// matches the variant
+    fn drop_enum(&mut self, _enum: Expr, guard: Guard) {
+        let def = _enum.ty().enum_def();
+        for (i, (_, fields)) in def.1.iter().enumerate() {
+            // The data of a variant is a tuple
+            let Some((_, data_ty)) = fields.first() else {
+                continue;
+            };
+            let idx = self.ctx.constant_usize(i);
+            let mut cond = self.ctx.match_variant(_enum.clone(), idx.clone());
+            self.rename(&mut cond);
+            let mut variant_guard = guard.clone();
+            variant_guard.add(cond);
+            let data = self.ctx.object(self.ctx.as_variant(_enum.clone(), idx));
+            self.drop_fields(data, data_ty.tuple_def(), variant_guard);
         }
     }
 }
@@ -4,6 +4,8 @@ use stable_mir::mir::*;
 use super::place_state::NPlace;
 use super::place_state::PlaceState;
 use super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
 use crate::program::function::FunctionIdx;
 use crate::symbol::nstring::NString;
@@ -20,14 +22,22 @@ impl<'cfg> Symex<'cfg> {
         let ty = self.top_mut().function.operand_type(func);
         let fndef = ty.fn_def();
         let name = NString::from(fndef.0.name());
-        let trimmed_name = NString::from(fndef.0.trimmed_name());
 
         let ret = self.make_project(dest);
         let args_exprs = args.iter().map(|x| self.make_operand(x)).collect::<Vec<_>>();
 
-        if self.program.contains_function(trimmed_name) {
-            let i = self.program.function_idx(trimmed_name);
-            self.symex_function(i, args, dest, target);
+        self.mark_moved(args);
+
+        if let Some(i) = self.program.callee(&fndef) {
+            if self.reach_recursion_bound(i) {
+                // Cut the path
+                return;
+            }
+            self.symex_function(i, args, args_exprs, dest, target);
+            return;
+        } else if Symex::is_panic_function(&fndef) {
+            // The path ends
+            self.symex_panic(&fndef);
             return;
         } else if name.contains("rbmc".into()) {
             self.symex_builtin_function(&fndef, args_exprs.clone(), ret);
@@ -35,15 +45,37 @@ impl<'cfg> Symex<'cfg> {
             self.symex_alloc_api(&fndef, args_exprs.clone(), ret);
         } else if name.contains("std::boxed".into()) {
             self.symex_boxed_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::cell".into()) {
+            self.symex_cell_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::rc::".into()) || name.contains("std::sync::Arc".into()) {
+            self.symex_rc_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::mem".into()) {
+            self.symex_mem_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::num".into()) {
+            self.symex_num_api(&fndef, args_exprs.clone(), ret);
         } else if name.contains("std::ops".into()) {
             self.symex_ops_api(&fndef, args_exprs.clone(), ret);
-        } else if name.contains("std::ptr".into()) {
+        } else if name.contains("std::option".into()) {
+            self.symex_option_api(&fndef, args_exprs.clone(), ret);
+        } else if Symex::is_summarized_intrinsic(&fndef) {
+            if !self.symex_intrinsic(&fndef, args_exprs.clone(), ret) {
+                // The path ends, e.g., by `abort`
+                return;
+            }
+        } else if name.contains("std::ptr".into()) || name.contains("std::intrinsics".into()) {
             self.symex_ptr_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::result".into()) {
+            self.symex_result_api(&fndef, args_exprs.clone(), ret);
+        } else if name.contains("std::slice".into()) {
+            self.symex_slice_api(&fndef, args_exprs.clone(), ret);
         } else if name.contains("std::vec".into()) {
             self.symex_vec_api(&fndef, args_exprs.clone(), ret);
+        } else if !fndef.0.has_body() {
+            unsupported!("No body available for {name:?}. The result is unknown")
         } else {
-            panic!("Do not support {name:?}")
+            unsupported!("Do not support {name:?}")
         }
+        self.mark_assigned(dest);
 
         // Move semantic
         // for arg_expr in args_exprs {
@@ -57,19 +89,41 @@ impl<'cfg> Symex<'cfg> {
         }
     }
 
+    fn reach_recursion_bound(&mut self, i: FunctionIdx) -> bool {
+        let bound = self.config.cli.recursion;
+        if bound == 0 || self.exec_state.recursion_depth(i) < bound {
+            return false;
+        }
+        let name = self.program.function(i).name();
+        println!("Reach recursion bound of {name:?} for {bound} times");
+        if self.config.cli.unwinding_assertions {
+            let msg = NString::from(format!(
+                "unwinding assertion: recursion of {name:?} is not fully unwound"
+            ));
+            self.claim(Check::Unwind, msg, self.ctx._true());
+        }
+        true
+    }
+
     fn symex_function(
         &mut self,
         i: FunctionIdx,
         args: &Vec<Operand>,
+        arg_exprs: Vec<Expr>,
         dest: &Place,
         target: &Option<BasicBlockIdx>,
     ) {
-        let mut arg_exprs = Vec::new();
-        for arg in args {
-            arg_exprs.push(self.make_operand(arg));
-        }
+        let caller_locals = args
+            .iter()
+            .map(|arg| match arg {
+                Operand::Copy(p) | Operand::Move(p) => self.top().local_ident(p.local),
+                Operand::Constant(_) => NString::EMPTY,
+            })
+            .collect::<Vec<_>>();
         // Push frame for new name
         self.exec_state.push_frame(i, Some(dest.clone()), *target);
+        self.symex_borrow_args(args, caller_locals);
+        self.track_moved_locals();
         // Set alive local place state
         for local in self.top().function.locals_alive() {
             let l1_local = self.exec_state.current_local(*local, Level::Level1);
@@ -95,13 +149,19 @@ impl<'cfg> Symex<'cfg> {
 
     pub(super) fn symex_end_function(&mut self) {
         let pc = self.top().function.size();
-        // Must exist
-        assert!(self.merge_states(pc));
+        let is_reachable = self.merge_states(pc);
         if !self.exec_state.can_exec() {
             return;
         }
 
         let frame = self.exec_state.pop_frame();
+        if !is_reachable {
+            // All paths are cut by the bounds. Nothing returns to the caller.
+            self.exec_state.ns.clear_local_symbols(frame.function_id());
+            self.exec_state.renaming.borrow_mut().cleanr_locals(frame.function_id());
+            self.top_mut().inc_pc();
+            return;
+        }
         self.top_mut().cur_state = frame.cur_state.clone();
 
         // Assign return value
@@ -112,11 +172,14 @@ impl<'cfg> Symex<'cfg> {
                 let rhs_ty = frame.function.local_type(0);
                 let rhs = self.exec_state.l0_symbol(rhs_ident, rhs_ty);
                 self.assign(lhs, rhs, self.ctx._true().into());
+                self.mark_assigned(ret);
             }
         }
 
         if let Some(t) = &frame.target {
             let mut state = self.top().cur_state.clone();
+            // The target may be executed after other states of the caller
+            state.renaming = Some(self.exec_state.renaming.clone());
             state.remove_stack_places(frame.function_id());
             self.top_mut().add_state(*t, state);
         }
@@ -5,6 +5,10 @@ use crate::symbol::nstring::*;
 impl<'cfg> Symex<'cfg> {
     pub(super) fn track_new_object(&mut self, object: Expr) {
         assert!(object.is_object());
+        // Allocating a zero-sized object is a no-op
+        if object.ty().is_zst() {
+            return;
+        }
         let ctx = object.ctx.clone();
 
         // alloc[&object] = true
@@ -14,4 +18,16 @@ impl<'cfg> Symex<'cfg> {
         let store = ctx.store(alloc_array.clone(), pointer_base, ctx._true());
         self.assign(alloc_array, store, self.ctx._true().into());
     }
+
+    /// An infinite array object has `len` elements. The length of its
+    /// object space is the same.
+    pub(super) fn track_object_size(&mut self, object: Expr, len: Expr) {
+        assert!(object.is_object() && object.ty().is_infinite_array());
+        let mut size_eq = self.ctx.eq(self.ctx.object_size(object.clone()), len);
+        self.replace_predicates(&mut size_eq);
+        self.rename(&mut size_eq);
+        self.vc_system.borrow_mut().assume(size_eq, self.exec_state.span);
+        let ident = object.extract_inner_expr().extract_symbol().ident();
+        self.exec_state.sized_objects.insert(ident);
+    }
 }
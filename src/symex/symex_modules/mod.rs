@@ -1,5 +1,14 @@
 pub(super) mod symex_alloc;
 pub(super) mod symex_boxed;
+pub(super) mod symex_cell;
+pub(super) mod symex_intrinsics;
+pub(super) mod symex_mem;
+pub(super) mod symex_num;
 pub(super) mod symex_ops;
+pub(super) mod symex_option;
+pub(super) mod symex_panic;
 pub(super) mod symex_ptr;
+pub(super) mod symex_rc;
+pub(super) mod symex_result;
+pub(super) mod symex_slice;
 pub(super) mod symex_vec;
@@ -1,11 +1,18 @@
+use num_bigint::BigInt;
 use stable_mir::CrateDef;
+use stable_mir::ty::UintTy;
 
 use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
+use crate::expr::guard::Guard;
 use crate::expr::ty::*;
+use crate::program::program::{bigint_to_u64, bigint_to_usize};
 use crate::symbol::nstring::*;
 use crate::symex::place_state::PlaceState;
 use crate::symex::projection::Mode;
+use crate::symex::value_set::ObjectSet;
 
 /// This mod defines symbolic execution of api in std::alloc
 
@@ -13,22 +20,39 @@ impl<'cfg> Symex<'cfg> {
     pub fn symex_alloc_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
         let name = NString::from(fndef.0.trimmed_name());
         if name == "alloc" {
-            self.symex_alloc(dest, args);
+            self.symex_alloc(dest, args, false);
+        } else if name == "alloc_zeroed" {
+            self.symex_alloc(dest, args, true);
         } else if name == "dealloc" {
             self.symex_dealloc(args);
+        } else if name == "realloc" {
+            self.symex_realloc(dest, args);
         } else if name == "Layout::new" {
             self.symex_layout_new(dest, fndef);
+        } else if name == "Layout::from_size_align" {
+            self.symex_layout_from_size_align(dest, args, true);
+        } else if name == "Layout::from_size_align_unchecked" {
+            self.symex_layout_from_size_align(dest, args, false);
         } else {
-            panic!("Not support {name:?}");
+            unsupported!("Not support {name:?}");
         }
     }
 
-    fn symex_alloc(&mut self, dest: Expr, args: Vec<Expr>) {
-        let mut layout = args[0].clone();
-        self.replace_predicates(&mut layout);
-        self.rename(&mut layout);
-        assert!(layout.is_type());
-        let object = self.exec_state.new_object(layout.extract_type());
+    /// The object from `alloc_zeroed` is filled with zeros. Allocating a
+    /// zero-sized layout is undefined behavior.
+    fn symex_alloc(&mut self, dest: Expr, args: Vec<Expr>, zeroed: bool) {
+        let (ty, len) = self.alloc_layout(args[0].clone());
+        let zero_size = match &len {
+            Some(len) => self.ctx.eq(len.clone(), self.ctx.constant_usize(0)),
+            None => self.ctx.constant_bool(ty.is_zst()),
+        };
+        let msg = format!("zero-size-alloc failure: alloc with a zero-sized layout {ty:?}");
+        self.claim(Check::ZeroSizeAlloc, msg.into(), zero_size);
+
+        let object = self.exec_state.new_object(ty);
+        if let Some(len) = len {
+            self.track_object_size(object.clone(), len);
+        }
 
         let lhs = dest.clone();
         let address_of = self.ctx.address_of(object.clone(), lhs.ty());
@@ -36,18 +60,57 @@ impl<'cfg> Symex<'cfg> {
         self.assign(lhs, address_of, self.ctx._true().into());
 
         self.track_new_object(object.clone());
+        if zeroed && !object.ty().is_zst() {
+            let zero = self.zero_value(object.ty());
+            self.assign(object.clone(), zero, self.ctx._true().into());
+        }
+        self.track_uninit_object(object.clone(), zeroed);
 
         let place_state = PlaceState::Alive;
         self.exec_state.update_place_state(object, place_state);
     }
 
+    /// The value of `ty` whose bytes are all zero
+    fn zero_value(&self, ty: Type) -> Expr {
+        if ty.is_bool() {
+            return self.ctx._false();
+        }
+        if ty.is_integer() {
+            return self.ctx.constant_integer(BigInt::ZERO, ty);
+        }
+        if ty.is_primitive_ptr() {
+            return self.ctx.null(ty);
+        }
+        if ty.is_array() {
+            let elem_ty = ty.elem_type();
+            let elem = self.zero_value(elem_ty);
+            if elem_ty.is_bool() || elem_ty.is_integer() {
+                return self.ctx.constant_array(elem, ty.array_size());
+            }
+            match ty.array_size() {
+                Some(n) => return self.ctx.aggregate(vec![elem; n as usize], ty),
+                None => unsupported!("Not support zeroed {ty}"),
+            }
+        }
+        if ty.is_struct() {
+            let fields = ty.struct_def().1.iter().map(|(_, t)| self.zero_value(*t)).collect();
+            return self.ctx.aggregate(fields, ty);
+        }
+        if ty.is_tuple() {
+            let fields = ty.tuple_def().iter().map(|t| self.zero_value(*t)).collect();
+            return self.ctx.aggregate(fields, ty);
+        }
+        unsupported!("Not support zeroed {ty}");
+    }
+
     fn symex_dealloc(&mut self, args: Vec<Expr>) {
         let pt = args[0].clone();
-        let mut layout = args[1].clone();
-        self.replace_predicates(&mut layout);
-        self.rename(&mut layout);
-        let ty = layout.extract_type();
+        let (ty, _) = self.alloc_layout(args[1].clone());
         assert!(pt.ty().is_ptr());
+        // Deallocating a zero-sized object is a no-op
+        if ty.is_zst() {
+            return;
+        }
         // Generate assertions
         self.make_deref(pt.clone(), Mode::Dealloc, self.ctx._true().into(), ty);
 
@@ -60,8 +123,227 @@ impl<'cfg> Symex<'cfg> {
         self.assign(index, self.ctx._false(), self.ctx._true().into());
     }
 
+    /// `realloc` fails nondeterministically. On failure, it returns null and
+    /// the old object is still alive. Otherwise, the contents are copied to a
+    /// new object of `new_size` bytes and the old object is dealloced.
+    fn symex_realloc(&mut self, dest: Expr, args: Vec<Expr>) {
+        let pt = args[0].clone();
+        let (ty, _) = self.alloc_layout(args[1].clone());
+        assert!(pt.ty().is_ptr());
+        let mut new_size = args[2].clone();
+        if new_size.is_object() {
+            new_size = new_size.extract_inner_expr();
+        }
+        self.replace_predicates(&mut new_size);
+        self.rename(&mut new_size);
+        new_size.simplify();
+        let new_ty = if new_size.is_constant() {
+            Symex::realloc_type(ty, bigint_to_u64(&new_size.extract_constant().to_integer()))
+        } else {
+            let elem_ty = if ty.is_array() { ty.elem_type() } else { ty };
+            Type::infinite_array_type(elem_ty)
+        };
+
+        // The old object is dealloced when realloc succeeds
+        self.make_deref(pt.clone(), Mode::Dealloc, self.ctx._true().into(), ty);
+
+        let fail = self.make_nondet(Type::bool_type());
+        let succeed = self.ctx.not(fail.clone());
+        let new_object = self.exec_state.new_object(new_ty);
+        if new_ty.is_infinite_array() {
+            // The runtime size is a multiple of the element size
+            let elem_size = new_ty.elem_type().size_in_bytes().unwrap() as usize;
+            let len = self.make_nondet(Type::usize_type());
+            let size = self.ctx.mul(len.clone(), self.ctx.constant_usize(elem_size));
+            let mut size_eq = self.ctx.eq(size, new_size);
+            self.rename(&mut size_eq);
+            self.vc_system.borrow_mut().assume(size_eq, self.exec_state.span);
+            self.track_object_size(new_object.clone(), len);
+        }
+
+        let lhs = dest.clone();
+        let address_of = self.ctx.address_of(new_object.clone(), lhs.ty());
+        let rhs = self.ctx.ite(fail.clone(), self.ctx.null(lhs.ty()), address_of.clone());
+        self.assign(lhs, rhs, self.ctx._true().into());
+
+        // Copy contents of the old object
+        let mut objects = ObjectSet::new();
+        self.top().cur_state.get_value_set(pt.clone(), &mut objects);
+        for (object, _) in objects {
+            if object.is_null_object() || object.is_unknown() {
+                continue;
+            }
+            let root_object = object.extract_root_object();
+            let mut pointer_cond = self.ctx.same_object(
+                pt.clone(),
+                self.ctx.address_of(root_object.clone(), root_object.extract_address_type()),
+            );
+            self.replace_predicates(&mut pointer_cond);
+            self.rename(&mut pointer_cond);
+            let mut guard = Guard::from(pointer_cond);
+            guard.add(succeed.clone());
+            self.copy_object(new_object.clone(), root_object.clone(), guard);
+            if root_object.extract_inner_expr().extract_symbol().is_heap_symbol() {
+                self.exec_state.update_place_state(root_object, PlaceState::Unknown);
+            }
+        }
+
+        // alloc[old] = fail, alloc[new] = !fail
+        let alloc_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
+        let old_base = self.ctx.pointer_base(pt);
+        let index = self.ctx.index(alloc_array.clone(), old_base, Type::bool_type());
+        self.assign(index, fail, self.ctx._true().into());
+        let new_base = self.ctx.pointer_base(address_of);
+        let index = self.ctx.index(alloc_array, new_base, Type::bool_type());
+        self.assign(index, succeed, self.ctx._true().into());
+
+        // Whether the new object is alive depends on the result
+        self.exec_state.update_place_state(new_object, PlaceState::Unknown);
+    }
+
+    /// The type of the new object after resizing `ty` to `new_size` bytes.
+    /// Only the same type or an array of the same element type is supported.
+    fn realloc_type(ty: Type, new_size: u64) -> Type {
+        if ty.size_in_bytes() == Some(new_size) {
+            return ty;
+        }
+        if ty.is_array() {
+            let elem_ty = ty.elem_type();
+            let elem_size = elem_ty.size_in_bytes().unwrap();
+            if elem_size != 0 && new_size % elem_size == 0 {
+                return Type::array_type(elem_ty, new_size / elem_size);
+            }
+        }
+        unsupported!("Not support realloc {ty} to {new_size} bytes");
+    }
+
+    /// Copy `min(old, new)` contents from `src` to `dst`. The length of
+    /// an infinite array is its object size.
+    fn copy_object(&mut self, dst: Expr, src: Expr, guard: Guard) {
+        if dst.ty() == src.ty() {
+            self.assign(dst, src, guard);
+            return;
+        }
+        let len = |ty: Type| match ty.is_array() {
+            true if ty.is_infinite_array() => None,
+            true => ty.array_size(),
+            false => Some(1),
+        };
+        let n = match (len(dst.ty()), len(src.ty())) {
+            (Some(m), Some(n)) => m.min(n),
+            (Some(n), None) | (None, Some(n)) => n,
+            (None, None) => unsupported!("Not support copying {src:?} to {dst:?}"),
+        };
+        let ctx = self.ctx.clone();
+        let element = |object: &Expr, i: Expr| match object.ty().is_array() {
+            true => ctx.index(object.clone(), i, object.ty().elem_type()),
+            false => object.clone(),
+        };
+        for i in 0..n as usize {
+            let i = self.ctx.constant_usize(i);
+            let mut guard = guard.clone();
+            for object in [&dst, &src] {
+                if object.ty().is_infinite_array() {
+                    let mut in_bound = self.ctx.lt(i.clone(), self.ctx.object_size(object.clone()));
+                    self.rename(&mut in_bound);
+                    guard.add(in_bound);
+                }
+            }
+            self.assign(element(&dst, i.clone()), element(&src, i), guard);
+        }
+    }
+
     fn symex_layout_new(&mut self, dest: Expr, fndef: &FunctionDef) {
         let ty = Type::from(fndef.1.0[0].expect_ty());
         self.assign(dest, self.ctx.mk_type(ty), self.ctx._true().into());
     }
+
+    /// The type of the object alloced by `layout`, and the runtime length
+    /// of the object if the size of the layout is symbolic
+    fn alloc_layout(&mut self, mut layout: Expr) -> (Type, Option<Expr>) {
+        self.replace_predicates(&mut layout);
+        self.rename(&mut layout);
+        if layout.is_cast() {
+            return (layout.ty(), Some(layout.extract_src()));
+        }
+        assert!(layout.is_type());
+        (layout.extract_type(), None)
+    }
+
+    /// A layout of `size` bytes aligned to `align` is an array of unsigned
+    /// integers of `align` bytes, whose length is rounded up. A symbolic
+    /// size makes an infinite array whose length is carried by a cast. The
+    /// checked version is `Err` if `align` is not a power of two or `size`
+    /// overflows `isize` after rounding up to `align`.
+    fn symex_layout_from_size_align(&mut self, dest: Expr, args: Vec<Expr>, checked: bool) {
+        let mut operands = Vec::new();
+        for mut arg in args {
+            if arg.is_object() {
+                arg = arg.extract_inner_expr();
+            }
+            self.replace_predicates(&mut arg);
+            self.rename(&mut arg);
+            arg.simplify();
+            operands.push(arg);
+        }
+        let (size, align) = (operands[0].clone(), operands[1].clone());
+        if !align.is_constant() {
+            unsupported!("Not support layout with symbolic align {align:?}");
+        }
+        let align_value = bigint_to_u64(&align.extract_constant().to_integer());
+        let valid = if checked && align_value.is_power_of_two() {
+            let rounded =
+                self.ctx.add(size.clone(), self.ctx.constant_usize(align_value as usize - 1));
+            let mut valid = self.ctx.le(rounded, self.ctx.constant_usize(isize::MAX as usize));
+            valid.simplify();
+            valid
+        } else {
+            self.ctx.constant_bool(!checked)
+        };
+        if valid.is_false() {
+            let err = self.layout_error(dest.ty());
+            self.assign(dest, err, self.ctx._true().into());
+            return;
+        }
+
+        let elem_ty = match align_value {
+            1 => Type::unsigned_type(UintTy::U8),
+            2 => Type::unsigned_type(UintTy::U16),
+            4 => Type::unsigned_type(UintTy::U32),
+            8 => Type::unsigned_type(UintTy::U64),
+            16 => Type::unsigned_type(UintTy::U128),
+            _ => unsupported!("Not support layout with align {align_value}"),
+        };
+        let layout = if size.is_constant() {
+            let size_value = bigint_to_u64(&size.extract_constant().to_integer());
+            self.ctx.mk_type(Type::array_type(elem_ty, size_value.div_ceil(align_value)))
+        } else {
+            let rounded = self.ctx.add(size, self.ctx.constant_usize(align_value as usize - 1));
+            let len = self.ctx.div(rounded, align);
+            self.ctx.cast(len, self.ctx.mk_type(Type::infinite_array_type(elem_ty)))
+        };
+
+        if !checked {
+            self.assign(dest, layout, self.ctx._true().into());
+            return;
+        }
+        // The failure is reported by unwrapping `Err`
+        let ok = self.option_variant(dest.ty(), "Ok");
+        let data_ty = dest.ty().enum_variant_data_type(bigint_to_usize(&ok.extract_integer()));
+        let data = self.ctx.aggregate(vec![layout], data_ty);
+        let mut result = self.ctx.variant(ok, Some(data), dest.ty());
+        if !valid.is_true() {
+            result = self.ctx.ite(valid, result, self.layout_error(dest.ty()));
+        }
+        self.assign(dest, result, self.ctx._true().into());
+    }
+
+    /// `Err(LayoutError)` of the result type `ty`
+    fn layout_error(&self, ty: Type) -> Expr {
+        let err = self.option_variant(ty, "Err");
+        let data_ty = ty.enum_variant_data_type(bigint_to_usize(&err.extract_integer()));
+        let error_ty = data_ty.tuple_def()[0];
+        let data = self.ctx.aggregate(vec![self.ctx.aggregate(vec![], error_ty)], data_ty);
+        self.ctx.variant(err, Some(data), ty)
+    }
 }
@@ -1,6 +1,7 @@
 use stable_mir::CrateDef;
 
 use super::super::symex::*;
+use crate::bmc::error::unsupported;
 use crate::expr::expr::*;
 use crate::expr::ty::*;
 use crate::symbol::nstring::*;
@@ -24,11 +25,11 @@ impl<'cfg> Symex<'cfg> {
         } else if name == "Box::<T, A>::into_raw" {
             self.symex_box_into_raw(dest, args);
         } else {
-            panic!("Not support {name:?}");
+            unsupported!("Not support {name:?}");
         }
     }
 
-    fn symex_box_new(&mut self, dest: Expr, args: Vec<Expr>) {
+    pub(super) fn symex_box_new(&mut self, dest: Expr, args: Vec<Expr>) {
         let lhs = dest.clone();
         let ty = lhs.ty().pointee_ty();
         let object = self.exec_state.new_object(ty);
@@ -92,6 +93,11 @@ impl<'cfg> Symex<'cfg> {
         let rhs = self.ctx.cast(_box.clone(), target_ty);
         self.assign(lhs, rhs, self.ctx._true().into());
 
+        self.disown_objects(_box);
+    }
+
+    /// The objects owned by the box are not dropped with it anymore.
+    pub(in super::super) fn disown_objects(&mut self, _box: Expr) {
         // Update place states for objects.
         let mut objects = ObjectSet::new();
         self.top().cur_state.get_value_set(_box.clone(), &mut objects);
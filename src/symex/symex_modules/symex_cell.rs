@@ -0,0 +1,91 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::expr::expr::*;
+use crate::expr::ty::FunctionDef;
+use crate::symbol::nstring::*;
+use crate::symex::projection::Mode;
+
+/// This mod defines symbolic execution of api in std::cell.
+/// `Cell` and `UnsafeCell` are transparent, so reading or writing a
+/// cell is the same as the wrapped value. Mutating a cell through a
+/// shared reference is the only difference in Rust.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_cell_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        if name == "Cell::<T>::new" || name == "UnsafeCell::<T>::new" {
+            self.symex_cell_new(dest, args);
+        } else if name == "Cell::<T>::get" {
+            self.symex_cell_get(dest, args);
+        } else if name == "Cell::<T>::set" {
+            self.symex_cell_set(args);
+        } else if name == "Cell::<T>::replace" {
+            self.symex_cell_replace(dest, args);
+        } else if name == "Cell::<T>::into_inner" || name == "UnsafeCell::<T>::into_inner" {
+            self.symex_cell_into_inner(dest, args);
+        } else if name == "UnsafeCell::<T>::get"
+            || name == "Cell::<T>::as_ptr"
+            || name == "Cell::<T>::get_mut"
+            || name == "UnsafeCell::<T>::get_mut"
+        {
+            self.symex_cell_as_ptr(dest, args);
+        } else {
+            unsupported!("Not support for {name:?}");
+        }
+    }
+
+    /// The wrapped value of a cell, seeing through `UnsafeCell`
+    fn cell_value(&self, cell: Expr) -> Expr {
+        assert!(cell.ty().is_cell());
+        let ty = cell.ty().struct_def().1[0].1;
+        let object = if cell.is_object() { cell } else { self.ctx.object(cell) };
+        let value = self.ctx.index(object, self.ctx.constant_isize(0), ty);
+        if ty.is_cell() { self.cell_value(value) } else { value }
+    }
+
+    fn cell_place(&mut self, pt: Expr) -> Expr {
+        let ty = pt.ty().pointee_ty();
+        self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty)
+    }
+
+    fn symex_cell_new(&mut self, dest: Expr, args: Vec<Expr>) {
+        let value = self.cell_value(dest);
+        self.assign(value, args[0].clone(), self.ctx._true().into());
+    }
+
+    fn symex_cell_get(&mut self, dest: Expr, args: Vec<Expr>) {
+        let cell = self.cell_place(args[0].clone());
+        let value = self.cell_value(cell);
+        self.assign(dest, value, self.ctx._true().into());
+    }
+
+    fn symex_cell_set(&mut self, args: Vec<Expr>) {
+        let cell = self.cell_place(args[0].clone());
+        let value = self.cell_value(cell);
+        self.assign(value, args[1].clone(), self.ctx._true().into());
+    }
+
+    fn symex_cell_replace(&mut self, dest: Expr, args: Vec<Expr>) {
+        let cell = self.cell_place(args[0].clone());
+        let value = self.cell_value(cell);
+        self.assign(dest, value.clone(), self.ctx._true().into());
+        self.assign(value, args[1].clone(), self.ctx._true().into());
+    }
+
+    fn symex_cell_into_inner(&mut self, dest: Expr, args: Vec<Expr>) {
+        let mut cell = args[0].clone();
+        self.replace_predicates(&mut cell);
+        let value = self.cell_value(cell);
+        self.assign(dest, value, self.ctx._true().into());
+    }
+
+    /// The pointer to the wrapped value
+    fn symex_cell_as_ptr(&mut self, dest: Expr, args: Vec<Expr>) {
+        let cell = self.cell_place(args[0].clone());
+        let value = self.cell_value(cell);
+        let address_of = self.ctx.address_of(self.ctx.object(value), dest.ty());
+        self.assign(dest, address_of, self.ctx._true().into());
+    }
+}
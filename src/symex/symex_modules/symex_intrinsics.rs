@@ -0,0 +1,77 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::config::cli::Check;
+use crate::expr::expr::*;
+use crate::expr::ty::FunctionDef;
+use crate::symbol::nstring::*;
+
+/// Names of the intrinsics and the hints that have summaries
+pub(crate) const SUMMARIZED_INTRINSICS: [&str; 14] = [
+    "abort",
+    "assert_inhabited",
+    "assert_mem_uninitialized_valid",
+    "assert_unchecked",
+    "assert_zero_valid",
+    "assume",
+    "black_box",
+    "cold_path",
+    "likely",
+    "spin_loop",
+    "ub_checks",
+    "unlikely",
+    "unreachable",
+    "unreachable_unchecked",
+];
+
+/// This mod defines the summaries of intrinsics and hints, whose bodies
+/// are not available. Only the ones in `SUMMARIZED_INTRINSICS` are
+/// recognized. Calling other functions without bodies makes the result
+/// unknown.
+
+impl<'cfg> Symex<'cfg> {
+    /// The last segment of the path, for the same intrinsic is exported
+    /// by both `std::intrinsics` and `std::hint`
+    fn intrinsic_name(fndef: &FunctionDef) -> String {
+        let name = fndef.0.name();
+        name.rsplit("::").next().unwrap().to_string()
+    }
+
+    pub(in super::super) fn is_summarized_intrinsic(fndef: &FunctionDef) -> bool {
+        let name = NString::from(fndef.0.name());
+        (name.contains("std::intrinsics".into()) || name.contains("std::hint".into()))
+            && SUMMARIZED_INTRINSICS.contains(&Symex::intrinsic_name(fndef).as_str())
+    }
+
+    /// Return `false` if the intrinsic diverges, and the path ends
+    pub(in super::super) fn symex_intrinsic(
+        &mut self,
+        fndef: &FunctionDef,
+        args: Vec<Expr>,
+        dest: Expr,
+    ) -> bool {
+        let name = Symex::intrinsic_name(fndef);
+        match name.as_str() {
+            "likely" | "unlikely" | "black_box" => {
+                self.assign(dest, args[0].clone(), self.ctx._true().into());
+            }
+            "assume" => self.symex_assume(args[0].clone()),
+            // Preconditions of unsafe functions in std are not checked
+            "ub_checks" => self.assign(dest, self.ctx._false(), self.ctx._true().into()),
+            "assert_unchecked" => {
+                let msg = NString::from("assert_unchecked failure: the condition does not hold");
+                self.claim(Check::Assertion, msg, self.ctx.not(args[0].clone()));
+                self.symex_assume(args[0].clone());
+            }
+            "unreachable" | "unreachable_unchecked" => {
+                let msg = NString::from("unreachable failure: reach unreachable code");
+                self.claim(Check::Assertion, msg, self.ctx._true());
+                return false;
+            }
+            "abort" => return false,
+            // No effect in the model
+            _ => {}
+        }
+        true
+    }
+}
@@ -0,0 +1,115 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::expr::expr::*;
+use crate::expr::ty::*;
+use crate::symbol::nstring::*;
+use crate::symex::projection::Mode;
+
+/// This mod defines symbolic execution of api in std::mem.
+/// `drop` drops its argument as leaving the scope. `forget` takes
+/// the ownership without dropping, so the memory owned by a box is
+/// never dealloced. `swap`, `replace` and `take` move values between
+/// places, and the pointers in value sets move along with them.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_mem_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        if name == "drop" {
+            self.symex_mem_drop(args);
+        } else if name == "forget" {
+            self.symex_mem_forget(args);
+        } else if name == "swap" {
+            self.symex_mem_swap(args);
+        } else if name == "replace" {
+            self.symex_mem_replace(dest, args);
+        } else if name == "take" {
+            self.symex_mem_take(dest, args);
+        } else {
+            unsupported!("Not support for {name:?}");
+        }
+    }
+
+    fn symex_mem_drop(&mut self, args: Vec<Expr>) {
+        let mut value = args[0].clone();
+        self.replace_predicates(&mut value);
+        let ty = value.ty();
+        if !ty.is_box() && !ty.is_vec() && !ty.is_rc() && !ty.is_struct() && !ty.is_enum() {
+            return;
+        }
+        if !value.is_object() {
+            value = self.ctx.object(value);
+        }
+        self.symex_drop_rec(value, self.ctx._true().into());
+    }
+
+    fn symex_mem_forget(&mut self, args: Vec<Expr>) {
+        let mut value = args[0].clone();
+        self.replace_predicates(&mut value);
+        if value.is_object() {
+            value = value.extract_inner_expr();
+        }
+        if value.ty().is_box() {
+            self.disown_objects(value);
+        }
+    }
+
+    fn mem_place(&mut self, pt: Expr, mode: Mode) -> Expr {
+        let ty = pt.ty().pointee_ty();
+        self.make_deref(pt, mode, self.ctx._true().into(), ty)
+    }
+
+    fn symex_mem_swap(&mut self, args: Vec<Expr>) {
+        let x = self.mem_place(args[0].clone(), Mode::Read);
+        let y = self.mem_place(args[1].clone(), Mode::Read);
+        let tmp = self.exec_state.new_tmp(x.ty());
+        self.assign(tmp.clone(), x, self.ctx._true().into());
+        let x = self.mem_place(args[0].clone(), Mode::Write);
+        self.assign(x, y, self.ctx._true().into());
+        let y = self.mem_place(args[1].clone(), Mode::Write);
+        self.assign(y, tmp, self.ctx._true().into());
+    }
+
+    fn symex_mem_replace(&mut self, dest: Expr, args: Vec<Expr>) {
+        let old = self.mem_place(args[0].clone(), Mode::Read);
+        self.assign(dest, old, self.ctx._true().into());
+        let mut src = args[1].clone();
+        self.replace_predicates(&mut src);
+        let place = self.mem_place(args[0].clone(), Mode::Write);
+        self.assign(place, src, self.ctx._true().into());
+    }
+
+    fn symex_mem_take(&mut self, dest: Expr, args: Vec<Expr>) {
+        let old = self.mem_place(args[0].clone(), Mode::Read);
+        self.assign(dest, old, self.ctx._true().into());
+        let place = self.mem_place(args[0].clone(), Mode::Write);
+        self.assign_default(place);
+    }
+
+    /// Assign `Default::default()` to the place. Only integers, bools,
+    /// `Option`, `Vec` and `Box` of them are supported, for other types
+    /// may implement `Default` by arbitrary code.
+    fn assign_default(&mut self, place: Expr) {
+        let ty = place.ty();
+        if ty.is_vec() {
+            self.symex_vec_new(place, self.ctx.constant_usize(0), false);
+        } else if ty.is_box() {
+            let inner = self.exec_state.new_tmp(ty.pointee_ty());
+            self.assign_default(inner.clone());
+            self.symex_box_new(place, vec![inner]);
+        } else {
+            let default = if ty.is_integer() {
+                self.ctx.constant_integer(0.into(), ty)
+            } else if ty.is_bool() {
+                self.ctx._false()
+            } else if ty.is_enum() && ty.name() == "Option" {
+                let none = self.option_variant(ty, "None");
+                self.ctx.variant(none, None, ty)
+            } else {
+                unsupported!("Not support for taking {ty}")
+            };
+            self.assign(place, default, self.ctx._true().into());
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::expr::expr::*;
+use crate::expr::ty::*;
+use crate::program::program::bigint_to_usize;
+use crate::symbol::nstring::*;
+
+/// This mod defines symbolic execution of the integer methods in std::num.
+/// Integers are unbounded in SMT, so the result of `+`, `-` or `*` is
+/// computed first. Then `wrapping_*` wraps it mod 2^width, `checked_*`
+/// returns `None` if it is out of range, and `saturating_*` clamps it to
+/// the range. `wrapping_shl` and `wrapping_shr` mask the shift amount by
+/// the width. None of them is an overflow.
+
+/// The methods on two integers that are modeled
+const NUM_METHODS: [&str; 11] = [
+    "wrapping_add",
+    "wrapping_sub",
+    "wrapping_mul",
+    "wrapping_shl",
+    "wrapping_shr",
+    "checked_add",
+    "checked_sub",
+    "checked_mul",
+    "saturating_add",
+    "saturating_sub",
+    "saturating_mul",
+];
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_num_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        let method = fndef.0.name().rsplit("::").next().unwrap().to_string();
+        if !NUM_METHODS.contains(&method.as_str()) || args.len() != 2 {
+            unsupported!("Not support for {name:?}");
+        }
+        let (kind, op) = method.split_once('_').unwrap();
+        let mut lhs = args[0].clone();
+        let mut rhs = args[1].clone();
+        self.replace_predicates(&mut lhs);
+        self.replace_predicates(&mut rhs);
+        if kind == "wrapping" && (op == "shl" || op == "shr") {
+            let shifted = self.make_shift(lhs, rhs, op == "shl");
+            self.assign(dest, shifted, self.ctx._true().into());
+            return;
+        }
+        let value = match op {
+            "add" => self.ctx.add(lhs, rhs),
+            "sub" => self.ctx.sub(lhs, rhs),
+            "mul" => self.ctx.mul(lhs, rhs),
+            _ => unreachable!(),
+        };
+        let rhs = match kind {
+            "wrapping" => self.wrap_integer(value),
+            "checked" => self.checked_integer(value, dest.ty()),
+            "saturating" => self.saturate_integer(value),
+            _ => unreachable!(),
+        };
+        self.assign(dest, rhs, self.ctx._true().into());
+    }
+
+    /// `Some(value)` if the value is in the range of its type
+    fn checked_integer(&self, value: Expr, option_ty: Type) -> Expr {
+        let none = self.option_variant(option_ty, "None");
+        let some = self.option_variant(option_ty, "Some");
+        let data_ty = option_ty.enum_variant_data_type(bigint_to_usize(&some.extract_integer()));
+        let data = self.ctx.aggregate(vec![value.clone()], data_ty);
+        let mut checked = self.ctx.ite(
+            self.in_range(value),
+            self.ctx.variant(some, Some(data), option_ty),
+            self.ctx.variant(none, None, option_ty),
+        );
+        checked.simplify();
+        checked
+    }
+
+    /// The value clamped to the range of its type
+    fn saturate_integer(&self, value: Expr) -> Expr {
+        let ty = value.ty();
+        let (min, max) = ty.integer_range();
+        let min = self.ctx.constant_integer(min, ty);
+        let max = self.ctx.constant_integer(max, ty);
+        let clamped = self.ctx.ite(self.ctx.lt(value.clone(), min.clone()), min, value.clone());
+        let mut saturated = self.ctx.ite(self.ctx.gt(value, max.clone()), max, clamped);
+        saturated.simplify();
+        saturated
+    }
+
+    fn in_range(&self, value: Expr) -> Expr {
+        let ty = value.ty();
+        let (min, max) = ty.integer_range();
+        self.ctx.and(
+            self.ctx.ge(value.clone(), self.ctx.constant_integer(min, ty)),
+            self.ctx.le(value, self.ctx.constant_integer(max, ty)),
+        )
+    }
+}
@@ -1,6 +1,8 @@
 use stable_mir::CrateDef;
 
 use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
 use crate::expr::guard::Guard;
 use crate::expr::ty::*;
@@ -16,7 +18,7 @@ impl<'cfg> Symex<'cfg> {
         if name == "Index::index" || name == "IndexMut::index_mut" {
             self.symex_ops_index(dest, args);
         } else {
-            panic!("Not support for {name:?}");
+            unsupported!("Not support for {name:?}");
         }
     }
 
@@ -30,8 +32,6 @@ impl<'cfg> Symex<'cfg> {
             let pt = args[0].clone();
             let (l, r) = self.make_range(args[1].clone());
 
-            // Maybe a bug for stable mir: the operands for Range do not
-            // follow the endian.
             let slice = self.make_deref(pt.clone(), Mode::Slice(l, r), self.ctx._true().into(), ty);
 
             let rhs = self.ctx.address_of(self.ctx.object(slice), lhs.ty());
@@ -51,7 +51,7 @@ impl<'cfg> Symex<'cfg> {
                 self.ctx.ge(i.clone(), vec_len),
             );
             let msg = NString::from("dereference fail: out of Vec bound");
-            self.claim(msg, out_of_bound);
+            self.claim(Check::Bound, msg, out_of_bound);
 
             // Index vec
             let array_ty = _vec.ty().pointee_ty();
@@ -65,7 +65,7 @@ impl<'cfg> Symex<'cfg> {
             return;
         }
 
-        panic!("Do not support index({:?})", ty.pointee_ty());
+        unsupported!("Do not support index({})", ty.pointee_ty());
     }
 
     pub(super) fn make_range(&mut self, range: Expr) -> (Option<usize>, Option<usize>) {
@@ -76,9 +76,8 @@ impl<'cfg> Symex<'cfg> {
         } else {
             let fields = range.extract_constant().to_struct_fields();
             if name == "Range" {
-                // Maybe a bug for MIR
-                let l = bigint_to_usize(&fields[1].0.to_integer());
-                let r = bigint_to_usize(&fields[0].0.to_integer());
+                let l = bigint_to_usize(&fields[0].0.to_integer());
+                let r = bigint_to_usize(&fields[1].0.to_integer());
                 (Some(l), Some(r))
             } else if name == "RangeFrom" {
                 let l = bigint_to_usize(&fields[0].0.to_integer());
@@ -87,7 +86,7 @@ impl<'cfg> Symex<'cfg> {
                 let r = bigint_to_usize(&fields[0].0.to_integer());
                 (None, Some(r))
             } else {
-                panic!("No support {name:?}")
+                unsupported!("No support {name:?}")
             }
         }
     }
@@ -0,0 +1,71 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
+use crate::expr::expr::*;
+use crate::expr::ty::*;
+use crate::symbol::nstring::*;
+use crate::symex::projection::Mode;
+
+/// This mod defines symbolic execution of api in std::option.
+/// `Option` is an enum whose variants are `None` and `Some`. Taking
+/// an option moves its value to the destination and leaves `None`,
+/// so the box in it is only dropped by the new owner.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_option_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        if name == "Option::<T>::take" {
+            self.symex_option_take(dest, args);
+        } else if name == "Option::<T>::is_some" {
+            self.symex_option_is_variant(dest, args, "Some");
+        } else if name == "Option::<T>::is_none" {
+            self.symex_option_is_variant(dest, args, "None");
+        } else if name == "Option::<T>::unwrap" {
+            self.symex_option_unwrap(dest, args);
+        } else {
+            unsupported!("Not support for {name:?}");
+        }
+    }
+
+    /// The index of the variant `name` of the option or result type `ty`
+    pub(super) fn option_variant(&self, ty: Type, name: &str) -> Expr {
+        let def = ty.enum_def();
+        let i = def.1.iter().position(|(n, _)| *n == name).expect("Not an option");
+        self.ctx.constant_usize(i)
+    }
+
+    fn option_place(&mut self, pt: Expr) -> Expr {
+        let ty = pt.ty().pointee_ty();
+        self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty)
+    }
+
+    fn symex_option_take(&mut self, dest: Expr, args: Vec<Expr>) {
+        let place = self.option_place(args[0].clone());
+        let none = self.option_variant(place.ty(), "None");
+        let none_value = self.ctx.variant(none, None, place.ty());
+        self.assign(dest, place.clone(), self.ctx._true().into());
+        self.assign(place, none_value, self.ctx._true().into());
+    }
+
+    fn symex_option_is_variant(&mut self, dest: Expr, args: Vec<Expr>, name: &str) {
+        let place = self.option_place(args[0].clone());
+        let idx = self.option_variant(place.ty(), name);
+        let is_variant = self.ctx.match_variant(place, idx);
+        self.assign(dest, is_variant, self.ctx._true().into());
+    }
+
+    fn symex_option_unwrap(&mut self, dest: Expr, args: Vec<Expr>) {
+        let mut value = args[0].clone();
+        self.replace_predicates(&mut value);
+        let none = self.option_variant(value.ty(), "None");
+        let msg = NString::from("unwrap failure: called `Option::unwrap()` on a `None` value");
+        self.claim(Check::Assertion, msg, self.ctx.match_variant(value.clone(), none));
+
+        let some = self.option_variant(value.ty(), "Some");
+        let data = self.ctx.object(self.ctx.as_variant(value, some));
+        let inner = self.ctx.index(data, self.ctx.constant_isize(0), dest.ty());
+        self.assign(dest, inner, self.ctx._true().into());
+    }
+}
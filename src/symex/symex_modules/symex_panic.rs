@@ -0,0 +1,45 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::config::cli::Check;
+use crate::expr::expr::*;
+use crate::expr::ty::FunctionDef;
+use crate::symbol::nstring::*;
+
+/// Names of the functions in std starting a panic
+pub(crate) const PANIC_FUNCTIONS: [&str; 12] = [
+    "assert_failed",
+    "begin_panic",
+    "expect_failed",
+    "panic",
+    "panic_any",
+    "panic_display",
+    "panic_explicit",
+    "panic_fmt",
+    "panic_nounwind",
+    "panic_str_2015",
+    "unreachable_display",
+    "unwrap_failed",
+];
+
+/// This mod defines the property of reachable panics. `panic!`,
+/// `unreachable!`, `assert!`, `todo!` and so on call one of
+/// `PANIC_FUNCTIONS`. Reaching the call is a failure, and the path ends
+/// since a panic never returns. Index out of bounds and unwrapping `None`
+/// are reported by their own checks.
+
+impl<'cfg> Symex<'cfg> {
+    pub(in super::super) fn is_panic_function(fndef: &FunctionDef) -> bool {
+        let name = fndef.0.name();
+        let last = name.rsplit("::").next().unwrap();
+        !fndef.0.krate().is_local && PANIC_FUNCTIONS.contains(&last)
+    }
+
+    pub(in super::super) fn symex_panic(&mut self, fndef: &FunctionDef) {
+        let msg = NString::from(format!(
+            "reachable-panic failure: {} is reached",
+            fndef.0.trimmed_name()
+        ));
+        self.claim(Check::Panic, msg, self.ctx._true());
+    }
+}
@@ -1,9 +1,14 @@
+use num_bigint::BigInt;
 use stable_mir::CrateDef;
 
 use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::expr::*;
+use crate::expr::guard::Guard;
 use crate::expr::ty::*;
 use crate::symbol::nstring::*;
+use crate::symex::projection::Mode;
 use crate::symex::value_set::ObjectSet;
 
 /// This mod defines symbolic execution of api in std::ptr
@@ -15,14 +20,37 @@ impl<'cfg> Symex<'cfg> {
             self.symex_ptr_eq(dest, args);
         } else if name == "null_mut" || name == "null" {
             self.symex_ptr_null(dest);
-        } else if name == "std::ptr::mut_ptr::<impl *mut T>::add" {
-            self.symex_ptr_add(dest, args);
-        } else if name == "std::ptr::mut_ptr::<impl *mut T>::offset" {
-            self.symex_ptr_offset(dest, args);
-        } else if name == "std::ptr::mut_ptr::<impl *mut T>::is_null" {
+        } else if name == "std::ptr::mut_ptr::<impl *mut T>::add"
+            || name == "std::ptr::const_ptr::<impl *const T>::add"
+        {
+            self.symex_ptr_offset(dest, args, false);
+        } else if name == "std::ptr::mut_ptr::<impl *mut T>::sub"
+            || name == "std::ptr::const_ptr::<impl *const T>::sub"
+        {
+            self.symex_ptr_offset(dest, args, true);
+        } else if name == "std::ptr::mut_ptr::<impl *mut T>::offset"
+            || name == "std::ptr::const_ptr::<impl *const T>::offset"
+        {
+            self.symex_ptr_offset(dest, args, false);
+        } else if name == "std::ptr::mut_ptr::<impl *mut T>::is_null"
+            || name == "std::ptr::const_ptr::<impl *const T>::is_null"
+        {
             self.symex_ptr_is_null(dest, args);
+        } else if name == "drop_in_place" {
+            self.symex_ptr_drop_in_place(args);
+        } else if name == "write" || name == "std::ptr::mut_ptr::<impl *mut T>::write" {
+            self.symex_ptr_write(args);
+        } else if name == "read"
+            || name == "std::ptr::mut_ptr::<impl *mut T>::read"
+            || name == "std::ptr::const_ptr::<impl *const T>::read"
+        {
+            self.symex_ptr_read(dest, args);
+        } else if name == "copy_nonoverlapping" {
+            self.symex_ptr_copy(args, true);
+        } else if name == "copy" {
+            self.symex_ptr_copy(args, false);
         } else {
-            panic!("Not support for {name:?}");
+            unsupported!("Not support for {name:?}");
         }
     }
 
@@ -38,26 +66,179 @@ impl<'cfg> Symex<'cfg> {
         self.assign(lhs, rhs, self.ctx._true().into());
     }
 
-    fn symex_ptr_null(&mut self, dest: Expr) {
-        let lhs = dest.clone();
-        let rhs = self.ctx.null(lhs.ty());
-        self.assign(lhs, rhs, self.ctx._true().into());
+    /// Drop the value pointed to without moving it out. Dropping it
+    /// again later is a double free.
+    fn symex_ptr_drop_in_place(&mut self, args: Vec<Expr>) {
+        let pt = args[0].clone();
+        let ty = pt.ty().pointee_ty();
+        let place = self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty);
+        let object = self.ctx.object(place);
+        self.symex_drop_rec(object, self.ctx._true().into());
     }
 
-    fn symex_ptr_add(&mut self, dest: Expr, args: Vec<Expr>) {
-        let lhs = dest.clone();
+    /// `write(p, v)` overwrites the value pointed to without dropping it
+    fn symex_ptr_write(&mut self, args: Vec<Expr>) {
+        let pt = args[0].clone();
+        let value = args[1].clone();
+        let ty = pt.ty().pointee_ty();
+        if ty.is_zst() {
+            return;
+        }
+        let place = self.make_deref(pt, Mode::Write, self.ctx._true().into(), ty);
+        self.assign(place, value, self.ctx._true().into());
+    }
 
+    /// `read(p)` copies the value pointed to, leaving it unchanged
+    fn symex_ptr_read(&mut self, dest: Expr, args: Vec<Expr>) {
         let pt = args[0].clone();
-        let mut count = args[1].clone();
+        let ty = pt.ty().pointee_ty();
+        if ty.is_zst() {
+            return;
+        }
+        let place = self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty);
+        self.uninit_read_check(&place, self.ctx._true().into());
+        self.assign(dest, place, self.ctx._true().into());
+    }
+
+    /// `copy_nonoverlapping(src, dst, count)` copies `count` elements. Both
+    /// regions must be in bounds, and overlapping regions are UB. `copy`
+    /// permits overlapping regions like `memmove`, which is exactly what the
+    /// copy does, for the elements are read from the array before the copy.
+    fn symex_ptr_copy(&mut self, args: Vec<Expr>, nonoverlapping: bool) {
+        let src = args[0].clone();
+        let dst = args[1].clone();
+        let mut count = args[2].clone();
         if count.is_object() {
             count = count.extract_inner_expr();
         }
-        let rhs = self.ctx.offset(pt, count);
+        self.replace_predicates(&mut count);
+        self.rename(&mut count);
+        count.simplify();
+        let ty = dst.ty().pointee_ty();
+        if ty.is_zst()
+            || count.is_constant() && count.extract_constant().to_integer() == BigInt::ZERO
+        {
+            return;
+        }
+
+        if nonoverlapping {
+            // Neither region ends before the other starts. Offsets are in
+            // field-level.
+            let n = self.ctx.mul(
+                self.ctx.cast(count.clone(), self.ctx.mk_type(Type::isize_type())),
+                self.ctx.constant_isize(ty.num_fields() as isize),
+            );
+            let src_offset = self.ctx.pointer_offset(src.clone());
+            let dst_offset = self.ctx.pointer_offset(dst.clone());
+            let overlap = self.ctx.and(
+                self.ctx.same_object(src.clone(), dst.clone()),
+                self.ctx.and(
+                    self.ctx.lt(src_offset.clone(), self.ctx.add(dst_offset.clone(), n.clone())),
+                    self.ctx.lt(dst_offset, self.ctx.add(src_offset, n)),
+                ),
+            );
+            let msg = NString::from("copy_nonoverlapping failure: the regions overlap");
+            self.claim(Check::InvalidPointer, msg, overlap);
+        }
+
+        let dst_place = self.make_deref(dst, Mode::Write, self.ctx._true().into(), ty);
+        let src_place = self.make_deref(src, Mode::Read, self.ctx._true().into(), ty);
+        for (d, dst_guard) in self.split_ite(&dst_place, self.ctx._true().into()) {
+            for (s, src_guard) in self.split_ite(&src_place, dst_guard.clone()) {
+                self.copy_elements(d.clone(), s, count.clone(), src_guard);
+            }
+        }
+    }
 
+    /// Copy `count` elements from the place `src` to the place `dst`. The
+    /// region of an element of an array is copied at once.
+    fn copy_elements(&mut self, dst: Expr, src: Expr, count: Expr, guard: Guard) {
+        let region = |place: &Expr| {
+            (place.is_index() && place.extract_object().ty().is_array())
+                .then(|| (place.extract_object(), place.extract_index()))
+        };
+        match (region(&dst), region(&src)) {
+            (Some((dst_array, dst_start)), Some((src_array, src_start))) => {
+                self.region_bound_check(
+                    "copy",
+                    dst_array.clone(),
+                    dst_start.clone(),
+                    count.clone(),
+                    guard.clone(),
+                );
+                self.region_bound_check(
+                    "copy",
+                    src_array.clone(),
+                    src_start.clone(),
+                    count.clone(),
+                    guard.clone(),
+                );
+                let dst_region = self.ctx.slice(dst_array.clone(), dst_start, count.clone());
+                let src_region = self.ctx.slice(src_array, src_start, count.clone());
+                let value = self.ctx.copy_region(dst_region, src_region, count.clone());
+                // Only the copied region of the array is written
+                self.mark_region_written(&dst, &count, guard.clone());
+                self.assign_rec(dst_array, value, guard);
+            }
+            _ => {
+                // Only one element is in the object
+                let one = self.ctx.constant_integer(BigInt::from(1), count.ty());
+                let mut error = guard.clone();
+                error.add(self.ctx.gt(count, one));
+                let msg = NString::from(format!("copy failure: region out of bounds of {dst:?}"));
+                self.claim(Check::Bound, msg, error.to_expr());
+                self.assign(dst, src, guard);
+            }
+        }
+    }
+
+    /// `count` elements from `start` must be in the array
+    pub(super) fn region_bound_check(
+        &mut self,
+        api: &str,
+        array: Expr,
+        start: Expr,
+        count: Expr,
+        guard: Guard,
+    ) {
+        let len = match array.ty().array_size() {
+            Some(n) => self.ctx.constant_integer(BigInt::from(n), start.ty()),
+            None => {
+                // The buffer of a vec is bounded by its capacity
+                let root = array.extract_root_object().extract_inner_expr();
+                let len = if let Some(cap) =
+                    self.exec_state.vec_caps.get(&root.extract_symbol().ident())
+                {
+                    cap.clone()
+                } else if self.exec_state.is_sized_object(&array) {
+                    self.ctx.object_size(array.clone())
+                } else {
+                    return;
+                };
+                self.ctx.cast(len, self.ctx.mk_type(start.ty()))
+            }
+        };
+        let n = self.ctx.cast(count, self.ctx.mk_type(start.ty()));
+        let zero = self.ctx.constant_integer(BigInt::ZERO, start.ty());
+        let out_of_bound =
+            self.ctx.or(self.ctx.lt(start.clone(), zero), self.ctx.gt(self.ctx.add(start, n), len));
+        let mut error = guard;
+        error.add(out_of_bound);
+        let msg = NString::from(format!("{api} failure: region out of bounds of {array:?}"));
+        self.claim(Check::Bound, msg, error.to_expr());
+    }
+
+    fn symex_ptr_null(&mut self, dest: Expr) {
+        let lhs = dest.clone();
+        let rhs = self.ctx.null(lhs.ty());
         self.assign(lhs, rhs, self.ctx._true().into());
     }
 
-    fn symex_ptr_offset(&mut self, dest: Expr, args: Vec<Expr>) {
+    /// `add`, `sub` and `offset` move the pointer by `count` elements of
+    /// the pointee. The offset is counted in elements, while the offset in
+    /// bytes, `count * size_of::<T>()`, must not overflow `isize`. The
+    /// result must be in the allocation or one past its end.
+    fn symex_ptr_offset(&mut self, dest: Expr, args: Vec<Expr>, backward: bool) {
         let lhs = dest.clone();
 
         let pt = args[0].clone();
@@ -65,11 +246,80 @@ impl<'cfg> Symex<'cfg> {
         if count.is_object() {
             count = count.extract_inner_expr();
         }
+        if backward {
+            count = if count.is_constant() {
+                let c = count.extract_constant().to_integer();
+                self.ctx.constant_integer(-c, Type::isize_type())
+            } else {
+                self.ctx.neg(self.ctx.cast(count, self.ctx.mk_type(Type::isize_type())))
+            };
+        }
+        self.pointer_offset_check(pt.clone(), count.clone());
         let rhs = self.ctx.offset(pt, count);
 
         self.assign(lhs, rhs, self.ctx._true().into());
     }
 
+    fn pointer_offset_check(&mut self, pt: Expr, count: Expr) {
+        let size = pt.ty().pointee_ty().size_in_bytes().expect("Pointee must be sized");
+        if size == 0 {
+            return;
+        }
+        let ty = count.ty();
+        let bytes = self.ctx.mul(count.clone(), self.ctx.constant_integer(BigInt::from(size), ty));
+        let (min, max) = Type::isize_type().integer_range();
+        let overflow = self.ctx.or(
+            self.ctx.lt(bytes.clone(), self.ctx.constant_integer(min, ty)),
+            self.ctx.gt(bytes, self.ctx.constant_integer(max, ty)),
+        );
+        let msg = NString::from("pointer arithmetic failure: the offset in bytes overflows isize");
+        self.claim(Check::Bound, msg, overflow);
+        self.allocation_bound_check(pt, count);
+    }
+
+    /// The offset of `pt` moved by `count` must be in `[0, len]`, where
+    /// `len` is the length of the object space pointed to
+    fn allocation_bound_check(&mut self, pt: Expr, count: Expr) {
+        let mut objects = ObjectSet::new();
+        self.top().cur_state.get_value_set(pt.clone(), &mut objects);
+        for (object, _) in objects {
+            if object.is_null_object() || object.is_unknown() {
+                continue;
+            }
+            let root_object = object.extract_root_object();
+            let ty = root_object.ty();
+            let len = if ty.is_infinite_array() {
+                // The buffer of a vec is bounded by its capacity
+                let ident = root_object.extract_inner_expr().extract_symbol().ident();
+                match self.exec_state.vec_caps.get(&ident) {
+                    Some(cap) => cap.clone(),
+                    None if self.exec_state.is_sized_object(&root_object) => {
+                        self.ctx.object_size(root_object.clone())
+                    }
+                    None => continue,
+                }
+            } else if ty.is_slice() {
+                continue;
+            } else {
+                self.ctx.constant_usize(ty.num_fields())
+            };
+            let offset = self.ctx.pointer_offset(pt.clone());
+            let len = self.ctx.cast(len, self.ctx.mk_type(offset.ty()));
+            let count = self.ctx.cast(count.clone(), self.ctx.mk_type(offset.ty()));
+            let new_offset = self.ctx.add(offset.clone(), count);
+            let zero = self.ctx.constant_integer(BigInt::ZERO, offset.ty());
+            let out_of_bound =
+                self.ctx.or(self.ctx.lt(new_offset.clone(), zero), self.ctx.gt(new_offset, len));
+            let address =
+                self.ctx.address_of(root_object.clone(), root_object.extract_address_type());
+            let error = self.ctx.and(self.ctx.same_object(pt.clone(), address), out_of_bound);
+            let msg = NString::from(format!(
+                "pointer arithmetic failure: the pointer is out of bounds of {root_object:?}"
+            ));
+            self.claim(Check::Bound, msg, error);
+        }
+    }
+
     fn symex_ptr_is_null(&mut self, dest: Expr, args: Vec<Expr>) {
         let lhs = dest.clone();
 
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::expr::expr::*;
+use crate::expr::guard::Guard;
+use crate::expr::ty::*;
+use crate::symbol::nstring::*;
+use crate::symex::place_state::PlaceState;
+use crate::symex::projection::Mode;
+
+/// This mod defines symbolic execution of api in std::rc and
+/// std::sync::Arc. An `Rc` is a box to a shared object, which has a
+/// shadow strong count. Cloning an `Rc` increases the count and dropping
+/// it decreases the count. The object is freed when the count drops to 0,
+/// thus the object of an `Rc` that is never dropped is leaked. `Weak`
+/// and reference cycles are not supported.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_rc_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        // `Arc` is modeled the same as `Rc`
+        let name = NString::from(fndef.0.trimmed_name().replace("Arc", "Rc"));
+        if name == "Rc::<T>::new" {
+            self.symex_rc_new(dest, args);
+        } else if name == "<Rc<T, A> as Clone>::clone" {
+            self.symex_rc_clone(dest, args);
+        } else if name == "<Rc<T, A> as Deref>::deref" {
+            self.symex_rc_deref(dest, args);
+        } else if name == "Rc::<T, A>::strong_count" {
+            self.symex_rc_strong_count(dest, args);
+        } else {
+            unsupported!("Not support {name:?}");
+        }
+    }
+
+    /// The strong counts of the objects the rc may point to. Each count
+    /// is guarded by that the rc points to its object.
+    pub(in super::super) fn strong_counts(
+        &mut self,
+        rc: Expr,
+        guard: Guard,
+    ) -> Vec<(Expr, Expr, Guard)> {
+        let pt = self.ctx.inner_pointer(rc);
+        let mut objects = HashSet::new();
+        self.top().cur_state.get_value_set(pt.clone(), &mut objects);
+        let n = objects.len();
+        let mut counts = Vec::new();
+        for (object, _) in objects {
+            if object.is_unknown() || object.is_null_object() {
+                continue;
+            }
+            let inner = object.extract_inner_expr();
+            if !inner.is_symbol() {
+                continue;
+            }
+            let Some(count) = self.exec_state.rc_counts.get(&inner.extract_symbol().ident()) else {
+                continue;
+            };
+            let mut count_guard = guard.clone();
+            if n > 1 {
+                let address_of = self.ctx.address_of(object.clone(), object.extract_address_type());
+                let mut same_object = self
+                    .ctx
+                    .eq(self.ctx.pointer_base(pt.clone()), self.ctx.pointer_base(address_of));
+                self.rename(&mut same_object);
+                count_guard.add(same_object);
+            }
+            counts.push((object, count.clone(), count_guard));
+        }
+        counts
+    }
+
+    fn rc_place(&mut self, pt: Expr) -> Expr {
+        let ty = pt.ty().pointee_ty();
+        self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty)
+    }
+
+    fn symex_rc_new(&mut self, dest: Expr, args: Vec<Expr>) {
+        let ty = dest.ty().pointee_ty();
+        let object = self.exec_state.new_object(ty);
+        self.assign(object.clone(), args[0].clone(), self.ctx._true().into());
+
+        // Construct the rc from a box pointer
+        let inner_pt = self.ctx.address_of(object.clone(), object.extract_address_type());
+        let rc = self.ctx.cast(self.ctx._box(inner_pt), self.ctx.mk_type(dest.ty()));
+        self.assign(dest, rc, self.ctx._true().into());
+
+        self.track_new_object(object.clone());
+
+        // The strong count starts from 1
+        let ident = object.extract_inner_expr().extract_symbol().ident();
+        let name = NString::from("rc_count_") + self.exec_state.rc_counts.len().to_string();
+        let count = self.ctx.object(self.exec_state.l0_symbol(name, Type::usize_type()));
+        self.exec_state.rc_counts.insert(ident, count.clone());
+        self.assign(count, self.ctx.constant_usize(1), self.ctx._true().into());
+
+        // The object is not owned by any variable. It is freed only
+        // if the last rc is dropped.
+        self.exec_state.update_place_state(object, PlaceState::Alive);
+    }
+
+    fn symex_rc_clone(&mut self, dest: Expr, args: Vec<Expr>) {
+        let rc = self.rc_place(args[0].clone());
+        for (_, count, guard) in self.strong_counts(rc.clone(), self.ctx._true().into()) {
+            let inc = self.ctx.add(count.clone(), self.ctx.constant_usize(1));
+            self.assign(count, inc, guard);
+        }
+        self.assign(dest, rc, self.ctx._true().into());
+    }
+
+    fn symex_rc_deref(&mut self, dest: Expr, args: Vec<Expr>) {
+        let rc = self.rc_place(args[0].clone());
+        let pt = self.ctx.cast(self.ctx.inner_pointer(rc), self.ctx.mk_type(dest.ty()));
+        self.assign(dest, pt, self.ctx._true().into());
+    }
+
+    fn symex_rc_strong_count(&mut self, dest: Expr, args: Vec<Expr>) {
+        let rc = self.rc_place(args[0].clone());
+        let mut strong_count = self.make_nondet(dest.ty());
+        for (_, count, guard) in self.strong_counts(rc, self.ctx._true().into()) {
+            strong_count = self.ctx.ite(guard.to_expr(), count, strong_count);
+        }
+        self.assign(dest, strong_count, self.ctx._true().into());
+    }
+}
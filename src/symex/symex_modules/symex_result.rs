@@ -0,0 +1,65 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
+use crate::expr::expr::*;
+use crate::expr::ty::FunctionDef;
+use crate::program::program::bigint_to_usize;
+use crate::symbol::nstring::*;
+
+/// This mod defines symbolic execution of api in std::result.
+/// `Result` is an enum whose variants are `Ok` and `Err`. A result
+/// carrying a layout is resolved in symex, since layouts are never
+/// encoded.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_result_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        if name == "Result::<T, E>::unwrap" {
+            self.symex_result_unwrap(dest, args);
+        } else {
+            unsupported!("Not support for {name:?}");
+        }
+    }
+
+    fn symex_result_unwrap(&mut self, dest: Expr, args: Vec<Expr>) {
+        let mut value = args[0].clone();
+        self.replace_predicates(&mut value);
+        let err = self.option_variant(value.ty(), "Err");
+        let msg = NString::from("unwrap failure: called `Result::unwrap()` on an `Err` value");
+        if value.ty().contains_layout() {
+            // A result of layout is either `Ok`, `Err` or an `ite` of them
+            self.rename(&mut value);
+            let err_idx = bigint_to_usize(&err.extract_integer());
+            let mut layout = None;
+            for (result, guard) in self.split_ite(&value, self.ctx._true().into()) {
+                if !result.is_enum() {
+                    unsupported!("Not support unwrapping {value:?}");
+                }
+                if result.extract_variant_idx() == err_idx {
+                    self.claim(Check::Assertion, msg.clone(), guard.to_expr());
+                    continue;
+                }
+                if layout.is_some() {
+                    unsupported!("Not support unwrapping {value:?}");
+                }
+                let data = result.sub_exprs().unwrap()[1].clone();
+                layout = Some(data.extract_fields()[0].clone());
+            }
+            match layout {
+                Some(layout) => self.assign(dest, layout, self.ctx._true().into()),
+                // The path ends since unwrapping `Err` panics
+                None => self.symex_assume(self.ctx._false()),
+            }
+            return;
+        }
+
+        self.claim(Check::Assertion, msg, self.ctx.match_variant(value.clone(), err));
+
+        let ok = self.option_variant(value.ty(), "Ok");
+        let data = self.ctx.object(self.ctx.as_variant(value, ok));
+        let inner = self.ctx.index(data, self.ctx.constant_isize(0), dest.ty());
+        self.assign(dest, inner, self.ctx._true().into());
+    }
+}
@@ -0,0 +1,108 @@
+use stable_mir::CrateDef;
+
+use super::super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::expr::expr::*;
+use crate::expr::ty::FunctionDef;
+use crate::symbol::nstring::*;
+use crate::symex::projection::Mode;
+
+/// This mod defines symbolic execution of api in std::slice.
+/// `from_raw_parts` builds a slice pointer from a raw pointer to an
+/// element of an array. Its metadata is the given length, and indexing
+/// the slice is checked against it. `as_ptr` does the opposite, and the
+/// pointer is checked against the array.
+
+impl<'cfg> Symex<'cfg> {
+    pub fn symex_slice_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
+        let name = NString::from(fndef.0.trimmed_name());
+        if name == "from_raw_parts" || name == "from_raw_parts_mut" {
+            self.symex_slice_from_raw_parts(dest, args);
+        } else if name == "std::slice::<impl [T]>::as_ptr"
+            || name == "std::slice::<impl [T]>::as_mut_ptr"
+        {
+            self.symex_slice_as_ptr(dest, args);
+        } else {
+            unsupported!("Not support for {name:?}");
+        }
+    }
+
+    /// The slice of `len` elements starting from the element pointed to.
+    /// A slice exceeding the array is UB even if it is never accessed.
+    fn symex_slice_from_raw_parts(&mut self, dest: Expr, args: Vec<Expr>) {
+        let pt = args[0].clone();
+        let mut len = args[1].clone();
+        if len.is_object() {
+            len = len.extract_inner_expr();
+        }
+        self.replace_predicates(&mut len);
+        self.rename(&mut len);
+        len.simplify();
+
+        let ty = pt.ty().pointee_ty();
+        let place = self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty);
+        let mut slice_ptr = None;
+        for (elem, guard) in self.split_ite(&place, self.ctx._true().into()) {
+            let root = elem.extract_root_object().extract_inner_expr();
+            if root.extract_symbol().ident() == NString::INVALID_OBJECT {
+                continue;
+            }
+            if !elem.is_index() || !elem.extract_object().ty().is_array() {
+                unsupported!("Not support a slice from {elem:?}");
+            }
+            let array = elem.extract_object();
+            let start = elem.extract_index();
+            self.region_bound_check(
+                "from_raw_parts",
+                array.clone(),
+                start.clone(),
+                len.clone(),
+                guard.clone(),
+            );
+            let slice = self.ctx.slice(array, start, len.clone());
+            let address_of = self.ctx.address_of(self.ctx.object(slice), dest.ty());
+            slice_ptr = Some(match slice_ptr {
+                Some(p) => self.ctx.ite(guard.to_expr(), address_of, p),
+                None => address_of,
+            });
+        }
+
+        if let Some(rhs) = slice_ptr {
+            self.assign(dest, rhs, self.ctx._true().into());
+        }
+    }
+
+    /// The pointer to the first element of the slice
+    fn symex_slice_as_ptr(&mut self, dest: Expr, args: Vec<Expr>) {
+        let pt = args[0].clone();
+        let ty = pt.ty().pointee_ty();
+        let place = self.make_deref(pt, Mode::Read, self.ctx._true().into(), ty);
+        let mut elem_ptr = None;
+        for (slice, guard) in self.split_ite(&place, self.ctx._true().into()) {
+            let root = slice.extract_root_object().extract_inner_expr();
+            if root.extract_symbol().ident() == NString::INVALID_OBJECT {
+                continue;
+            }
+            let inner = if slice.is_object() { slice.extract_inner_expr() } else { slice.clone() };
+            let (array, start) = if inner.is_slice() {
+                (inner.extract_object(), inner.extract_slice_start())
+            } else if slice.ty().is_array() {
+                (slice.clone(), self.ctx.constant_usize(0))
+            } else {
+                unsupported!("Not support a pointer from {slice:?}");
+            };
+            let array = if array.is_object() { array } else { self.ctx.object(array) };
+            let elem_ty = array.ty().elem_type();
+            let elem = self.ctx.object(self.ctx.index(array, start, elem_ty));
+            let address_of = self.ctx.address_of(elem, dest.ty());
+            elem_ptr = Some(match elem_ptr {
+                Some(p) => self.ctx.ite(guard.to_expr(), address_of, p),
+                None => address_of,
+            });
+        }
+
+        if let Some(rhs) = elem_ptr {
+            self.assign(dest, rhs, self.ctx._true().into());
+        }
+    }
+}
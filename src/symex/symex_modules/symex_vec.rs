@@ -1,48 +1,71 @@
 use stable_mir::CrateDef;
 
 use super::super::symex::*;
+use crate::bmc::error::unsupported;
 use crate::expr::expr::*;
 use crate::expr::guard::Guard;
 use crate::expr::ty::*;
 use crate::symbol::nstring::NString;
-use crate::symbol::symbol::Symbol;
 use crate::symex::place_state::PlaceState;
 use crate::symex::projection::Mode;
 
 /// This mod defines symbolic execution of api in std::vec
 /// In our memory model, `vec` is a special pointer that owns
-/// an infinite array.
+/// an infinite array. Pushing to a full vec grows the capacity to
+/// a nondet value, and the buffer is never moved. The buffer
+/// has a shadow capacity, so raw pointers from `as_ptr` are checked
+/// against it.
 
 impl<'cfg> Symex<'cfg> {
     pub fn symex_vec_api(&mut self, fndef: &FunctionDef, args: Vec<Expr>, dest: Expr) {
         let name = NString::from(fndef.0.trimmed_name());
         if name == "Vec::<T>::new" {
-            self.symex_vec_new(dest);
+            self.symex_vec_new(dest, self.ctx.constant_usize(0), false);
+        } else if name == "Vec::<T>::with_capacity" {
+            let mut cap = args[0].clone();
+            self.replace_predicates(&mut cap);
+            self.symex_vec_new(dest, cap, true);
+        } else if name == "Vec::<T, A>::len" {
+            self.symex_vec_len(dest, args);
+        } else if name == "Vec::<T, A>::capacity" {
+            self.symex_vec_capacity(dest, args);
+        } else if name == "Vec::<T, A>::is_empty" {
+            self.symex_vec_is_empty(dest, args);
         } else if name == "Vec::<T, A>::push" {
             self.symex_vec_push(args);
         } else if name == "Vec::<T, A>::pop" {
             self.symex_vec_pop(dest, args);
+        } else if name == "Vec::<T, A>::as_ptr" || name == "Vec::<T, A>::as_mut_ptr" {
+            self.symex_vec_as_ptr(dest, args);
         } else {
-            panic!("Not support for {name:?}");
+            unsupported!("Not support for {name:?}");
         }
     }
 
-    fn symex_vec_new(&mut self, dest: Expr) {
+    /// The buffer of `with_capacity` has the size of the capacity, which
+    /// may be symbolic. Growing does not resize it in SMT.
+    pub(super) fn symex_vec_new(&mut self, dest: Expr, cap: Expr, sized: bool) {
         let lhs = dest.clone();
         let object = self.exec_state.new_object(lhs.ty().pointee_ty());
+        if sized {
+            self.track_object_size(object.clone(), cap.clone());
+        }
 
         // Construct vec pointer
         let inner_pt = self.ctx.address_of(object.clone(), object.extract_address_type());
         let len = self.ctx.constant_usize(0);
-        let ident = object.extract_inner_expr().extract_symbol().ident();
-        let cap_sym = Symbol::from(ident + "_size");
-        let cap = self.ctx.mk_symbol(cap_sym, Type::usize_type());
-        let _vec = self.ctx._vec(inner_pt, len, cap, dest.ty());
+        let _vec = self.ctx._vec(inner_pt, len, cap.clone(), dest.ty());
         self.assign(lhs, _vec, self.ctx._true().into());
 
         // Track new object
         self.track_new_object(object.clone());
 
+        let ident = object.extract_inner_expr().extract_symbol().ident();
+        let name = NString::from("vec_cap_") + self.exec_state.vec_caps.len().to_string();
+        let vec_cap = self.ctx.object(self.exec_state.l0_symbol(name, Type::usize_type()));
+        self.exec_state.vec_caps.insert(ident, vec_cap.clone());
+        self.assign(vec_cap, cap, self.ctx._true().into());
+
         // The newly object is owned by the box pointer
         let place_state = PlaceState::Own;
         self.exec_state.update_place_state(object, place_state);
@@ -63,11 +86,12 @@ impl<'cfg> Symex<'cfg> {
         // Update the inner array
         let inner_array =
             self.make_deref(inner_pt.clone(), Mode::Read, guard.clone(), _vec.ty().pointee_ty());
-        let array = self.ctx.object(inner_array);
+        let array = self.ctx.object(inner_array.clone());
         let elem_ty = array.ty().elem_type();
-        let index = self.ctx.index(array, old_len, elem_ty);
+        let index = self.ctx.index(array, old_len.clone(), elem_ty);
         self.assign(index, value, guard.clone());
-        // TODO: handle cap
+        let cap = self.grow_cap(old_len, cap);
+        self.update_vec_cap(&inner_array, cap.clone());
 
         let lhs = _vec;
         let rhs = self.ctx._vec(inner_pt, len, cap, lhs.ty());
@@ -93,4 +117,71 @@ impl<'cfg> Symex<'cfg> {
 
         // TODO: return the popped one
     }
+
+    /// The capacity after pushing to a vec of `len` elements. The growth
+    /// strategy of `RawVec` is not specified, so a full vec grows to a
+    /// nondet capacity that holds the new element.
+    fn grow_cap(&mut self, len: Expr, cap: Expr) -> Expr {
+        let new_cap = self.make_nondet(Type::usize_type());
+        let is_full = self.ctx.eq(len.clone(), cap.clone());
+        let mut enough = self.ctx.implies(is_full.clone(), self.ctx.gt(new_cap.clone(), len));
+        self.replace_predicates(&mut enough);
+        self.rename(&mut enough);
+        // The path condition is renamed
+        let path = self.exec_state.cur_state().guard.to_expr();
+        let mut enough = self.ctx.implies(path, enough);
+        enough.simplify();
+        self.vc_system.borrow_mut().assume(enough, self.exec_state.span);
+        let mut cap = self.ctx.ite(is_full, new_cap, cap);
+        cap.simplify();
+        cap
+    }
+
+    /// Set the shadow capacity of the buffers that `_vec` may own
+    fn update_vec_cap(&mut self, buffer: &Expr, cap: Expr) {
+        for (array, guard) in self.split_ite(buffer, self.ctx._true().into()) {
+            let root = array.extract_root_object().extract_inner_expr();
+            if !root.is_symbol() {
+                continue;
+            }
+            let Some(vec_cap) = self.exec_state.vec_caps.get(&root.extract_symbol().ident()) else {
+                continue;
+            };
+            self.assign(vec_cap.clone(), cap.clone(), guard);
+        }
+    }
+
+    /// The pointer to the first element of the buffer, i.e., the base of
+    /// the buffer with offset 0
+    fn symex_vec_as_ptr(&mut self, dest: Expr, args: Vec<Expr>) {
+        let guard = Guard::from(self.ctx._true());
+        let _vec =
+            self.make_deref(args[0].clone(), Mode::Read, guard.clone(), args[0].ty().pointee_ty());
+        let rhs = self.ctx.cast(self.ctx.inner_pointer(_vec), self.ctx.mk_type(dest.ty()));
+        self.assign(dest, rhs, guard);
+    }
+
+    fn symex_vec_len(&mut self, dest: Expr, args: Vec<Expr>) {
+        let guard = Guard::from(self.ctx._true());
+        let _vec =
+            self.make_deref(args[0].clone(), Mode::Read, guard.clone(), args[0].ty().pointee_ty());
+        let rhs = self.ctx.vec_len(_vec);
+        self.assign(dest, rhs, guard);
+    }
+
+    fn symex_vec_capacity(&mut self, dest: Expr, args: Vec<Expr>) {
+        let guard = Guard::from(self.ctx._true());
+        let _vec =
+            self.make_deref(args[0].clone(), Mode::Read, guard.clone(), args[0].ty().pointee_ty());
+        let rhs = self.ctx.vec_cap(_vec);
+        self.assign(dest, rhs, guard);
+    }
+
+    fn symex_vec_is_empty(&mut self, dest: Expr, args: Vec<Expr>) {
+        let guard = Guard::from(self.ctx._true());
+        let _vec =
+            self.make_deref(args[0].clone(), Mode::Read, guard.clone(), args[0].ty().pointee_ty());
+        let rhs = self.ctx.eq(self.ctx.vec_len(_vec), self.ctx.constant_usize(0));
+        self.assign(dest, rhs, guard);
+    }
 }
@@ -1,6 +1,10 @@
 use num_bigint::BigInt;
+use stable_mir::mir::*;
 
 use super::symex::*;
+use crate::config::cli::Check;
+use crate::expr::ty::Type;
+use crate::symbol::nstring::NString;
 use crate::{expr::expr::*, symbol::symbol::Level};
 
 impl<'cfg> Symex<'cfg> {
@@ -45,4 +49,62 @@ impl<'cfg> Symex<'cfg> {
             return;
         }
     }
+
+    /// Create the `moved` flags of the non-`Copy` locals in current frame.
+    /// A flag is set when the local is moved out, and cleared when the
+    /// local is assigned again.
+    pub(super) fn track_moved_locals(&mut self) {
+        if !self.config.cli.enable_check(Check::UseAfterMove) {
+            return;
+        }
+        for local in 0..self.top().function.locals().len() {
+            if self.top().function.local_type(local).is_copy() {
+                continue;
+            }
+            let ident = self.top().local_ident(local);
+            let name =
+                NString::from("moved_flags_") + self.exec_state.moved_flags.len().to_string();
+            let flag = self.ctx.object(self.exec_state.l0_symbol(name, Type::bool_type()));
+            self.exec_state.moved_flags.insert(ident, flag.clone());
+            self.assign(flag, self.ctx._false(), self.ctx._true().into());
+        }
+    }
+
+    fn moved_flag(&self, local: Local) -> Option<Expr> {
+        self.exec_state.moved_flags.get(&self.top().local_ident(local)).cloned()
+    }
+
+    /// Set the `moved` flags of the locals moved out by `operands`
+    pub(super) fn mark_moved(&mut self, operands: &[Operand]) {
+        for operand in operands {
+            let Operand::Move(p) = operand else { continue };
+            if !p.projection.is_empty() {
+                continue;
+            }
+            if let Some(flag) = self.moved_flag(p.local) {
+                self.assign(flag, self.ctx._true(), self.ctx._true().into());
+            }
+        }
+    }
+
+    /// Clear the `moved` flag if the whole local is assigned
+    pub(super) fn mark_assigned(&mut self, place: &Place) {
+        if !place.projection.is_empty() {
+            return;
+        }
+        if let Some(flag) = self.moved_flag(place.local) {
+            self.assign(flag, self.ctx._false(), self.ctx._true().into());
+        }
+    }
+
+    /// Reading a place of a moved-out local is an error. It never fails on
+    /// MIR accepted by the borrow checker.
+    pub(super) fn use_after_move_check(&self, place: &Place) {
+        let Some(flag) = self.moved_flag(place.local) else { return };
+        let msg = format!(
+            "use-after-move failure: {:?} is used after being moved",
+            self.top().local_ident(place.local)
+        );
+        self.claim(Check::UseAfterMove, msg.into(), flag);
+    }
 }
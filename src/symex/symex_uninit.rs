@@ -0,0 +1,174 @@
+use super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
+use crate::expr::expr::*;
+use crate::expr::guard::Guard;
+use crate::expr::ty::*;
+use crate::program::program::bigint_to_usize;
+use crate::symbol::nstring::NString;
+
+/// This mod defines the uninitialized-read check. An object returned by
+/// `alloc` has a shadow array of `written` flags in field-level, i.e.,
+/// indexed by `compute_offset`. A flag is set when the slot is assigned,
+/// and reading a slot whose flag is not set is reported.
+
+impl<'cfg> Symex<'cfg> {
+    /// Create the `written` flags of an alloced object. All slots are
+    /// written if `initialized`, e.g., the object from `alloc_zeroed`.
+    pub(super) fn track_uninit_object(&mut self, object: Expr, initialized: bool) {
+        assert!(object.is_object());
+        if object.ty().is_zst() || !self.config.cli.enable_check(Check::UninitializedRead) {
+            return;
+        }
+        let ident = object.extract_inner_expr().extract_symbol().ident();
+        let name =
+            NString::from("written_flags_") + self.exec_state.written_flags.len().to_string();
+        let ty = Type::infinite_array_type(Type::bool_type());
+        let written = self.ctx.object(self.exec_state.l0_symbol(name, ty));
+        self.exec_state.written_flags.insert(ident, written.clone());
+
+        let flags = self.ctx.constant_array(self.ctx.constant_bool(initialized), None);
+        self.assign(written, flags, self.ctx._true().into());
+    }
+
+    /// Set the `written` flags of the slots assigned by `lhs`
+    pub(super) fn mark_written(&mut self, lhs: &Expr, guard: Guard) {
+        if self.exec_state.written_flags.is_empty() {
+            return;
+        }
+
+        if lhs.is_ite() {
+            for (value, guard) in self.split_ite(lhs, guard) {
+                self.mark_written(&value, guard);
+            }
+            return;
+        }
+
+        let Some((written, offset, n)) = self.written_slots(lhs) else { return };
+        match n {
+            Some(n) => {
+                for j in 0..n {
+                    let i = self.ctx.add(offset.clone(), self.ctx.constant_isize(j as isize));
+                    let flag = self.ctx.index(written.clone(), i, Type::bool_type());
+                    self.assign(flag, self.ctx._true(), guard.clone());
+                }
+            }
+            None => {
+                let flags = self.ctx.constant_array(self.ctx._true(), None);
+                self.assign(written, flags, guard);
+            }
+        }
+    }
+
+    /// Set the `written` flags of `count` elements from the array element
+    /// `elem`. For a symbolic `count`, each slot of the array is written
+    /// only if it is in the region.
+    pub(super) fn mark_region_written(&mut self, elem: &Expr, count: &Expr, guard: Guard) {
+        if self.exec_state.written_flags.is_empty() {
+            return;
+        }
+
+        let Some((written, offset, Some(k))) = self.written_slots(elem) else { return };
+        let n = if count.is_constant() {
+            bigint_to_usize(&count.extract_constant().to_integer()) * k
+        } else {
+            match elem.extract_object().ty().array_size() {
+                Some(len) => len as usize * k,
+                None => unsupported!("Not support writing {count:?} elements from {elem:?}"),
+            }
+        };
+        let slots = self.ctx.mul(
+            self.ctx.cast(count.clone(), self.ctx.mk_type(Type::isize_type())),
+            self.ctx.constant_isize(k as isize),
+        );
+        for j in 0..n {
+            let j = self.ctx.constant_isize(j as isize);
+            let i = self.ctx.add(offset.clone(), j.clone());
+            let flag = self.ctx.index(written.clone(), i, Type::bool_type());
+            let mut in_region = guard.clone();
+            if !count.is_constant() {
+                in_region.add(self.ctx.lt(j, slots.clone()));
+            }
+            self.assign(flag, self.ctx._true(), in_region);
+        }
+    }
+
+    /// Reading `expr` is an error if any slot of it is not written
+    pub(super) fn uninit_read_check(&mut self, expr: &Expr, guard: Guard) {
+        if self.exec_state.written_flags.is_empty() {
+            return;
+        }
+
+        if expr.is_ite() {
+            for (value, guard) in self.split_ite(expr, guard) {
+                self.uninit_read_check(&value, guard);
+            }
+            return;
+        }
+
+        // Reading the whole infinite array is not checked
+        let Some((written, offset, Some(n))) = self.written_slots(expr) else { return };
+        let mut unwritten = None;
+        for j in 0..n {
+            let i = self.ctx.add(offset.clone(), self.ctx.constant_isize(j as isize));
+            let flag = self.ctx.index(written.clone(), i, Type::bool_type());
+            let not_written = self.ctx.not(flag);
+            unwritten = Some(match unwritten {
+                Some(e) => self.ctx.or(e, not_written),
+                None => not_written,
+            });
+        }
+        let Some(unwritten) = unwritten else { return };
+
+        let mut error = guard;
+        error.add(unwritten);
+        let msg = format!(
+            "uninitialized-read failure: {:?} is read before written",
+            expr.extract_root_object()
+        );
+        self.claim(Check::UninitializedRead, msg.into(), error.to_expr());
+    }
+
+    /// The `written` flags, the offset and the number of slots of the
+    /// place in an alloced object. `None` for the number if the place is
+    /// an infinite array.
+    fn written_slots(&self, place: &Expr) -> Option<(Expr, Expr, Option<usize>)> {
+        let root = Symex::place_root(place)?;
+        let written = self.exec_state.written_flags.get(&root.extract_symbol().ident())?;
+        // The variants of an enum are not in field-level. Take the whole enum.
+        let slot = Symex::variant_free_place(place);
+        let n = if slot.ty().is_infinite_array() { None } else { Some(slot.ty().num_fields()) };
+        Some((written.clone(), slot.compute_offset(), n))
+    }
+
+    fn place_root(place: &Expr) -> Option<Expr> {
+        if place.is_symbol() {
+            return Some(place.clone());
+        }
+        if place.is_object() {
+            return Symex::place_root(&place.extract_inner_expr());
+        }
+        if place.is_index() || place.is_slice() {
+            return Symex::place_root(&place.extract_object());
+        }
+        if place.is_as_variant() {
+            return Symex::place_root(&place.extract_enum());
+        }
+        None
+    }
+
+    fn variant_free_place(place: &Expr) -> Expr {
+        if place.is_as_variant() {
+            return Symex::variant_free_place(&place.extract_enum());
+        }
+        let inner = if place.is_object() {
+            place.extract_inner_expr()
+        } else if place.is_index() || place.is_slice() {
+            place.extract_object()
+        } else {
+            return place.clone();
+        };
+        let new_inner = Symex::variant_free_place(&inner);
+        if new_inner == inner { place.clone() } else { new_inner }
+    }
+}
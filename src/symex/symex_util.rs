@@ -3,20 +3,23 @@ use stable_mir::CrateDef;
 use stable_mir::mir::Operand;
 use stable_mir::mir::Place;
 use stable_mir::mir::alloc::GlobalAlloc;
-use stable_mir::target::*;
 use stable_mir::ty::*;
 
 use super::projection::*;
 use super::state::State;
 use super::symex::*;
+use crate::bmc::error::unsupported;
+use crate::config::cli::Check;
 use crate::expr::constant::*;
 use crate::expr::expr::*;
 use crate::expr::guard::*;
+use crate::expr::op::BinOp;
 use crate::expr::ty::*;
 use crate::program::function::*;
 use crate::program::program::*;
 use crate::symbol::nstring::*;
 use crate::symbol::symbol::*;
+use crate::vc::vc::span_location;
 
 impl<'cfg> Symex<'cfg> {
     pub(super) fn merge_states(&mut self, pc: Pc) -> bool {
@@ -24,9 +27,29 @@ impl<'cfg> Symex<'cfg> {
 
         // If pc is the entry of a loop and reaches loop bound, do not unwind the loop
         if self.top().function.is_loop_bb(pc) && self.top().reach_loop_bound(pc) {
+            if self.config.cli.unwinding_assertions {
+                // Not under the path condition of previous block
+                self.vc_system.borrow_mut().scope(self.ctx._true());
+                self.unwinding_assertion(pc, state_vec);
+            }
             return false;
         }
 
+        // Without merging, the states reaching `pc` are executed one by one.
+        // They are still merged at the entries of loops, where the
+        // unwindings are counted, and at the end of the function.
+        let is_join = self.top().function.is_loop_bb(pc) || pc == self.top().function.size();
+        let state_vec = match state_vec {
+            Some(mut states) if !self.config.cli.merge && !is_join && states.len() > 1 => {
+                let state = states.remove(0);
+                for rest in states {
+                    self.top_mut().add_state(pc, rest);
+                }
+                Some(vec![state])
+            }
+            _ => state_vec,
+        };
+
         // We have put all states that reach current pc in the
         // queue. Thus, we first construct an empty state.
         // That is, make `gurad` of current state be `false`.
@@ -45,7 +68,12 @@ impl<'cfg> Symex<'cfg> {
             }
         }
 
-        !self.top_mut().cur_state.guard.is_false()
+        if self.top().cur_state.guard.is_false() {
+            return false;
+        }
+        let guard = self.top().cur_state.guard.to_expr();
+        self.vc_system.borrow_mut().scope(guard);
+        true
     }
 
     fn phi_function(&mut self, nstate: &mut State) {
@@ -57,13 +85,24 @@ impl<'cfg> Symex<'cfg> {
 
         let mut nrenaming = nstate.renaming.as_ref().unwrap().borrow_mut();
 
+        // Without merging, a path continues with its own l1 symbols
+        if !self.config.cli.merge && self.top().cur_state.guard.is_false() {
+            self.exec_state.renaming.borrow_mut().restore_l1(&nrenaming);
+        }
+
         for var in nrenaming.variables() {
             let l1_ident = nrenaming.current_l1_symbol(var).l1_name();
+            // The paths may have different l1 symbols if they are not merged
+            let cur_l1_ident = if self.config.cli.merge {
+                l1_ident
+            } else {
+                self.exec_state.renaming.borrow_mut().current_l1_symbol(var).l1_name()
+            };
 
             let cur_l2_num = self.exec_state.renaming.borrow().l2_count(l1_ident);
             let n_l2_num = nrenaming.l2_count(l1_ident);
 
-            if cur_l2_num == n_l2_num || n_l2_num == 0 {
+            if cur_l1_ident == l1_ident && cur_l2_num == n_l2_num || n_l2_num == 0 {
                 continue;
             }
 
@@ -71,7 +110,9 @@ impl<'cfg> Symex<'cfg> {
             let mut new_rhs = self.exec_state.ns.lookup_symbol(var);
 
             // Get l1 number
-            nrenaming.l1_rename(&mut cur_rhs);
+            if self.config.cli.merge {
+                nrenaming.l1_rename(&mut cur_rhs);
+            }
             nrenaming.l1_rename(&mut new_rhs);
 
             // Current assignment
@@ -90,6 +131,24 @@ impl<'cfg> Symex<'cfg> {
         }
     }
 
+    /// The states reaching the loop entry after the loop bound
+    /// are dropped. The result is incomplete if any of them is reachable.
+    fn unwinding_assertion(&mut self, pc: Pc, state_vec: Option<Vec<State>>) {
+        let mut cond = state_vec
+            .unwrap_or_default()
+            .iter()
+            .fold(self.ctx._false(), |acc, state| self.ctx.or(acc, state.guard.to_expr()));
+        cond.simplify();
+        if cond.is_false() {
+            return;
+        }
+        let msg = NString::from(format!(
+            "unwinding assertion: loop bb{pc} in {:?} is not fully unwound",
+            self.top().function.name()
+        ));
+        self.vc_system.borrow_mut().assert(msg, cond, self.exec_state.span);
+    }
+
     /// Unwind loop if `pc` is the entry of a loop
     pub(super) fn unwind(&mut self, pc: Pc) {
         if self.top().function.is_loop_bb(pc) {
@@ -113,15 +172,19 @@ impl<'cfg> Symex<'cfg> {
     }
 
     pub(super) fn memory_leak_check(&self) {
-        for object in &self.exec_state.objects {
+        for (object, span) in &self.exec_state.objects {
             let mut l1_object = object.clone();
             self.exec_state.rename(&mut l1_object, Level::Level1);
             let object_state = self.exec_state.get_place_state(&l1_object);
-            if object_state.is_dead() || object_state.is_own() {
+            if object_state.is_dead() || object_state.is_own() || object.ty().is_zst() {
                 continue;
             }
 
-            let msg = NString::from(format!("memory leak: {object:?} is not dealloced"));
+            let site = match span {
+                Some(_) => format!(" allocated at {}", span_location(*span)),
+                None => String::new(),
+            };
+            let msg = NString::from(format!("memory leak: {object:?}{site} is not dealloced"));
             let is_leak = if object_state.is_unknown() {
                 let alloac_array = self.exec_state.ns.lookup_object(NString::ALLOC_SYM);
                 let address_of = self.ctx.address_of(object.clone(), object.extract_address_type());
@@ -130,7 +193,145 @@ impl<'cfg> Symex<'cfg> {
             } else {
                 self.ctx._true()
             };
-            self.claim(msg, is_leak.into());
+            self.claim(Check::Leak, msg, is_leak.into());
+        }
+    }
+
+    /// Integers are unbounded in SMT. Check the result is in the range of its type.
+    pub(super) fn overflow_check(&self, op: BinOp, expr: Expr) {
+        let ty = expr.ty();
+        let (min, max) = ty.integer_range();
+        let overflow = self.ctx.or(
+            self.ctx.lt(expr.clone(), self.ctx.constant_integer(min, ty)),
+            self.ctx.gt(expr, self.ctx.constant_integer(max, ty)),
+        );
+        let msg = NString::from(format!("arithmetic overflow: {op:?} overflows {:?}", ty.name()));
+        self.claim(Check::Overflow, msg, overflow);
+    }
+
+    /// The value mod 2^width in the range of its type
+    pub(super) fn wrap_integer(&self, value: Expr) -> Expr {
+        let ty = value.ty();
+        let (min, _) = ty.integer_range();
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let modulus = self.ctx.constant_integer(BigInt::from(1) << width, ty);
+        let min = self.ctx.constant_integer(min, ty);
+        // The remainder has the sign of the dividend
+        let rem = self.ctx.rem(self.ctx.sub(value, min.clone()), modulus.clone());
+        let zero = self.ctx.constant_integer(BigInt::ZERO, ty);
+        let is_neg = self.ctx.lt(rem.clone(), zero);
+        let bits = self.ctx.ite(is_neg, self.ctx.add(rem.clone(), modulus), rem);
+        let mut wrapped = self.ctx.add(bits, min);
+        wrapped.simplify();
+        wrapped
+    }
+
+    /// The shift amount must be less than the width of the shifted value
+    pub(super) fn shift_overflow_check(&self, ty: Type, amount: Expr) {
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let amount_ty = amount.ty();
+        let mut overflow =
+            self.ctx.ge(amount.clone(), self.ctx.constant_integer(BigInt::from(width), amount_ty));
+        if amount_ty.is_signed() {
+            let zero = self.ctx.constant_integer(BigInt::ZERO, amount_ty);
+            overflow = self.ctx.or(overflow, self.ctx.lt(amount, zero));
+        }
+        let msg = NString::from(format!(
+            "shift-overflow failure: the shift amount is not less than {width} bits of {:?}",
+            ty.name()
+        ));
+        self.claim(Check::Overflow, msg, overflow);
+    }
+
+    /// `value << amount` or `value >> amount`. The amount is masked by the
+    /// width, i.e., taken mod the width, as MIR does. The right shift of a
+    /// signed integer is arithmetic.
+    pub(super) fn make_shift(&self, value: Expr, amount: Expr, left: bool) -> Expr {
+        let ty = value.ty();
+        let width = ty.size_in_bytes().unwrap() * 8;
+        let amount_ty = amount.ty();
+        let n = self.ctx.constant_integer(BigInt::from(width), amount_ty);
+        let rem = self.ctx.rem(amount, n.clone());
+        let zero = self.ctx.constant_integer(BigInt::ZERO, amount_ty);
+        let is_neg = self.ctx.lt(rem.clone(), zero);
+        let mut masked = self.ctx.ite(is_neg, self.ctx.add(rem.clone(), n), rem);
+        masked.simplify();
+
+        let shift_by = |k: u64| {
+            let factor = self.ctx.constant_integer(BigInt::from(1) << k, ty);
+            if left {
+                return self.wrap_integer(self.ctx.mul(value.clone(), factor));
+            }
+            if !ty.is_signed() {
+                return self.ctx.div(value.clone(), factor);
+            }
+            // Round toward negative infinity
+            let zero = self.ctx.constant_integer(BigInt::ZERO, ty);
+            let is_neg = self.ctx.lt(value.clone(), zero);
+            let down = self.ctx.sub(
+                value.clone(),
+                self.ctx.sub(factor.clone(), self.ctx.constant_integer(BigInt::from(1), ty)),
+            );
+            self.ctx.ite(
+                is_neg,
+                self.ctx.div(down, factor.clone()),
+                self.ctx.div(value.clone(), factor),
+            )
+        };
+        if masked.is_constant() {
+            let mut shifted = shift_by(bigint_to_u64(&masked.extract_constant().to_integer()));
+            shifted.simplify();
+            return shifted;
+        }
+        let mut shifted = shift_by(0);
+        for k in 1..width {
+            let is_k =
+                self.ctx.eq(masked.clone(), self.ctx.constant_integer(BigInt::from(k), amount_ty));
+            shifted = self.ctx.ite(is_k, shift_by(k), shifted);
+        }
+        shifted.simplify();
+        shifted
+    }
+
+    /// A `char` is a Unicode scalar value, i.e., a code point in
+    /// `0..=0x10FFFF` except the surrogates `0xD800..=0xDFFF`
+    pub(super) fn is_valid_char(&self, code: Expr) -> Expr {
+        let ty = code.ty();
+        let int = |i: u32| self.ctx.constant_integer(BigInt::from(i), ty);
+        let low =
+            self.ctx.and(self.ctx.ge(code.clone(), int(0)), self.ctx.lt(code.clone(), int(0xD800)));
+        let high =
+            self.ctx.and(self.ctx.gt(code.clone(), int(0xDFFF)), self.ctx.le(code, int(0x10FFFF)));
+        self.ctx.or(low, high)
+    }
+
+    /// The `char` of the code point. Report if it is not valid.
+    pub(super) fn char_from_code(&self, code: Expr) -> Expr {
+        let invalid = self.ctx.not(self.is_valid_char(code.clone()));
+        let msg = NString::from("invalid-char failure: the code point is not a valid char");
+        self.claim(Check::InvalidChar, msg, invalid);
+        self.ctx.cast(code, self.ctx.mk_type(Type::char_type()))
+    }
+
+    /// Check the divisor is not zero. For signed integers, `MIN / -1`
+    /// and `MIN % -1` overflow.
+    pub(super) fn division_check(&self, op: BinOp, lhs: Expr, rhs: Expr) {
+        let ty = lhs.ty();
+        let name = if op == BinOp::Div { "division" } else { "remainder" };
+
+        let is_zero = self.ctx.eq(rhs.clone(), self.ctx.constant_integer(BigInt::ZERO, ty));
+        let msg = NString::from(format!("arithmetic failure: {name} by zero"));
+        self.claim(Check::DivByZero, msg, is_zero);
+
+        if ty.is_signed() {
+            let (min, _) = ty.integer_range();
+            let overflow = self.ctx.and(
+                self.ctx.eq(lhs, self.ctx.constant_integer(min, ty)),
+                self.ctx.eq(rhs, self.ctx.constant_integer(BigInt::from(-1), ty)),
+            );
+            let msg =
+                NString::from(format!("arithmetic overflow: {name} overflows {:?}", ty.name()));
+            self.claim(Check::Overflow, msg, overflow);
         }
     }
 
@@ -157,10 +358,17 @@ impl<'cfg> Symex<'cfg> {
                 }
             }
             ConstantKind::Unevaluated(uneval_const) => {
-                todo!("{uneval_const:?}")
+                // Associated constants `MIN` and `MAX` of integers
+                let name = uneval_const.def.name();
+                if ty.is_integer() && (name.ends_with("::MIN") || name.ends_with("::MAX")) {
+                    let (min, max) = ty.integer_range();
+                    let i = if name.ends_with("::MIN") { min } else { max };
+                    return self.ctx.constant_integer(i, ty);
+                }
+                unsupported!("{uneval_const:?}")
             }
             ConstantKind::ZeroSized => self.ctx.mk_type(ty),
-            _ => panic!("Not support {:?}", mirconst.kind()),
+            _ => unsupported!("Not support {:?}", mirconst.kind()),
         }
     }
 
@@ -174,7 +382,7 @@ impl<'cfg> Symex<'cfg> {
                 let object = self.exec_state.ns.lookup_object(ident);
                 self.ctx.address_of(object.clone(), ty)
             }
-            _ => panic!("Do not support global alloc {global_alloc:?}"),
+            _ => unsupported!("Do not support global alloc {global_alloc:?}"),
         }
     }
 
@@ -190,57 +398,58 @@ impl<'cfg> Symex<'cfg> {
             return self.ctx.null(ty);
         }
 
-        let fields = if ty.is_struct() { ty.struct_def().1 } else { vec![(NString::EMPTY, ty)] };
-        let mut value_vec = Vec::new();
-        let bytes = &allocation.bytes;
-        for i in 0..fields.len() {
-            let l = if MachineInfo::target().endian == Endian::Little {
-                bytes.len() - allocation.align as usize * (i + 1)
-            } else {
-                allocation.align as usize * i
-            };
-            let r = l + allocation.align as usize;
-            let mut raw_bytes = Vec::new();
-            for j in l..r {
-                if let Some(x) = bytes[j] {
-                    raw_bytes.push(x);
-                }
-            }
-            if fields[i].1.is_bool() {
-                assert!(raw_bytes.len() == 1);
-                value_vec.push(Constant::Bool(raw_bytes[0] == 1));
-                continue;
-            }
-            let value = read_target_integer(raw_bytes.as_slice());
-            value_vec.push(Constant::Integer(value));
+        // Uninitialized bytes are read as 0
+        let bytes = allocation.bytes.iter().map(|b| b.unwrap_or(0)).collect::<Vec<_>>();
+        match Symex::constant_from_bytes(&bytes, ty) {
+            Constant::Struct(fields, _) => self.ctx.constant_struct(fields, ty),
+            Constant::Bool(b) => self.ctx.constant_bool(b),
+            Constant::Integer(i) => self.ctx.constant_integer(i, ty),
+            _ => unsupported!("Not support construct {ty} from Allocation"),
         }
+    }
 
+    /// Read a constant of `ty` at the beginning of `bytes`. Fields of a
+    /// struct are placed by the layout of `ty`.
+    fn constant_from_bytes(bytes: &[u8], ty: Type) -> Constant {
         if ty.is_struct() {
-            let mut struct_fields = Vec::new();
-            for i in 0..fields.len() {
-                struct_fields.push((value_vec[i].clone(), fields[i].1.clone()));
-            }
-            self.ctx.constant_struct(struct_fields, ty)
-        } else {
-            assert!(value_vec.len() == 1);
-            if ty.is_bool() {
-                self.ctx.constant_bool(value_vec[0].to_bool())
-            } else if ty.is_integer() {
-                let i = value_vec[0].to_integer();
-                self.ctx.constant_integer(i, ty)
-            } else {
-                panic!("Not support construct {ty:?} from Allocation")
-            }
+            let fields = ty
+                .struct_def()
+                .1
+                .iter()
+                .zip(ty.field_byte_offsets())
+                .map(|((_, fty), o)| (Symex::constant_from_bytes(&bytes[o as usize..], *fty), *fty))
+                .collect::<Vec<_>>();
+            return Constant::Struct(fields, ty);
+        }
+
+        let size = ty.size_in_bytes().expect("Must be sized") as usize;
+        let raw_bytes = &bytes[..size];
+        if ty.is_bool() {
+            return Constant::Bool(raw_bytes[0] == 1);
+        }
+        assert!(ty.is_integer() || ty.is_char(), "Not support construct {ty:?} from Allocation");
+        let mut value = read_target_integer(raw_bytes);
+        if ty.is_unsigned() && value < BigInt::ZERO {
+            value += BigInt::from(1) << (8 * size);
         }
+        Constant::Integer(value)
     }
 
     /// Return `l1` expr
     pub(super) fn make_operand(&mut self, operand: &Operand) -> Expr {
         match operand {
-            Operand::Copy(p) => self.make_project(p),
+            Operand::Copy(p) => {
+                self.use_after_move_check(p);
+                let expr = self.make_project(p);
+                self.uninit_read_check(&expr, self.ctx._true().into());
+                expr
+            }
             Operand::Move(p) => {
+                self.use_after_move_check(p);
                 let expr = self.make_project(p);
-                self.ctx._move(expr)
+                self.uninit_read_check(&expr, self.ctx._true().into());
+                // Moving a `Copy` value leaves the source valid
+                if expr.ty().is_copy() { expr } else { self.ctx._move(expr) }
             }
             Operand::Constant(op) => self.make_mirconst(&op.const_),
         }
@@ -254,13 +463,34 @@ impl<'cfg> Symex<'cfg> {
                     let n = tyconst.eval_target_usize().expect("Not usize") as usize;
                     self.ctx.constant_integer(BigInt::from(n), ty)
                 } else {
-                    todo!()
+                    unsupported!("{:?}", tyconst.kind())
                 }
             }
-            _ => todo!("{:?}", tyconst.kind()),
+            _ => unsupported!("{:?}", tyconst.kind()),
         }
     }
 
+    /// The places in the branches of an `ite` place with their guards
+    pub(super) fn split_ite(&self, expr: &Expr, guard: Guard) -> Vec<(Expr, Guard)> {
+        if !expr.is_ite() {
+            return vec![(expr.clone(), guard)];
+        }
+        let sub_exprs = expr.sub_exprs().unwrap();
+        let mut true_cond = sub_exprs[0].clone();
+        self.rename(&mut true_cond);
+        let mut true_guard = guard.clone();
+        true_guard.add(true_cond);
+
+        let mut false_cond = self.ctx.not(sub_exprs[0].clone());
+        self.rename(&mut false_cond);
+        let mut false_guard = guard;
+        false_guard.add(false_cond);
+
+        let mut places = self.split_ite(&sub_exprs[1], true_guard);
+        places.extend(self.split_ite(&sub_exprs[2], false_guard));
+        places
+    }
+
     /// Interface for `l2` reaming.
     pub(super) fn rename(&self, expr: &mut Expr) {
         self.exec_state.rename(expr, Level::Level2);
@@ -300,8 +530,12 @@ impl<'cfg> Symex<'cfg> {
         }
     }
 
-    /// Generating assertion in form: `path /\ error`,
-    pub(super) fn claim(&self, msg: NString, mut error: Expr) {
+    /// Generating assertion in form: `path /\ error`. The assertion is
+    /// dropped if its class of property is not enabled.
+    pub(super) fn claim(&self, check: Check, msg: NString, mut error: Expr) {
+        if !self.config.cli.enable_check(check) {
+            return;
+        }
         self.replace_predicates(&mut error);
         self.rename(&mut error);
         error.simplify();
@@ -1,12 +1,13 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 
-use num_bigint::BigInt;
-
 use crate::expr::expr::Expr;
 use crate::symbol::nstring::NString;
 
-pub type Object = (Expr, Option<BigInt>);
+/// An object with the offset of the pointer in it. `None` means the pointer
+/// points to the object itself. A non-constant offset only marks that the
+/// offset is known by the pointer at runtime.
+pub type Object = (Expr, Option<Expr>);
 pub type ObjectSet = HashSet<Object>;
 
 #[derive(Default, Clone)]
@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::rc::Rc;
 use std::slice::{Iter, IterMut};
@@ -9,6 +9,30 @@ use stable_mir::ty::Span;
 use crate::expr::expr::*;
 use crate::symbol::nstring::NString;
 
+/// Render a span as `file:line:col` of its start
+pub fn span_location(span: Option<Span>) -> String {
+    let Some(span) = span else { return "unknown".to_string() };
+    let lines = span.get_lines();
+    format!("{}:{}:{}", span.get_filename(), lines.start_line, lines.start_col)
+}
+
+/// The source code at the start line of a span
+pub fn span_source_line(span: Option<Span>) -> Option<(usize, String)> {
+    let span = span?;
+    let line = span.get_lines().start_line;
+    let source = std::fs::read_to_string(span.get_filename()).ok()?;
+    Some((line, source.lines().nth(line.checked_sub(1)?)?.to_string()))
+}
+
+pub fn collect_symbols(expr: &Expr, symbols: &mut HashSet<Expr>) {
+    if expr.is_symbol() {
+        symbols.insert(expr.clone());
+    }
+    for sub_expr in expr.sub_exprs().unwrap_or_default() {
+        collect_symbols(&sub_expr, symbols);
+    }
+}
+
 #[derive(Clone)]
 pub enum VcKind {
     Assign(Expr, Expr),
@@ -50,6 +74,11 @@ impl Vc {
         matches!(self.kind, VcKind::Assume(..))
     }
 
+    pub fn location(&self) -> String {
+        assert!(self.span.is_some(), "Span must exist");
+        span_location(self.span)
+    }
+
     pub fn msg(&self) -> NString {
         if let VcKind::Assert(msg, _) = &self.kind {
             return *msg;
@@ -71,12 +100,33 @@ impl Debug for Vc {
     }
 }
 
+/// A branch taken in symbolic execution
+#[derive(Clone)]
+pub struct Branch {
+    pub guard: Expr,
+    /// The path condition after taking the branch
+    pub path: Expr,
+    pub span: Option<Span>,
+    /// Number of VCs generated before the branch
+    pub pos: usize,
+}
+
+/// The path condition of a block entered in symbolic execution. The VCs
+/// from `pos` are generated under it until the next scope.
+#[derive(Clone)]
+pub struct Scope {
+    pub guard: Expr,
+    pub pos: usize,
+}
+
 /// Verification Condition System. The output of symbolic execution.
 /// Used for encoding SMT formulas.
 #[derive(Default)]
 pub struct VCSystem {
     pub(super) vcs: Vec<Vc>,
     pub(super) asserts_map: HashMap<usize, usize>,
+    pub(super) branches: Vec<Branch>,
+    pub(super) scopes: Vec<Scope>,
 }
 
 impl VCSystem {
@@ -101,6 +151,50 @@ impl VCSystem {
         self.vcs.push(Vc::new(VcKind::Assume(cond), span));
     }
 
+    pub fn branch(&mut self, guard: Expr, path: Expr, span: Option<Span>) {
+        self.branches.push(Branch { guard, path, span, pos: self.vcs.len() });
+    }
+
+    /// Enter a new scope. A scope without VCs is replaced and a scope
+    /// with the same path condition is merged into the previous one.
+    pub fn scope(&mut self, guard: Expr) {
+        let pos = self.vcs.len();
+        if self.scopes.last().is_some_and(|s| s.pos == pos) {
+            self.scopes.pop();
+        }
+        if self.scopes.last().is_some_and(|s| s.guard == guard) {
+            return;
+        }
+        self.scopes.push(Scope { guard, pos });
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// Branches in order that may lead to the nth assertion
+    pub fn branches_to_nth_assertion(&self, n: usize) -> Vec<Branch> {
+        let m = *self.asserts_map.get(&n).unwrap();
+        self.branches.iter().filter(|b| b.pos <= m).cloned().collect()
+    }
+
+    /// Symbols in the VCs that are not sliced
+    pub fn unsliced_symbols(&self) -> HashSet<Expr> {
+        let mut symbols = HashSet::new();
+        for vc in self.vcs.iter().filter(|vc| !vc.is_sliced) {
+            match &vc.kind {
+                VcKind::Assign(lhs, rhs) => {
+                    collect_symbols(lhs, &mut symbols);
+                    collect_symbols(rhs, &mut symbols);
+                }
+                VcKind::Assert(_, cond) | VcKind::Assume(cond) => {
+                    collect_symbols(cond, &mut symbols)
+                }
+            }
+        }
+        symbols
+    }
+
     pub fn nth_assertion(&self, n: usize) -> Vc {
         assert!(n < self.asserts_map.len());
         self.vcs[*self.asserts_map.get(&n).unwrap()].clone()
@@ -139,13 +233,7 @@ impl VCSystem {
             if self.vcs[m].is_sliced {
                 continue;
             }
-            let span = self.vcs[m].span.expect("Span must exist");
-            println!(
-                "\nAssertion {i}: {}:{}:{}",
-                span.get_filename(),
-                span.get_lines().start_line,
-                span.get_lines().start_col
-            );
+            println!("\nAssertion {i}: {}", self.vcs[m].location());
             println!("-> Check: {:?}", self.vcs[m].msg());
             let mut n = 0;
             for j in 0..m {